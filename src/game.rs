@@ -2,6 +2,27 @@ use std::fmt::Display;
 
 use grid::Grid;
 
+mod player;
+mod game_state;
+pub(crate) mod encoding;
+mod replay;
+mod plan;
+mod spot_info;
+mod summary;
+pub(crate) mod light;
+pub(crate) mod bitboard;
+pub(crate) mod zobrist;
+pub(crate) mod rules;
+mod ai;
+pub(crate) use self::player::{Player, PlayerBoard, default_player_color};
+pub(crate) use self::game_state::{GameState, GameAction};
+pub(crate) use self::replay::{LogEntry, Replay};
+pub(crate) use self::plan::Plan;
+pub(crate) use self::spot_info::SpotInfo;
+pub(crate) use self::summary::GameSummary;
+pub(crate) use self::rules::{RuleSet, ShadeRules};
+pub(crate) use self::ai::{Difficulty, MAX_ACTIONS_PER_TURN, choose_action_at};
+
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Ord, PartialOrd, Hash)]
 pub struct Sun {
 	pub(crate) direction: SunDirection,
@@ -45,6 +66,11 @@ impl Default for Sun {
 /// The moon sort of sits in between two spots on a grid, and it shines diagonally in two direction.  
 /// 
 /// For this reason, the moon holds two coordinates, and sits between them.  
+/// The light from the moon spreads out, so the first coordinate is where the first of the two diagonals from moon_grid_starts_directions() starts,
+/// and the second coordinate is where the second one starts.  
+/// 
+/// The moon always sits on the edge of the board, so there are 4 * (grid_side_length - 1) places it can be.
+/// Going counter-clockwise from the top right corner, these are numbered by ring_index().  
 /// 
 /// It also has a handy function to calculate the next position.  
 #[allow(dead_code)]
@@ -72,143 +98,108 @@ impl Moon {
 	/// This function will fail if the side length provided is less than 2.  
 	/// 
 	pub fn new(grid_side_length: usize) -> Moon {
-		Moon {
-			direction: MoonDirection::South,
-			row1: 0,
-			row2: 0,
-			col1: grid_side_length - 2,
-			col2: grid_side_length - 1,
-			full_moon: false,
-			grid_side_length,
-		}//end struct construction.
+		return Moon::at_ring_index(grid_side_length, 0, false);
 	}//end new(grid_side_length)
 
-	/// # next(&self)
+	/// # ring_count(&self)
+	/// 
+	/// Returns the number of places the moon can be around the edge of the board.
+	pub fn ring_count(&self) -> usize {
+		return 4 * (self.grid_side_length - 1);
+	}//end ring_count(&self)
+
+	/// # at_ring_index(grid_side_length, index, full_moon)
+	/// 
+	/// Creates a moon at the given place around the edge of the board.  
+	/// 
+	/// Places are numbered counter-clockwise, starting in the top right corner.
+	/// The moon points south along the top edge, east along the left edge, north along the bottom edge, and west along the right edge.  
+	/// 
+	/// ## parameters
+	/// grid_side_length : the length of each side of the square grid  
+	/// index : which place the moon is at. This wraps around, so any number can be given.  
+	/// full_moon : whether this is a full moon or a half moon
+	pub fn at_ring_index(grid_side_length: usize, index: usize, full_moon: bool) -> Moon {
+		let last = grid_side_length - 1;
+		let index = index % (4 * last);
+		// how far along the current side of the board we are
+		let along = index % last;
+		let (direction, (row1, col1), (row2, col2)) = match index / last {
+			// top edge, moving left
+			0 => (MoonDirection::South, (0, last - along), (0, last - along - 1)),
+			// left edge, moving down
+			1 => (MoonDirection::East, (along, 0), (along + 1, 0)),
+			// bottom edge, moving right
+			2 => (MoonDirection::North, (last, along), (last, along + 1)),
+			// right edge, moving up
+			_ => (MoonDirection::West, (last - along, last), (last - along - 1, last)),
+		};//end matching which side of the board we're on
+		return Moon {
+			direction,
+			row1,
+			col1,
+			row2,
+			col2,
+			full_moon,
+			grid_side_length,
+		};//end struct construction
+	}//end at_ring_index(grid_side_length, index, full_moon)
+
+	/// # ring_index(&self)
+	/// 
+	/// Returns which place around the edge of the board the moon is at, as described in at_ring_index().
+	pub fn ring_index(&self) -> usize {
+		let last = self.grid_side_length - 1;
+		match self.direction {
+			MoonDirection::South => last - self.col1,
+			MoonDirection::East => last + self.row1,
+			MoonDirection::North => 2 * last + self.col1,
+			MoonDirection::West => 3 * last + (last - self.row1),
+		}//end matching direction
+	}//end ring_index(&self)
+
+	/// # advance(&self, steps)
 	/// 
-	/// This function calculates the next position of this moon object, based on the given grid side length.
+	/// This function calculates the next position of this moon object, the given number of places further counter-clockwise.
+	/// Each round the moon moves grid_side_length places, plus the moon.extra_steps from the rules, which is 2 in the official rules.
 	/// Please note that this function does not take into account the transition between full and half moon.  
 	/// 
 	/// 	: When the moon passes the sun, it flips between half and full moon.   
 	/// 	: Half moon light gives 1 lunar point, and full moon light gives 2 lunar points.  
-	pub fn next(&self) -> Moon {
-		// this counter will be used to help us move things.
-		let mut spaces_left_to_move = self.grid_side_length + 2;
-
-		// reference variables to build a new moon
-		let mut new_row1 = self.row1;
-		let mut new_col1 = self.col1;
-		let mut new_row2 = self.row2;
-		let mut new_col2 = self.col2;
-		let mut new_direction = self.direction;
-		
-		// loop will help us 
-		while spaces_left_to_move > 0 {
-			match self.direction {
-				MoonDirection::South => {
-					// how many space can we move counter-clockwise?
-					if spaces_left_to_move > new_col1 {
-						// space after column
-						let spaces_used: usize = (spaces_left_to_move - new_col1) + 1;
-						// set columns to 0 (left/west side now)
-						new_col1 = 0;
-						new_col2 = 0;
-						// update row to be first possible value
-						new_row1 = 1;
-						new_row2 = 0;
-						// update direction since we're flipping to a different side of the grid
-						new_direction = new_direction.next();
-						// update_spaces_left_to_move, since we've used part of it
-						spaces_left_to_move -= spaces_used;
-					}//end if we'll be changing direction
-					else {
-						// take spaces left to move out of column location
-						new_col1 -= spaces_left_to_move;
-						new_col2 -= spaces_left_to_move;
-						spaces_left_to_move = 0;
-					}//end else we will stay on the same direction
-				},
-				MoonDirection::East => {
-					// how many space can we move counter-clockwise?
-					if spaces_left_to_move + new_row1 >= self.grid_side_length  {
-						// space after column
-						let spaces_used: usize = self.grid_side_length - new_row1;
-						// set columns to 0 (left/west side now)
-						new_col1 = 1;
-						new_col2 = 0;
-						// update row to be first possible value
-						new_row1 = self.grid_side_length - 1;
-						new_row2 = self.grid_side_length - 1;
-						// update direction since we're flipping to a different side of the grid
-						new_direction = new_direction.next();
-						// update_spaces_left_to_move, since we've used part of it
-						spaces_left_to_move -= spaces_used;
-					}//end if we'll be changing direction
-					else {
-						// take spaces left to move out of column location
-						new_col1 -= spaces_left_to_move;
-						new_col2 -= spaces_left_to_move;
-						spaces_left_to_move = 0;
-					}//end else we will stay on the same direction
-				},
-				MoonDirection::North => {
-					// how many space can we move counter-clockwise?
-					if new_col1 + spaces_left_to_move + 2 >= self.grid_side_length {
-						// space after column
-						let spaces_used: usize = self.grid_side_length - new_col2;
-						// set columns to far right side
-						new_col1 = self.grid_side_length - 2;
-						new_col2 = self.grid_side_length - 2;
-						// update row to be first possible value
-						new_row1 = self.grid_side_length - 1;
-						new_row2 = self.grid_side_length - 2;
-						// update direction since we're flipping to a different side of the grid
-						new_direction = new_direction.next();
-						// update_spaces_left_to_move, since we've used part of it
-						spaces_left_to_move -= spaces_used;
-					}//end if we'll be changing direction
-					else {
-						// take spaces left to move out of column location
-						new_col1 += spaces_left_to_move;
-						new_col2 += spaces_left_to_move;
-						spaces_left_to_move = 0;
-					}//end else we will stay on the same direction
-				},
-				MoonDirection::West => {
-					// how many space can we move counter-clockwise?
-					if spaces_left_to_move > new_row1 {
-						// space after column
-						let spaces_used: usize = (spaces_left_to_move - new_row1) + 1;
-						// set columns to first possible value
-						new_col1 = self.grid_side_length-2;
-						new_col2 = self.grid_side_length-1;
-						// update row to be top side now
-						new_row1 = 0;
-						new_row2 = 0;
-						// update direction since we're flipping to a different side of the grid
-						new_direction = new_direction.next();
-						// update_spaces_left_to_move, since we've used part of it
-						spaces_left_to_move -= spaces_used;
-					}//end if we'll be changing direction
-					else {
-						// take spaces left to move out of column location
-						new_row1 -= spaces_left_to_move;
-						new_row2 -= spaces_left_to_move;
-						spaces_left_to_move = 0;
-					}//end else we will stay on the same direction
-				},
-			}//end matching direction
-		}//end looping while we have more spaces to move
-		
-		Moon {
-			direction: new_direction,
-			row1: new_row1,
-			col1: new_col1,
-			row2: new_row2,
-			col2: new_col2,
-			full_moon: self.full_moon,
-			grid_side_length: self.grid_side_length,
-		}//end struct construction
-	}//end next(&self)
+	pub fn advance(&self, steps: usize) -> Moon {
+		return Moon::at_ring_index(self.grid_side_length, self.ring_index() + steps, self.full_moon);
+	}//end advance(&self, steps)
+
+	/// # passes_sun(&self, sun, steps)
+	/// 
+	/// Returns whether the moon will pass the sun when the sun moves to its next position and the moon moves the given number of places, as in advance().  
+	/// 
+	/// The moon moves counter-clockwise and the sun moves clockwise, so they keep passing each other. 
+	/// Distances are measured around the edge of the board, in half-spaces so that the middle of each side and the middle of the moon both land on whole numbers.
+	pub fn passes_sun(&self, sun: &Sun, steps: usize) -> bool {
+		let side = self.grid_side_length;
+		let perimeter = 8 * (side - 1);
+		// middle of the moon, in half-spaces counter-clockwise from the top right corner
+		let moon_position = 2 * self.ring_index() + 1;
+		// each of the eight sun positions is an eighth of the way around
+		let sun_eighths = match sun.direction {
+			SunDirection::Southwest => 0,
+			SunDirection::South => 1,
+			SunDirection::Southeast => 2,
+			SunDirection::East => 3,
+			SunDirection::Northeast => 4,
+			SunDirection::North => 5,
+			SunDirection::Northwest => 6,
+			SunDirection::West => 7,
+		};//end matching where the sun is
+		let sun_position = sun_eighths * (side - 1);
+		// how far ahead of the sun the moon is, and how much further ahead it'll get
+		let ahead = (moon_position + perimeter - sun_position) % perimeter;
+		let moon_move = 2 * steps;
+		let sun_move = side - 1;
+		let times_passed = (ahead + moon_move + sun_move) / perimeter;
+		return times_passed % 2 == 1;
+	}//end passes_sun(&self, sun, steps)
 }//end impl for Moon
 
 /// # Board struct
@@ -218,7 +209,7 @@ impl Moon {
 /// It stores position in a Grid of BoardSpot objects,
 /// and each BoardSpot object stores mechanic information about that position.
 /// 
-/// It should be noted that several methods will assume that the board is a grid of 7x7, and they will not work without that assumption.
+/// The board is always square. It's 7x7 unless the rules ask for another size.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Board {
 	/// the grid that represents the game board
@@ -231,80 +222,58 @@ pub struct Board {
 
 #[allow(dead_code)]
 impl Board {
+	/// # with_size(side)
+	/// 
+	/// Creates an empty square board with the given number of spots along each side, with the sun and moon in their starting places.  
+	/// 
+	/// Call initialize_board() afterwards to give each spot its fertility.
+	pub fn with_size(side: usize) -> Board {
+		return Board {
+			board: Grid::new(side, side),
+			sun: Sun::default(),
+			moon: Moon::new(side),
+		};//end struct construction
+	}//end with_size(side)
+
 	/// # initialize_board(&mut self)
 	/// 
 	/// This function will fill the board with appropriate board pieces for the beginning of the game.  
 	/// 
-	/// This function assumes that the board is a grid of 7x7.
+	/// Fertility goes up by one leaf for each ring in from the edge, so on the usual 7x7 board the outer ring is one leaf and the middle spot is four leaf.
+	/// Bigger boards have a bigger four leaf area in the middle, and smaller boards stop before reaching four leaf.
 	pub fn initialize_board(&mut self) {
-		// create reference pieces to fill board with
-		let one_leaf = BoardSpot::new(Fertility::OneLeaf);
-		let two_leaf = BoardSpot::new(Fertility::TwoLeaf);
-		let three_leaf = BoardSpot::new(Fertility::ThreeLeaf);
-		let four_leaf = BoardSpot::new(Fertility::FourLeaf);
-
-		// fill whole board with four_leaf (will end up being at center)
-		self.board.fill(four_leaf);
-
-		// overwrite row index 2 and 4 with three leaf
-		for piece in self.board.iter_row_mut(2) {
-			*piece = three_leaf;
-		}//end looping over row index 2
-		for piece in self.board.iter_row_mut(4) {
-			*piece = three_leaf;
-		}//end looping over row index 4
-
-		// overwrite col index 2 and 4 with three leaf
-		for piece in self.board.iter_col_mut(2) {
-			*piece = three_leaf;
-		}//end looping over col index 2
-		for piece in self.board.iter_col_mut(4) {
-			*piece = three_leaf;
-		}//end looping over col index 4
-
-		// overwrite row index 1 and 5 with two leaf
-		for piece in self.board.iter_row_mut(1) {
-			*piece = two_leaf;
-		}//end looping over row index 1
-		for piece in self.board.iter_row_mut(5) {
-			*piece = two_leaf;
-		}//end looping over row index 5
-
-		// overwrite col index 1 and 5 with two leaf
-		for piece in self.board.iter_col_mut(1) {
-			*piece = two_leaf;
-		}//end looping over col index 1
-		for piece in self.board.iter_col_mut(5) {
-			*piece = two_leaf;
-		}//end looping over col index 5
-
-		// overwrite row index 0 and 6 with one leaf
-		for piece in self.board.iter_row_mut(0) {
-			*piece = one_leaf;
-		}//end looping over row index 0
-		for piece in self.board.iter_row_mut(6) {
-			*piece = one_leaf;
-		}//end looping over row index 6
-
-		// overwrite col index 0 and 6 with one leaf
-		for piece in self.board.iter_col_mut(0) {
-			*piece = one_leaf;
-		}//end looping over col index 0
-		for piece in self.board.iter_col_mut(6) {
-			*piece = one_leaf;
-		}//end looping over col index 6
+		let fertilities = [Fertility::OneLeaf, Fertility::TwoLeaf, Fertility::ThreeLeaf, Fertility::FourLeaf];
+		let last_row = self.board.rows().saturating_sub(1);
+		let last_col = self.board.cols().saturating_sub(1);
+		for row in 0..self.board.rows() {
+			for col in 0..self.board.cols() {
+				// number of rings between this spot and the edge
+				let ring = row.min(col).min(last_row - row).min(last_col - col);
+				*self.board.get_mut(row, col).unwrap() = BoardSpot::new(fertilities[ring.min(3)]);
+			}//end looping over cols
+		}//end looping over rows
 	}//end initialize_board(&mut self)
 
-	/// # pass_sun_and_moon(&mut self)
+	/// # on_edge(&self, row, col)
 	/// 
-	/// Carries out the rotation of the moon and sun, updating the area which is in shadow.  
+	/// Returns whether the given spot is on the outer ring of the board.
+	pub fn on_edge(&self, row: usize, col: usize) -> bool {
+		return row == 0 || col == 0 || row + 1 == self.board.rows() || col + 1 == self.board.cols();
+	}//end on_edge(&self, row, col)
+
+	/// # pass_sun_and_moon(&mut self, moon_steps)
+	/// 
+	/// Carries out the rotation of the moon and sun, updating the area which is in shadow. The moon moves the given number of places around the edge of the board.  
 	/// 
 	/// Since this function is just for moving the moon and sun, it shouldn't be called every time a player takes a turn
-	pub fn pass_sun_and_moon(&mut self) {
-		// TODO: Figure out when to flip self.moon.full_moon
+	pub fn pass_sun_and_moon(&mut self, moon_steps: usize) {
+		let flip = self.moon.passes_sun(&self.sun, moon_steps);
 		self.sun = self.sun.next();
-		self.moon = self.moon.next();
-	}//end pass_sun_and_moon(&mut self)
+		self.moon = self.moon.advance(moon_steps);
+		if flip {
+			self.moon.full_moon = !self.moon.full_moon;
+		}//end if moon passed the sun
+	}//end pass_sun_and_moon(&mut self, moon_steps)
 
 	/// # sun_grid_starts_directions(direciton, rows, cols)
 	/// 
@@ -328,17 +297,17 @@ impl Board {
 		let row_col_direction: (i8, i8);
 
 		// bottom row
-		let row_starts_north = fill_new_vec(rows, rows - 1);
+		let row_starts_north = fill_new_vec(cols, rows - 1);
 		let col_starts_north = (0..cols).collect::<Vec<usize>>();
 		// leftmost column
 		let row_starts_east = (0..rows).collect::<Vec<usize>>();
-		let col_starts_east = fill_new_vec(cols, 0);
+		let col_starts_east = fill_new_vec(rows, 0);
 		// top row
-		let row_starts_south = fill_new_vec(rows, 0);
+		let row_starts_south = fill_new_vec(cols, 0);
 		let col_starts_south = (0..cols).collect::<Vec<usize>>();
 		// rightmost column
 		let row_starts_west = (0..rows).collect::<Vec<usize>>();
-		let col_starts_west = fill_new_vec(cols, cols - 1);
+		let col_starts_west = fill_new_vec(rows, cols - 1);
 
 		match direction {
 			SunDirection::North => {
@@ -349,8 +318,9 @@ impl Board {
 				row_col_direction = (-1,0);
 			}, SunDirection::Northeast => {
 				// set starts to bottom left (combination of north and east)
-				row_starts = combine_two_vecs(&row_starts_north, &row_starts_east, true);
-				col_starts = combine_two_vecs(&col_starts_north, &col_starts_east, true);
+				let starts = combine_two_vecs(&merge_two_vecs(&row_starts_north, &col_starts_north), &merge_two_vecs(&row_starts_east, &col_starts_east), true);
+				row_starts = starts.iter().map(|start| start.0).collect();
+				col_starts = starts.iter().map(|start| start.1).collect();
 				// set direction to decrease in row, increase in column
 				row_col_direction = (-1,1);
 			}, SunDirection::East => {
@@ -361,8 +331,9 @@ impl Board {
 				row_col_direction = (0,1);
 			}, SunDirection::Southeast => {
 				// set starts to top left (combination of south and east)
-				row_starts = combine_two_vecs(&row_starts_south, &row_starts_east, true);
-				col_starts = combine_two_vecs(&col_starts_south, &col_starts_east, true);
+				let starts = combine_two_vecs(&merge_two_vecs(&row_starts_south, &col_starts_south), &merge_two_vecs(&row_starts_east, &col_starts_east), true);
+				row_starts = starts.iter().map(|start| start.0).collect();
+				col_starts = starts.iter().map(|start| start.1).collect();
 				// set direction to increase in both row and column (go down and right)
 				row_col_direction = (1,1);
 			}, SunDirection::South => {
@@ -373,8 +344,9 @@ impl Board {
 				row_col_direction = (1, 0);
 			}, SunDirection::Southwest => {
 				// set starts to top right (combination of south and west)
-				row_starts = combine_two_vecs(&row_starts_south, &row_starts_west, true);
-				col_starts = combine_two_vecs(&col_starts_south, &col_starts_west, true);
+				let starts = combine_two_vecs(&merge_two_vecs(&row_starts_south, &col_starts_south), &merge_two_vecs(&row_starts_west, &col_starts_west), true);
+				row_starts = starts.iter().map(|start| start.0).collect();
+				col_starts = starts.iter().map(|start| start.1).collect();
 				// set direction to increase in row, decrease in column (go down and left)
 				row_col_direction = (1,-1);
 			}, SunDirection::West => {
@@ -385,8 +357,9 @@ impl Board {
 				row_col_direction = (0,-1);
 			}, SunDirection::Northwest => {
 				// set starts to bottom right
-				row_starts = combine_two_vecs(&row_starts_north, &row_starts_west, true);
-				col_starts = combine_two_vecs(&col_starts_north, &col_starts_west, true);
+				let starts = combine_two_vecs(&merge_two_vecs(&row_starts_north, &col_starts_north), &merge_two_vecs(&row_starts_west, &col_starts_west), true);
+				row_starts = starts.iter().map(|start| start.0).collect();
+				col_starts = starts.iter().map(|start| start.1).collect();
 				// set direction to decrease in both row and column
 				row_col_direction = (-1,-1); },
 		}//end matching sun direction
@@ -410,6 +383,13 @@ impl Board {
 	/// 
 	/// This function returns a grid of booleans parallel to self.board. It should be noted, that if a spot would be in shadow, but that spot holds a tree taller than the shadow, such that the tree should still provide light points, then the returned grid will state that that spot is not in shadow. This only happens for trees though. This is done so that one can easily check using this method whether a tree should receive light points or whether a random spot on the board is in shadow for seed planting or tree upgrading purposes.
 	pub(crate) fn sun_shaded(&self) -> Grid<bool> {
+		return self.sun_shaded_with(&ShadeRules::default());
+	}//end sun_shaded(&self)
+
+	/// # sun_shaded_with(&self, shade)
+	/// 
+	/// Same as sun_shaded(), but with the heights and shadow lengths from the given rules instead of the official ones.
+	pub(crate) fn sun_shaded_with(&self, shade: &ShadeRules) -> Grid<bool> {
 		// instantiate parallel grid
 		let mut is_shaded: Grid<bool> = Grid::new(self.board.rows(), self.board.cols());
 		is_shaded.fill(false);
@@ -442,31 +422,38 @@ impl Board {
 				let is_great_elder_tree = this_spot.piece_type == PieceType::GreatElderTree;
 				let is_moonstone = this_spot.piece_type == PieceType::Moonstone;
 				
-				// apply shadows of normal trees
+				// run through shadows cast by earlier spots on this line to determine if this spot is in shadow
+				for shadow in &shadow_size_left {
+					// if tree here, check if shadow big enough. Else, set shadowed if not great elder tree
+					if (maybe_this_tree.is_some() && shadow.0 >= shade.tree_heights[maybe_this_tree.unwrap().size.size()]) || (maybe_this_tree.is_none() && !is_great_elder_tree) {
+						// set this spot as shaded
+						*is_shaded.get_mut(cur_row as usize, cur_col as usize).unwrap() = true;
+						break;
+					}//end if shadow from big enough object
+				}//end checking for any valid shadows on this spot
+
+				// decrement the number of tiles left each shadow covers, dropping shadows that don't reach any further
+				for shadow in &mut shadow_size_left {
+					shadow.1 -= 1;
+				}//end decrementing each shadow
+				shadow_size_left.retain(|shadow| shadow.1 > 0);
+
+				// add the shadow cast by this spot, which starts on the next spot so that nothing shades itself
 				if let Some(tree) = maybe_this_tree {
-					// add shadow of tree to our funky little Vec
-					shadow_size_left.push((tree.size.size(), tree.size.size()));
+					// seeds are too small to cast a shadow
+					let size = tree.size.size();
+					if shade.tree_lengths[size] > 0 {
+						shadow_size_left.push((shade.tree_heights[size], shade.tree_lengths[size]));
+					}//end if tree is big enough to cast a shadow
 				}//end if we have a tree to add a shadow to
 				// apply shadows of great elder tree, if detected
 				if is_great_elder_tree {
-					shadow_size_left.push((4,self.board.rows() + self.board.cols()));
+					shadow_size_left.push((shade.elder_height, shade.elder_reach(self.board.rows(), self.board.cols())));
 				}//end if we have a great elder tree here
-				if is_moonstone {
-					shadow_size_left.push((1,1));
+				if is_moonstone && shade.moonstone_length > 0 {
+					shadow_size_left.push((shade.moonstone_height, shade.moonstone_length));
 				}//end if we have a moonstone
 
-				// run through shadow vec to determine if this tree is in shadow
-				for shadow in &mut shadow_size_left {
-					// if tree here, check if shadow big enough. Else, set shadowed if not great elder tree
-					if (maybe_this_tree.is_some() && shadow.0 >= maybe_this_tree.unwrap().size.size()) || (maybe_this_tree.is_none() && !is_great_elder_tree) {
-						// set this spot as shaded
-						*is_shaded.get_mut(cur_row as usize, cur_col as usize).unwrap() = true;
-						// spot already shaded, but don't break, because we need to decrement the other shadows
-					}//end if shadow from big enough object
-					// decrement the number of tiles left this shadow covers
-					shadow.1 -= 1;
-				}//end checking for any valid shadows on this spot
-
 				// check if next change would be in bounds
 				let row_too_sml = cur_row == 0 && row_col_direction.0 < 0;
 				let row_too_big = cur_row == self.board.rows() as i8 - 1 && row_col_direction.0 > 0;
@@ -477,6 +464,9 @@ impl Board {
 					cur_row += row_col_direction.0;
 					cur_col += row_col_direction.1;
 				}//end if we're in bounds for next operation
+				else {
+					break;
+				}//end else we've reached the edge of the board
 			}//end looping until we hit an invalid index
 		}//end looping over the row_col coord starts
 
@@ -541,6 +531,8 @@ impl Board {
 	/// Each element in grid of booleans says whether that spot is lit by the moon.
 	/// 
 	/// So, if true, then it is lit by the moon, and if false, then it receives no moonlight.
+	/// 
+	/// Moonstones the moon reaches are marked as lit too, along with everything around them.
 	pub(crate) fn moon_lit(&self) -> Grid<bool> {
 		// instantiate parallel grid
 		let mut is_lit: Grid<bool> = Grid::new(self.board.rows(), self.board.cols());
@@ -575,6 +567,8 @@ impl Board {
 					break;
 				}//end if everything after this is in shadow
 				if this_spot.piece_type == PieceType::Moonstone {
+					// the moonstone itself counts as lit, so we know to give out lunar points for it later
+					*is_lit.get_mut(cur_row, cur_col).unwrap() = true;
 					// Get list of adjacent spot, shine light on them, check for more adjacent in loop
 					let mut adjacents_queue_index = 0;
 					let mut adjacents_queue: Vec<(usize,usize)> = get_adjacent_coords(cur_row, cur_col, self.board.rows() - 1, self.board.cols() - 1, true);
@@ -618,6 +612,9 @@ impl Board {
 					cur_row = (cur_row as i8 + direction.0) as usize;
 					cur_col = (cur_col as i8 + direction.1) as usize;
 				}//end if we're in bounds for next operation
+				else {
+					break;
+				}//end else we've reached the edge of the board
 			}//end looping until we finish getting shadows figured out
 		}//end looping over starts and directions
 
@@ -838,6 +835,15 @@ impl BoardSpot {
 	pub fn expend(&mut self) {
 		self.expended = true;
 	}//end expend(self)
+
+	/// # can_hold_animal(&self)
+	/// 
+	/// Returns whether a forest animal could move onto this spot.  
+	/// 
+	/// Forest animals cannot move onto the same spot as another animal, a moonstone, or the great elder tree.
+	pub fn can_hold_animal(&self) -> bool {
+		return self.animal.is_none() && !matches!(self.piece_type, PieceType::Moonstone | PieceType::GreatElderTree);
+	}//end can_hold_animal(&self)
 }//end impl for BoardSpot
 
 impl Default for BoardSpot {
@@ -1105,3 +1111,338 @@ impl MovingLightDirection for MoonDirection {
         }//end matching self
     }//end next(&self)
 }//end impl MovingLightDirection for MoonDirection
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+
+	/// every direction the sun can point, in the order it moves
+	pub(super) const ALL_SUN_DIRECTIONS: [SunDirection; 8] = [
+		SunDirection::North, SunDirection::Northeast, SunDirection::East, SunDirection::Southeast,
+		SunDirection::South, SunDirection::Southwest, SunDirection::West, SunDirection::Northwest,
+	];
+
+	/// # XorShift
+	///
+	/// Tiny random number generator so property tests can make lots of boards, while always making the same boards each run.
+	pub(super) struct XorShift(pub(super) u64);
+
+	impl XorShift {
+		pub(super) fn next(&mut self) -> u64 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			return self.0;
+		}//end next(&mut self)
+
+		pub(super) fn below(&mut self, n: usize) -> usize {
+			return (self.next() % n as u64) as usize;
+		}//end below(&mut self, n)
+	}//end impl for XorShift
+
+	/// # empty_board(rows, cols, direction)
+	///
+	/// Makes a board with nothing on it, with the sun pointing in the given direction.
+	pub(super) fn empty_board(rows: usize, cols: usize, direction: SunDirection) -> Board {
+		let mut board = Board {board: Grid::new(rows, cols), sun: Sun::new(direction), moon: Moon::new(rows.max(2))};
+		board.board.fill(BoardSpot::default());
+		return board;
+	}//end empty_board(rows, cols, direction)
+
+	/// # players(count)
+	///
+	/// Makes the given number of players, called Player 1, Player 2 and so on, with the default colors.
+	pub(crate) fn players(count: usize) -> Vec<Player> {
+		return (0..count).map(|index| Player::new(&format!("Player {}", index + 1), default_player_color(index))).collect();
+	}//end players(count)
+
+	/// # started_game(players)
+	///
+	/// Makes a game under the official rules, except that nobody places starting trees, so the first turn can start right away.
+	pub(crate) fn started_game(players: Vec<Player>) -> GameState {
+		return GameState::with_rules(players, RuleSet {starting_trees: 0, ..RuleSet::default()});
+	}//end started_game(players)
+
+	/// # place(board, row, col, size)
+	///
+	/// Puts a tree on the board.
+	pub(super) fn place(board: &mut Board, row: usize, col: usize, size: TreeSize) {
+		let spot = board.board.get_mut(row, col).unwrap();
+		spot.tree = Some(Tree {color: (0,0,0), size});
+		spot.piece_type = PieceType::Tree;
+	}//end place(board, row, col, size)
+
+	/// # shaded_coords(shaded)
+	///
+	/// Lists every shaded spot, in row-major order.
+	fn shaded_coords(shaded: &Grid<bool>) -> Vec<(usize,usize)> {
+		let mut coords = Vec::new();
+		for row in 0..shaded.rows() {
+			for col in 0..shaded.cols() {
+				if *shaded.get(row, col).unwrap() {
+					coords.push((row, col));
+				}//end if this spot is shaded
+			}//end looping over cols
+		}//end looping over rows
+		return coords;
+	}//end shaded_coords(shaded)
+
+	/// # step(direction)
+	///
+	/// The (row,col) change for one step in the direction the sun is pointing.
+	fn step(direction: SunDirection) -> (isize, isize) {
+		match direction {
+			SunDirection::North => (-1,0),
+			SunDirection::Northeast => (-1,1),
+			SunDirection::East => (0,1),
+			SunDirection::Southeast => (1,1),
+			SunDirection::South => (1,0),
+			SunDirection::Southwest => (1,-1),
+			SunDirection::West => (0,-1),
+			SunDirection::Northwest => (-1,-1),
+		}//end matching direction
+	}//end step(direction)
+
+	/// # brute_force_shade(board)
+	///
+	/// Slow but obvious version of sun_shaded, which looks back toward the sun from every spot for a tree that's tall enough and close enough to shade it.
+	fn brute_force_shade(board: &Board) -> Grid<bool> {
+		let (dr, dc) = step(board.sun.direction);
+		let mut shaded = Grid::new(board.board.rows(), board.board.cols());
+		shaded.fill(false);
+		for row in 0..board.board.rows() {
+			for col in 0..board.board.cols() {
+				let here = board.board.get(row, col).unwrap().tree.map(|tree| tree.size.size());
+				for distance in 1..=3isize {
+					let (r, c) = (row as isize - dr * distance, col as isize - dc * distance);
+					if r < 0 || c < 0 {
+						break;
+					}//end if we went off the board
+					let caster = match board.board.get(r as usize, c as usize) {
+						Some(spot) => spot.tree.map(|tree| tree.size.size()).unwrap_or(0),
+						None => break,
+					};//end matching whether spot is on the board
+					if caster as isize >= distance && here.is_none_or(|size| caster >= size) {
+						*shaded.get_mut(row, col).unwrap() = true;
+					}//end if caster is tall enough and close enough
+				}//end looking back toward the sun
+			}//end looping over cols
+		}//end looping over rows
+		return shaded;
+	}//end brute_force_shade(board)
+
+	#[test]
+	fn large_tree_in_center_shades_three_spots_in_every_direction() {
+		let expected = [
+			(SunDirection::North, vec![(0,3),(1,3),(2,3)]),
+			(SunDirection::Northeast, vec![(0,6),(1,5),(2,4)]),
+			(SunDirection::East, vec![(3,4),(3,5),(3,6)]),
+			(SunDirection::Southeast, vec![(4,4),(5,5),(6,6)]),
+			(SunDirection::South, vec![(4,3),(5,3),(6,3)]),
+			(SunDirection::Southwest, vec![(4,2),(5,1),(6,0)]),
+			(SunDirection::West, vec![(3,0),(3,1),(3,2)]),
+			(SunDirection::Northwest, vec![(0,0),(1,1),(2,2)]),
+		];
+		for (direction, shaded) in expected {
+			let mut board = empty_board(7, 7, direction);
+			place(&mut board, 3, 3, TreeSize::Large);
+			assert_eq!(shaded_coords(&board.sun_shaded()), shaded, "sun {}", direction);
+		}//end checking each direction
+	}//end large_tree_in_center_shades_three_spots_in_every_direction()
+
+	#[test]
+	fn row_of_mixed_trees_pointing_east() {
+		let mut board = empty_board(7, 7, SunDirection::East);
+		place(&mut board, 3, 0, TreeSize::Small);
+		place(&mut board, 3, 1, TreeSize::Medium);
+		place(&mut board, 3, 3, TreeSize::Large);
+		place(&mut board, 3, 5, TreeSize::Small);
+		// the small tree can't shade the medium one, and the medium tree can't shade the large one
+		assert_eq!(shaded_coords(&board.sun_shaded()), vec![(3,2),(3,4),(3,5),(3,6)]);
+	}//end row_of_mixed_trees_pointing_east()
+
+	#[test]
+	fn shadows_stop_at_edge_of_board() {
+		let mut board = empty_board(7, 7, SunDirection::Northeast);
+		place(&mut board, 1, 5, TreeSize::Medium);
+		assert_eq!(shaded_coords(&board.sun_shaded()), vec![(0,6)]);
+
+		let mut board = empty_board(7, 7, SunDirection::West);
+		place(&mut board, 6, 0, TreeSize::Large);
+		assert_eq!(shaded_coords(&board.sun_shaded()), Vec::<(usize,usize)>::new());
+	}//end shadows_stop_at_edge_of_board()
+
+	#[test]
+	fn seeds_cast_no_shadow_but_can_be_shaded() {
+		let mut board = empty_board(7, 7, SunDirection::South);
+		place(&mut board, 0, 0, TreeSize::Seed);
+		place(&mut board, 0, 1, TreeSize::Small);
+		place(&mut board, 1, 1, TreeSize::Seed);
+		assert_eq!(shaded_coords(&board.sun_shaded()), vec![(1,1)]);
+	}//end seeds_cast_no_shadow_but_can_be_shaded()
+
+	#[test]
+	fn empty_board_is_fully_lit() {
+		for (rows, cols) in [(7,7), (5,9), (9,4), (2,2)] {
+			for direction in ALL_SUN_DIRECTIONS {
+				let board = empty_board(rows, cols, direction);
+				assert!(shaded_coords(&board.sun_shaded()).is_empty(), "{}x{} board, sun {}", rows, cols, direction);
+			}//end checking each direction
+		}//end checking each board size
+	}//end empty_board_is_fully_lit()
+
+	#[test]
+	fn lone_tree_never_shades_itself() {
+		for direction in ALL_SUN_DIRECTIONS {
+			for size in [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large] {
+				for row in 0..7 {
+					for col in 0..7 {
+						let mut board = empty_board(7, 7, direction);
+						place(&mut board, row, col, size);
+						assert!(!*board.sun_shaded().get(row, col).unwrap());
+					}//end looping over cols
+				}//end looping over rows
+			}//end checking each size
+		}//end checking each direction
+	}//end lone_tree_never_shades_itself()
+
+	#[test]
+	fn large_tree_shades_exactly_three_spots_of_smaller_trees() {
+		let mut rng = XorShift(0x9E3779B97F4A7C15);
+		let smaller = [None, Some(TreeSize::Seed), Some(TreeSize::Small), Some(TreeSize::Medium)];
+		for _ in 0..500 {
+			let direction = ALL_SUN_DIRECTIONS[rng.below(8)];
+			let (dr, dc) = step(direction);
+			// pick a spot with room for three spots of shadow
+			let (row, col) = loop {
+				let (row, col) = (rng.below(7) as isize, rng.below(7) as isize);
+				let (end_row, end_col) = (row + dr * 3, col + dc * 3);
+				if (0..7).contains(&end_row) && (0..7).contains(&end_col) {
+					break (row, col);
+				}//end if whole shadow fits on the board
+			};//end looking for a spot
+			let mut board = empty_board(7, 7, direction);
+			place(&mut board, row as usize, col as usize, TreeSize::Large);
+			let mut expected = Vec::new();
+			for distance in 1..=3 {
+				let (r, c) = ((row + dr * distance) as usize, (col + dc * distance) as usize);
+				// keep each smaller tree short enough that its own shadow stays inside the large tree's shadow
+				if let Some(size) = smaller[rng.below(smaller.len() + 1 - distance as usize)] {
+					place(&mut board, r, c, size);
+				}//end if we're putting a tree here
+				expected.push((r, c));
+			}//end filling spots in the shadow
+			expected.sort();
+
+			assert_eq!(shaded_coords(&board.sun_shaded()), expected, "large tree at {},{} with sun {}", row, col, direction);
+		}//end trying random boards
+	}//end large_tree_shades_exactly_three_spots_of_smaller_trees()
+
+	#[test]
+	fn sun_shaded_matches_brute_force_on_random_boards() {
+		let mut rng = XorShift(0x2545F4914F6CDD1D);
+		let sizes = [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large];
+		for (rows, cols) in [(7,7), (5,9), (9,4), (3,3)] {
+			for _ in 0..300 {
+				let mut board = empty_board(rows, cols, ALL_SUN_DIRECTIONS[rng.below(8)]);
+				for _ in 0..rng.below(rows * cols) {
+					place(&mut board, rng.below(rows), rng.below(cols), sizes[rng.below(4)]);
+				}//end placing random trees
+				assert_eq!(board.sun_shaded(), brute_force_shade(&board), "{:?}", board);
+			}//end trying random boards
+		}//end trying each board size
+	}//end sun_shaded_matches_brute_force_on_random_boards()
+
+	#[test]
+	fn moon_visits_every_edge_position_once_per_orbit() {
+		for side in [2, 3, 5, 7, 9] {
+			let first = Moon::new(side);
+			assert_eq!(first.ring_index(), 0);
+			let mut seen = Vec::new();
+			for index in 0..first.ring_count() {
+				let moon = Moon::at_ring_index(side, index, false);
+				assert_eq!(moon.ring_index(), index, "side {}", side);
+				// both spots are on the edge the moon is pointing away from, right next to each other
+				let on_edge = |row: usize, col: usize| match moon.direction {
+					MoonDirection::South => row == 0,
+					MoonDirection::East => col == 0,
+					MoonDirection::North => row == side - 1,
+					MoonDirection::West => col == side - 1,
+				};//end matching direction
+				assert!(on_edge(moon.row1, moon.col1) && on_edge(moon.row2, moon.col2), "{:?}", moon);
+				assert_eq!(moon.row1.abs_diff(moon.row2) + moon.col1.abs_diff(moon.col2), 1, "{:?}", moon);
+				let spots = ((moon.row1, moon.col1), (moon.row2, moon.col2), moon.direction);
+				assert!(!seen.contains(&spots), "{:?} visited twice", moon);
+				seen.push(spots);
+			}//end looping over each place around the board
+			assert_eq!(Moon::at_ring_index(side, first.ring_count(), false), first);
+		}//end checking each board size
+	}//end moon_visits_every_edge_position_once_per_orbit()
+
+	#[test]
+	fn moon_advance_follows_golden_path_on_standard_board() {
+		// (direction, first spot, second spot) after each round, starting from the top right corner
+		let expected = [
+			(MoonDirection::East, (3,0), (4,0)),
+			(MoonDirection::West, (6,6), (5,6)),
+			(MoonDirection::South, (0,3), (0,2)),
+			(MoonDirection::North, (6,0), (6,1)),
+			(MoonDirection::West, (3,6), (2,6)),
+			(MoonDirection::East, (0,0), (1,0)),
+			(MoonDirection::North, (6,3), (6,4)),
+			(MoonDirection::South, (0,6), (0,5)),
+		];
+		let mut moon = Moon::new(7);
+		for (direction, first, second) in expected {
+			moon = moon.advance(7 + RuleSet::default().moon_extra_steps);
+			assert_eq!((moon.direction, (moon.row1, moon.col1), (moon.row2, moon.col2)), (direction, first, second));
+		}//end checking each round
+		assert_eq!(moon, Moon::new(7));
+	}//end moon_advance_follows_golden_path_on_standard_board()
+
+	#[test]
+	fn moon_advance_never_runs_off_board() {
+		for side in [2, 3, 4, 5, 7, 9, 11] {
+			let mut moon = Moon::new(side);
+			for _ in 0..(3 * moon.ring_count()) {
+				moon = moon.advance(side + RuleSet::default().moon_extra_steps);
+				assert!(moon.row1 < side && moon.col1 < side && moon.row2 < side && moon.col2 < side, "{:?}", moon);
+				let board = empty_board(side, side, SunDirection::North);
+				let board = Board {moon, ..board};
+				assert!(*board.moon_lit().get(moon.row1, moon.col1).unwrap());
+			}//end moving the moon around several times
+		}//end checking each board size
+	}//end moon_advance_never_runs_off_board()
+
+	#[test]
+	fn moon_lights_two_diagonals_spreading_out() {
+		let board = empty_board(7, 7, SunDirection::North);
+		assert_eq!(shaded_coords(&board.moon_lit()), vec![(0,5),(0,6),(1,4),(2,3),(3,2),(4,1),(5,0)]);
+
+		let mut board = empty_board(7, 7, SunDirection::North);
+		board.moon = Moon::at_ring_index(7, 9, false);
+		assert_eq!(shaded_coords(&board.moon_lit()), vec![(0,3),(1,2),(2,1),(3,0),(4,0),(5,1),(6,2)]);
+	}//end moon_lights_two_diagonals_spreading_out()
+
+	#[test]
+	fn moon_turns_full_after_passing_sun() {
+		let mut board = empty_board(7, 7, SunDirection::Northeast);
+		// the moon starts on the opposite corner to the sun, and they meet on the left side of the board
+		let steps = 7 + RuleSet::default().moon_extra_steps;
+		assert!(board.moon.passes_sun(&board.sun, steps));
+		board.pass_sun_and_moon(steps);
+		assert!(board.moon.full_moon);
+
+		// over a whole game the moon and sun pass each other an even number of times per orbit of the sun
+		let mut flips = 0;
+		for _ in 0..8 {
+			let before = board.moon.full_moon;
+			board.pass_sun_and_moon(steps);
+			if board.moon.full_moon != before {
+				flips += 1;
+			}//end if moon changed
+		}//end moving through a whole revolution of the sun
+		assert_eq!(flips % 2, 0);
+		assert!(flips > 0);
+	}//end moon_turns_full_after_passing_sun()
+}//end mod tests
@@ -0,0 +1,315 @@
+use std::fmt::Display;
+
+use super::{GameAction, GameState, PieceType, TreeSize, bitboard::CompactBoard, light::LightEvaluator, zobrist::TranspositionTable};
+
+/// How much each tree size is worth while it's on the board, indexed by TreeSize::size(). Bigger trees earn more light and are closer to being collected.
+const BOARD_TREE_VALUES: [i64; 4] = [2, 4, 8, 14];
+/// How much each tree size is worth while it's waiting in the available area, indexed by TreeSize::size().
+const AVAILABLE_TREE_VALUES: [i64; 4] = [1, 1, 2, 3];
+/// The most actions the computer will take in one turn, in case the rules make some action free.
+pub(crate) const MAX_ACTIONS_PER_TURN: usize = 40;
+/// The most actions the computer takes in one turn when playing easy.
+const EASY_ACTIONS_PER_TURN: usize = 2;
+/// How many of the best looking actions the computer looks past when playing hard, to see what they'd let it do next.
+const HARD_LOOKAHEAD: usize = 6;
+/// The computer remembers the scores of up to 2^SCORE_TABLE_BITS games while choosing each action.
+const SCORE_TABLE_BITS: u32 = 10;
+
+/// # Difficulty
+///
+/// How well the computer plays.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Default)]
+pub enum Difficulty {
+	/// greedy, but only takes a couple of actions each turn
+	Easy,
+	/// greedy, taking the best action until nothing looks better than ending the turn
+	#[default]
+	Normal,
+	/// like normal, but also looks at what each of the best actions would let it do next
+	Hard,
+}//end enum Difficulty
+
+#[allow(dead_code)]
+impl Difficulty {
+	/// # all()
+	///
+	/// Returns every difficulty, from easiest to hardest.
+	pub fn all() -> [Difficulty; 3] {
+		return [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+	}//end all()
+
+	/// # from_name(name)
+	///
+	/// Returns the difficulty with the given name, ignoring case, as written by Display.
+	pub fn from_name(name: &str) -> Option<Difficulty> {
+		return Difficulty::all().into_iter().find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(name.trim()));
+	}//end from_name(name)
+}//end impl for Difficulty
+
+impl Display for Difficulty {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Difficulty::Easy => write!(f, "Easy"),
+			Difficulty::Normal => write!(f, "Normal"),
+			Difficulty::Hard => write!(f, "Hard"),
+		}//end matching self
+	}//end fmt(&self, f)
+}//end impl Display for Difficulty
+
+/*
+The computer player is greedy. It tries every action it's allowed to take, scores the game that would result, and picks the best one.
+Once nothing scores better than how things are now, it ends its turn.
+
+Trees are worth more early in the game, when they have time to earn light, and scoring tokens are worth the same the whole game.
+This makes the computer grow its forest at first, then collect trees as the game gets close to the end.
+
+Easier and harder computers use the same scoring. Easy ones stop after a couple of actions, and hard ones score the few best actions by the best thing they could do next.
+
+Each action is tried on one working copy of the game and undone again, rather than on a fresh copy of the whole game every time.
+The board is scored as bitboards, and scores are remembered by hash, since different orders of the same actions lead to the same game.
+*/
+
+/// # Scorer
+///
+/// What the computer keeps around while choosing an action, so the work of scoring a game isn't repeated for every candidate.
+struct Scorer {
+	/// the index of the player the scores are for
+	me: usize,
+	/// the color of each player, in turn order, for telling whose trees are whose on a compact board
+	colors: Vec<(u8,u8,u8)>,
+	/// shadow tables for the board being played on, or None if the board is too big for bitboards
+	evaluator: Option<LightEvaluator>,
+	/// scores of games already looked at, by zobrist hash
+	scores: TranspositionTable<i64>,
+}//end struct Scorer
+
+impl Scorer {
+	/// # new(state, me)
+	///
+	/// Gets ready to score games like the given one for the player at index me.
+	fn new(state: &GameState, me: usize) -> Scorer {
+		let (rows, cols) = state.board.board.size();
+		return Scorer {
+			me,
+			colors: state.players.iter().map(|player| player.color).collect(),
+			evaluator: LightEvaluator::with_rules(rows, cols, &state.rules).ok(),
+			scores: TranspositionTable::new(SCORE_TABLE_BITS),
+		};//end struct construction
+	}//end new(state, me)
+
+	/// # evaluate(&mut self, state)
+	///
+	/// Scores how good the game looks for our player, remembering the score in case the same game comes up again. Higher is better.
+	fn evaluate(&mut self, state: &GameState) -> i64 {
+		if let Some(score) = self.scores.get(state.zobrist()) {
+			return *score;
+		}//end if we've already scored this game
+		let player = &state.players[self.me];
+		let rounds_left = if state.game_over {0} else {(state.max_rounds + 1).saturating_sub(state.round) as i64};
+		let max_rounds = state.max_rounds.max(1) as i64;
+
+		// trees on the board, and the light they'd earn next round
+		let compact = CompactBoard::from_board(&state.board, &self.colors).ok();
+		let (mut trees, income) = match (&self.evaluator, compact) {
+			(Some(evaluator), Some(mut compact)) => {
+				let mine = compact.owners[self.me];
+				let trees = (0..4).map(|size| BOARD_TREE_VALUES[size] * (compact.trees[size] & mine).count_ones() as i64).sum();
+				compact.sun = compact.sun.next();
+				(trees, compact.light_income(evaluator, self.me) as i64)
+			},
+			_ => board_trees_and_income(state, self.me),
+		};//end matching whether the board fits in bitboards
+		trees += player.available.iter().map(|tree| AVAILABLE_TREE_VALUES[tree.size.size()]).sum::<i64>();
+
+		let light = player.light_points.min(state.rules.max_light) as i64;
+		let score = 10 * player.score() as i64 + 2 * light + 3 * income * rounds_left.min(1) + 6 * trees * rounds_left / max_rounds;
+		self.scores.insert(state.zobrist(), score);
+		return score;
+	}//end evaluate(&mut self, state)
+}//end impl for Scorer
+
+/// # choose_action_at(state, difficulty)
+///
+/// Picks the next action for the current player, playing as well as the given difficulty. This is always an action the rules allow.
+///
+/// While setting up, this picks where to put a starting tree. Otherwise, it gives EndTurn once nothing else looks worth doing.
+pub fn choose_action_at(state: &GameState, difficulty: Difficulty) -> GameAction {
+	let me = state.current_player;
+	let setting_up = state.starting_trees_left > 0;
+	if difficulty == Difficulty::Easy && !setting_up && state.undo_stack.len() >= EASY_ACTIONS_PER_TURN {
+		return GameAction::EndTurn;
+	}//end if an easy computer has done enough this turn
+	let mut scorer = Scorer::new(state, me);
+	let staying = if setting_up {i64::MIN} else {scorer.evaluate(state)};
+	let mut working = state.clone();
+	let mut scored = scored_actions(&mut working, &mut scorer);
+	if difficulty == Difficulty::Hard && !setting_up {
+		// the best few actions are scored again by the best thing they'd let us do next, which is never worse than stopping there
+		scored.sort_by_key(|(value, _)| std::cmp::Reverse(*value));
+		for (value, action) in scored.iter_mut().take(HARD_LOOKAHEAD) {
+			if working.apply(*action).is_err() {
+				continue;
+			}//end if action isn't allowed
+			if let Some(next) = scored_actions(&mut working, &mut scorer).iter().map(|(next, _)| *next).max() {
+				*value = (*value).max(next);
+			}//end if there's anything to do next
+			let _ = working.apply(GameAction::Undo);
+		}//end looking past each of the best actions
+	}//end if we're looking ahead
+	let best = scored.into_iter().fold((staying, GameAction::EndTurn), |best, (value, action)| if value > best.0 {(value, action)} else {best});
+	return best.1;
+}//end choose_action_at(state, difficulty)
+
+/// # scored_actions(state, scorer)
+///
+/// Tries every candidate action, giving back each one which is allowed along with how good the game looks afterwards.
+///
+/// Each action is carried out on state and then undone, so state is left as it was.
+fn scored_actions(state: &mut GameState, scorer: &mut Scorer) -> Vec<(i64, GameAction)> {
+	let mut scored = Vec::new();
+	for action in candidate_actions(state) {
+		if state.starting_trees_left > 0 {
+			// placing a starting tree passes to the next player, so it can't be undone and has to be tried on a copy
+			let mut after = state.clone();
+			if after.apply(action).is_ok() {
+				scored.push((scorer.evaluate(&after), action));
+			}//end if action is allowed
+			continue;
+		}//end if we're still setting up
+		if state.apply(action).is_err() {
+			continue;
+		}//end if action isn't allowed
+		scored.push((scorer.evaluate(state), action));
+		// an action that was just carried out can always be undone
+		let _ = state.apply(GameAction::Undo);
+	}//end trying each action
+	return scored;
+}//end scored_actions(state, scorer)
+
+/// # candidate_actions(state)
+///
+/// Lists actions worth trying for the current player. Some might not be allowed, since costs are only checked when they're tried.
+fn candidate_actions(state: &GameState) -> Vec<GameAction> {
+	let board = &state.board;
+	let (rows, cols) = board.board.size();
+	let mut actions = Vec::new();
+	if state.starting_trees_left > 0 {
+		for row in 0..rows {
+			for col in 0..cols {
+				if board.on_edge(row, col) && board.board.get(row, col).unwrap().piece_type == PieceType::Empty {
+					actions.push(GameAction::PlaceStartingTree(row, col));
+				}//end if a starting tree could go here
+			}//end looping over cols
+		}//end looping over rows
+		return actions;
+	}//end if we're still setting up
+
+	for size in [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large] {
+		actions.push(GameAction::Buy(size));
+	}//end trying to buy each size
+	let color = state.current().color;
+	for row in 0..rows {
+		for col in 0..cols {
+			let spot = board.board.get(row, col).unwrap();
+			let range = match spot.tree {
+				Some(tree) if tree.color == color => {
+					actions.push(if tree.size == TreeSize::Large {GameAction::Collect(row, col)} else {GameAction::Grow(row, col)});
+					state.rules.seed_ranges[tree.size.size()]
+				},
+				None if spot.piece_type == PieceType::GreatElderTree => state.rules.elder_seed_range,
+				_ => 0,
+			};//end matching what's on this spot
+			for to_row in row.saturating_sub(range)..(row + range + 1).min(rows) {
+				for to_col in col.saturating_sub(range)..(col + range + 1).min(cols) {
+					if board.board.get(to_row, to_col).unwrap().piece_type == PieceType::Empty {
+						actions.push(GameAction::PlantSeed {from: (row, col), to: (to_row, to_col)});
+					}//end if a seed could land here
+				}//end looping over cols in range
+			}//end looping over rows in range
+		}//end looping over cols
+	}//end looping over rows
+	return actions;
+}//end candidate_actions(state)
+
+/// # board_trees_and_income(state, me)
+///
+/// Helper function giving how much the trees on the board are worth to the player at index me, and the light they'd earn next round.
+///
+/// This is the slow way of scoring the board, for boards too big to fit in bitboards.
+fn board_trees_and_income(state: &GameState, me: usize) -> (i64, i64) {
+	let player = &state.players[me];
+	let mut next_board = state.board.clone();
+	next_board.sun = next_board.sun.next();
+	let shaded = next_board.sun_shaded_with(&state.rules.shade);
+	let mut trees = 0;
+	let mut income = 0;
+	for (spot, in_shade) in state.board.board.iter().zip(shaded.iter()) {
+		if let Some(tree) = spot.tree.filter(|tree| tree.color == player.color) {
+			trees += BOARD_TREE_VALUES[tree.size.size()];
+			if !in_shade {
+				income += state.rules.light_yields[tree.size.size()] as i64;
+			}//end if tree will be lit
+		}//end if this is one of our trees
+	}//end looping over each spot
+	return (trees, income);
+}//end board_trees_and_income(state, me)
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::tests::players;
+
+	#[test]
+	fn computer_players_finish_a_game() {
+		let mut state = GameState::new(players(2));
+		let mut actions_this_turn = 0;
+		while !state.game_over {
+			let action = if actions_this_turn >= MAX_ACTIONS_PER_TURN {GameAction::EndTurn} else {choose_action_at(&state, Difficulty::Normal)};
+			state.apply(action).unwrap();
+			actions_this_turn = if action == GameAction::EndTurn {0} else {actions_this_turn + 1};
+		}//end playing until the game is over
+
+		// both players should have done well enough to score, and collecting is the only way to score much
+		for player in &state.players {
+			assert!(player.score() > 0, "{:?}", player);
+		}//end checking each player
+		assert!(state.log.iter().any(|entry| matches!(entry, crate::game::LogEntry::Action {action: GameAction::Collect(..), ..})));
+	}//end computer_players_finish_a_game()
+
+	#[test]
+	fn bitboard_scoring_matches_scoring_the_board() {
+		let mut state = GameState::new(players(3));
+		for _ in 0..60 {
+			for me in 0..state.players.len() {
+				let mut fast = Scorer::new(&state, me);
+				let mut slow = Scorer {evaluator: None, ..Scorer::new(&state, me)};
+				assert!(fast.evaluator.is_some());
+				assert_eq!(fast.evaluate(&state), slow.evaluate(&state), "round {}", state.round);
+			}//end scoring for each player
+			state.apply(choose_action_at(&state, Difficulty::Normal)).unwrap();
+		}//end playing part of a game
+	}//end bitboard_scoring_matches_scoring_the_board()
+
+	#[test]
+	fn easy_computers_stop_early_and_hard_ones_still_play_legally() {
+		let mut state = GameState::new(players(2));
+		while state.starting_trees_left > 0 {
+			state.apply(choose_action_at(&state, Difficulty::Easy)).unwrap();
+		}//end placing starting trees
+		state.current_mut().light_points = 20;
+
+		// with plenty of light, an easy computer still ends its turn after a couple of actions
+		let mut actions = 0;
+		while choose_action_at(&state, Difficulty::Easy) != GameAction::EndTurn {
+			state.apply(choose_action_at(&state, Difficulty::Easy)).unwrap();
+			actions += 1;
+		}//end letting the easy computer play
+		assert_eq!(actions, EASY_ACTIONS_PER_TURN);
+
+		state.apply(GameAction::EndTurn).unwrap();
+		let action = choose_action_at(&state, Difficulty::Hard);
+		assert!(state.clone().apply(action).is_ok(), "{:?}", action);
+		assert_eq!(Difficulty::from_name(" hard "), Some(Difficulty::Hard));
+		assert_eq!(Difficulty::from_name("impossible"), None);
+	}//end easy_computers_stop_early_and_hard_ones_still_play_legally()
+}//end mod tests
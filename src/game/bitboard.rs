@@ -0,0 +1,309 @@
+use grid::Grid;
+
+use super::{Animal, Board, BoardSpot, Fertility, GameAction, Moon, PieceType, RuleSet, Sun, Tree, TreeSize, light::{LightEvaluator, MAX_BITBOARD_SPOTS, ShadeBits}};
+
+/// The most players whose trees can be told apart on a compact board.
+pub(crate) const MAX_COMPACT_OWNERS: usize = 4;
+
+/// every piece type, in the order they're stored in CompactBoard::pieces
+const PIECE_TYPES: [PieceType; 6] = [PieceType::Empty, PieceType::Tree, PieceType::Animal, PieceType::Dam, PieceType::Moonstone, PieceType::GreatElderTree];
+/// every animal, in the order they're stored in CompactBoard::animals
+const ANIMALS: [Animal; 8] = [Animal::Boar, Animal::Hedgehog, Animal::Squirrel, Animal::Fox, Animal::Owl, Animal::Badger, Animal::Beaver, Animal::Turtle];
+/// every fertility, in the order they're stored in CompactBoard::fertility
+const FERTILITIES: [Fertility; 4] = [Fertility::OneLeaf, Fertility::TwoLeaf, Fertility::ThreeLeaf, Fertility::FourLeaf];
+/// every tree size, in the order they're stored in CompactBoard::trees
+const TREE_SIZES: [TreeSize; 4] = [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large];
+
+/// # CompactBoard
+///
+/// A copy of a Board stored entirely as bitboards, so that it can be copied around cheaply while searching for moves.
+///
+/// Each bitboard has one bit per spot, with the spot at (row,col) stored in bit row * cols + col, the same as ShadeBits.
+/// Since the board doesn't know who owns which tree color, converting to and from a Board takes the colors of the players in turn order.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct CompactBoard {
+	/// number of rows on the board
+	pub(crate) rows: u8,
+	/// number of cols on the board
+	pub(crate) cols: u8,
+	/// spots holding each type of piece, in the order of PIECE_TYPES
+	pub(crate) pieces: [u64; 6],
+	/// spots holding a tree of each size, indexed by TreeSize::size()
+	pub(crate) trees: [u64; 4],
+	/// spots holding a tree owned by each player, in turn order
+	pub(crate) owners: [u64; MAX_COMPACT_OWNERS],
+	/// spots holding each animal, in the order of ANIMALS
+	pub(crate) animals: [u64; 8],
+	/// spots with each fertility, in the order of FERTILITIES
+	pub(crate) fertility: [u64; 4],
+	/// spots which have been expended this turn
+	pub(crate) expended: u64,
+	/// the sun, exactly as it was on the board
+	pub(crate) sun: Sun,
+	/// the moon, exactly as it was on the board
+	pub(crate) moon: Moon,
+}//end struct CompactBoard
+
+#[allow(dead_code)]
+impl CompactBoard {
+	/// # from_board(board, colors)
+	///
+	/// Converts a board into bitboards. Colors should be the color of each player, in turn order, so that each tree can be given an owner.
+	///
+	/// Gives an error if the board is too big for a bitboard, there are too many players, or a tree doesn't belong to any of the players.
+	pub fn from_board(board: &Board, colors: &[(u8,u8,u8)]) -> Result<CompactBoard, String> {
+		let (rows, cols) = board.board.size();
+		if rows * cols > MAX_BITBOARD_SPOTS || rows > u8::MAX as usize || cols > u8::MAX as usize {
+			return Err(format!("A {}x{} board is too big to store in bitboards.", rows, cols));
+		}//end if board won't fit in a u64
+		if colors.len() > MAX_COMPACT_OWNERS {
+			return Err(format!("Compact boards can only keep track of {} players.", MAX_COMPACT_OWNERS));
+		}//end if there are too many players
+
+		let mut compact = CompactBoard {
+			rows: rows as u8,
+			cols: cols as u8,
+			pieces: [0; 6],
+			trees: [0; 4],
+			owners: [0; MAX_COMPACT_OWNERS],
+			animals: [0; 8],
+			fertility: [0; 4],
+			expended: 0,
+			sun: board.sun,
+			moon: board.moon,
+		};//end struct construction
+		for (index, spot) in board.board.iter().enumerate() {
+			let bit = 1u64 << index;
+			compact.pieces[PIECE_TYPES.iter().position(|piece| *piece == spot.piece_type).unwrap()] |= bit;
+			compact.fertility[FERTILITIES.iter().position(|fertility| *fertility == spot.fertility).unwrap()] |= bit;
+			if let Some(tree) = spot.tree {
+				let owner = colors.iter().position(|color| *color == tree.color).ok_or(format!("The tree at row {}, col {} doesn't belong to any player.", index / cols, index % cols))?;
+				compact.trees[tree.size.size()] |= bit;
+				compact.owners[owner] |= bit;
+			}//end if there's a tree here
+			if let Some(animal) = spot.animal {
+				compact.animals[ANIMALS.iter().position(|other| *other == animal).unwrap()] |= bit;
+			}//end if there's an animal here
+			if spot.is_expended() {
+				compact.expended |= bit;
+			}//end if spot is expended
+		}//end looping over each spot in row-major order
+		return Ok(compact);
+	}//end from_board(board, colors)
+
+	/// # to_board(self, colors)
+	///
+	/// Converts back into a board, giving each tree the color of its owner. Colors should be the same as were given to from_board().
+	pub fn to_board(self, colors: &[(u8,u8,u8)]) -> Result<Board, String> {
+		let mut grid: Grid<BoardSpot> = Grid::new(self.rows as usize, self.cols as usize);
+		for (index, spot) in grid.iter_mut().enumerate() {
+			let bit = 1u64 << index;
+			let find = |masks: &[u64]| masks.iter().position(|mask| mask & bit != 0);
+			spot.piece_type = PIECE_TYPES[find(&self.pieces).unwrap_or(0)];
+			spot.fertility = FERTILITIES[find(&self.fertility).unwrap_or(0)];
+			spot.animal = find(&self.animals).map(|animal| ANIMALS[animal]);
+			spot.expended = self.expended & bit != 0;
+			if let Some(size) = find(&self.trees) {
+				let owner = find(&self.owners).ok_or(format!("The tree at spot {} has no owner.", index))?;
+				let color = *colors.get(owner).ok_or(format!("There's no color for player {}.", owner + 1))?;
+				spot.tree = Some(Tree {color, size: TREE_SIZES[size]});
+			}//end if there's a tree here
+		}//end looping over each spot in row-major order
+		return Ok(Board {board: grid, sun: self.sun, moon: self.moon});
+	}//end to_board(self, colors)
+
+	/// # shade_bits(&self)
+	///
+	/// Pulls out the pieces which cast or receive shadows, for use with a LightEvaluator.
+	pub fn shade_bits(&self) -> ShadeBits {
+		// a spot holding a tree counts as a tree, whatever its piece type says, the same as ShadeBits::from_board()
+		let not_tree = !(self.trees[0] | self.trees[1] | self.trees[2] | self.trees[3]);
+		return ShadeBits {
+			trees: self.trees,
+			great_elder_tree: self.pieces[5] & not_tree,
+			moonstones: self.pieces[4] & not_tree,
+		};//end struct construction
+	}//end shade_bits(&self)
+
+	/// # sun_shaded(&self, evaluator)
+	///
+	/// Returns a bitboard of the spots in shade from the sun where it is now, following the same rules as Board::sun_shaded().
+	pub fn sun_shaded(&self, evaluator: &LightEvaluator) -> u64 {
+		return evaluator.shaded(&self.shade_bits(), self.sun.direction);
+	}//end sun_shaded(&self, evaluator)
+
+	/// # light_income(&self, evaluator, owner)
+	///
+	/// Returns the light points the given player would gain from photosynthesis with the sun where it is now.
+	pub fn light_income(&self, evaluator: &LightEvaluator, owner: usize) -> usize {
+		let bits = self.shade_bits();
+		let shaded = evaluator.shaded(&bits, self.sun.direction);
+		return evaluator.light_income(&bits, shaded, self.owners[owner]);
+	}//end light_income(&self, evaluator, owner)
+
+	/// # board_actions(&self, rules, owner, light_points, available)
+	///
+	/// Lists every planting, growing and collecting action the given player could carry out, following the same rules as GameState::apply().
+	///
+	/// ## parameters
+	/// rules : the rules being played with, for seed ranges and costs
+	/// owner : index of the player, in turn order
+	/// light_points : the light points that player has to spend
+	/// available : number of trees of each size in that player's available area, indexed by TreeSize::size()
+	pub fn board_actions(&self, rules: &RuleSet, owner: usize, light_points: usize, available: [usize; 4]) -> Vec<GameAction> {
+		let mut actions = Vec::new();
+		let cols = self.cols as usize;
+		let usable = self.owners[owner] & !self.expended;
+
+		// growing and collecting
+		for size in 0..4 {
+			let mut spots = usable & self.trees[size];
+			while spots != 0 {
+				let index = spots.trailing_zeros() as usize;
+				spots &= spots - 1;
+				if size == 3 && light_points >= rules.collect_cost {
+					actions.push(GameAction::Collect(index / cols, index % cols));
+				}//end if this large tree can be collected
+				else if size < 3 && available[size + 1] > 0 && light_points >= rules.grow_costs[size + 1] {
+					actions.push(GameAction::Grow(index / cols, index % cols));
+				}//end else if this tree can grow
+			}//end looping over each of this player's trees
+		}//end looping over each tree size
+
+		// planting seeds
+		if available[0] == 0 || light_points < rules.plant_cost {
+			return actions;
+		}//end if player can't plant anything
+		let empty = self.pieces[0] & !(self.trees[0] | self.trees[1] | self.trees[2] | self.trees[3]) & !self.expended;
+		let mut sources = usable & (self.trees[1] | self.trees[2] | self.trees[3]);
+		sources |= self.pieces[5] & !self.expended;
+		while sources != 0 {
+			let from = sources.trailing_zeros() as usize;
+			sources &= sources - 1;
+			let range = match (1..4).find(|size| self.trees[*size] & (1u64 << from) != 0) {
+				Some(size) => rules.seed_ranges[size],
+				None => rules.elder_seed_range,
+			};//end matching what's throwing the seed
+			let mut targets = self.reach(from, range) & empty;
+			while targets != 0 {
+				let to = targets.trailing_zeros() as usize;
+				targets &= targets - 1;
+				actions.push(GameAction::PlantSeed {from: (from / cols, from % cols), to: (to / cols, to % cols)});
+			}//end looping over each spot the seed could land on
+		}//end looping over each spot a seed could be thrown from
+		return actions;
+	}//end board_actions(&self, rules, owner, light_points, available)
+
+	/// # reach(&self, index, range)
+	///
+	/// Helper method giving every spot within range steps of the spot at index, counting diagonal steps as one, and not including that spot itself.
+	fn reach(&self, index: usize, range: usize) -> u64 {
+		let rows = self.rows as usize;
+		let cols = self.cols as usize;
+		let (row, col) = (index / cols, index % cols);
+		let mut reached = 0u64;
+		for other_row in row.saturating_sub(range)..(row + range + 1).min(rows) {
+			for other_col in col.saturating_sub(range)..(col + range + 1).min(cols) {
+				reached |= 1u64 << (other_row * cols + other_col);
+			}//end looping over cols in range
+		}//end looping over rows in range
+		return reached & !(1u64 << index);
+	}//end reach(&self, index, range)
+}//end impl for CompactBoard
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{GameState, default_player_color};
+	use crate::game::tests::{ALL_SUN_DIRECTIONS, XorShift, players, started_game};
+
+	/// # random_state(rng)
+	///
+	/// Makes a two player game partway through a turn, with random trees, animals, expended spots and light points.
+	fn random_state(rng: &mut XorShift) -> GameState {
+		let mut state = started_game(players(2));
+		state.board.sun = Sun::new(ALL_SUN_DIRECTIONS[rng.below(8)]);
+		state.board.moon = Moon::at_ring_index(7, rng.below(24), rng.below(2) == 0);
+		for _ in 0..rng.below(20) {
+			let owner = rng.below(2);
+			let spot = state.board.board.get_mut(rng.below(7), rng.below(7)).unwrap();
+			spot.tree = Some(Tree {color: default_player_color(owner), size: TREE_SIZES[rng.below(4)]});
+			spot.piece_type = PieceType::Tree;
+		}//end placing random trees
+		for _ in 0..rng.below(4) {
+			let spot = state.board.board.get_mut(rng.below(7), rng.below(7)).unwrap();
+			spot.animal = Some(ANIMALS[rng.below(8)]);
+		}//end placing random animals
+		if rng.below(2) == 0 {
+			let spot = state.board.board.get_mut(rng.below(7), rng.below(7)).unwrap();
+			spot.tree = None;
+			spot.piece_type = PieceType::GreatElderTree;
+		}//end if this game has a great elder tree
+		for _ in 0..rng.below(6) {
+			state.board.board.get_mut(rng.below(7), rng.below(7)).unwrap().expend();
+		}//end expending random spots
+		state.current_mut().light_points = rng.below(8);
+		for _ in 0..rng.below(4) {
+			state.current_mut().take_available(TREE_SIZES[rng.below(4)]);
+		}//end using up some available trees
+		return state;
+	}//end random_state(rng)
+
+	#[test]
+	fn compact_board_converts_back_losslessly() {
+		let mut rng = XorShift(0xA0761D6478BD642F);
+		let colors = [default_player_color(0), default_player_color(1)];
+		for _ in 0..200 {
+			let board = random_state(&mut rng).board;
+			let compact = CompactBoard::from_board(&board, &colors).unwrap();
+			assert_eq!(compact.to_board(&colors).unwrap(), board);
+		}//end trying random boards
+		let mut stranger = Board::default();
+		stranger.board.get_mut(0, 0).unwrap().tree = Some(Tree {color: (1,2,3), size: TreeSize::Seed});
+		assert!(CompactBoard::from_board(&stranger, &colors).is_err());
+	}//end compact_board_converts_back_losslessly()
+
+	#[test]
+	fn compact_board_shade_matches_board() {
+		let mut rng = XorShift(0xE7037ED1A0B428DB);
+		let colors = [default_player_color(0), default_player_color(1)];
+		let evaluator = LightEvaluator::new(7, 7).unwrap();
+		for _ in 0..200 {
+			let board = random_state(&mut rng).board;
+			let compact = CompactBoard::from_board(&board, &colors).unwrap();
+			assert_eq!(evaluator.to_grid(compact.sun_shaded(&evaluator)), board.sun_shaded());
+		}//end trying random boards
+	}//end compact_board_shade_matches_board()
+
+	#[test]
+	fn board_actions_match_what_game_state_allows() {
+		let mut rng = XorShift(0x8EBC6AF09C88C6E3);
+		for _ in 0..40 {
+			let state = random_state(&mut rng);
+			let colors: Vec<(u8,u8,u8)> = state.players.iter().map(|player| player.color).collect();
+			let compact = CompactBoard::from_board(&state.board, &colors).unwrap();
+			let player = state.current();
+			let available = [0, 1, 2, 3].map(|size| player.available_count(TREE_SIZES[size]));
+			let mut generated = compact.board_actions(&state.rules, state.current_player, player.light_points, available);
+
+			let mut allowed = Vec::new();
+			let coords: Vec<(usize,usize)> = (0..7).flat_map(|row| (0..7).map(move |col| (row, col))).collect();
+			for from in &coords {
+				for action in [GameAction::Grow(from.0, from.1), GameAction::Collect(from.0, from.1)] {
+					if state.clone().apply(action).is_ok() {
+						allowed.push(action);
+					}//end if game allows this action
+				}//end trying actions on one spot
+				for to in &coords {
+					let action = GameAction::PlantSeed {from: *from, to: *to};
+					if state.clone().apply(action).is_ok() {
+						allowed.push(action);
+					}//end if game allows this action
+				}//end trying every seed target
+			}//end trying every spot
+			generated.sort_by_key(|action| action.to_string());
+			allowed.sort_by_key(|action| action.to_string());
+			assert_eq!(generated, allowed, "{:?}", state.board);
+		}//end trying random games
+	}//end board_actions_match_what_game_state_allows()
+}//end mod tests
@@ -0,0 +1,529 @@
+use std::{fmt::Display, str::FromStr};
+
+use grid::Grid;
+
+use super::{Animal, BoardSpot, Fertility, GameAction, GameState, Moon, MoonDirection, PieceType, Player, RuleSet, Sun, SunDirection, Tree, TreeSize, player::{PlayerBoard, StoreSlot}, replay::LogEntry, rules::{toml_text, toml_value}};
+
+/*
+Everything in here turns game objects into lines of plain text and back again.
+
+The state of a game is written as a list of lines, where the first word or two of each line says which part of the game it describes.
+For example "spot 3 4 ..." describes the spot at row 3, col 4, and "player 1 ..." describes the second player.
+Because of this, the difference between two states is just the lines that changed, and applying those lines on top of an old state brings it up to date.
+*/
+
+impl Display for GameAction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			GameAction::Buy(size) => write!(f, "buy {}", size),
+			GameAction::PlantSeed {from, to} => write!(f, "seed {},{} {},{}", from.0, from.1, to.0, to.1),
+			GameAction::Grow(row, col) => write!(f, "grow {},{}", row, col),
+			GameAction::Collect(row, col) => write!(f, "collect {},{}", row, col),
+			GameAction::EndTurn => write!(f, "end"),
+			GameAction::PlaceGreatElderTree(row, col) => write!(f, "elder {},{}", row, col),
+			GameAction::PlaceMoonstone(row, col) => write!(f, "moonstone {},{}", row, col),
+			GameAction::PlaceStartingTree(row, col) => write!(f, "start {},{}", row, col),
+			GameAction::Undo => write!(f, "undo"),
+			GameAction::Redo => write!(f, "redo"),
+		}//end matching self
+	}//end fmt(&self, f)
+}//end impl Display for GameAction
+
+impl FromStr for GameAction {
+	type Err = String;
+
+	/// # from_str(s)
+	///
+	/// Reads an action in the same format that Display writes it.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let words: Vec<&str> = s.split_whitespace().collect();
+		match words.as_slice() {
+			["buy", size] => Ok(GameAction::Buy(parse_tree_size(size)?)),
+			["seed", from, to] => Ok(GameAction::PlantSeed {from: parse_coord(from)?, to: parse_coord(to)?}),
+			["grow", coord] => {
+				let (row, col) = parse_coord(coord)?;
+				Ok(GameAction::Grow(row, col))
+			},
+			["collect", coord] => {
+				let (row, col) = parse_coord(coord)?;
+				Ok(GameAction::Collect(row, col))
+			},
+			["end"] => Ok(GameAction::EndTurn),
+			["elder", coord] => {
+				let (row, col) = parse_coord(coord)?;
+				Ok(GameAction::PlaceGreatElderTree(row, col))
+			},
+			["moonstone", coord] => {
+				let (row, col) = parse_coord(coord)?;
+				Ok(GameAction::PlaceMoonstone(row, col))
+			},
+			["start", coord] => {
+				let (row, col) = parse_coord(coord)?;
+				Ok(GameAction::PlaceStartingTree(row, col))
+			},
+			["undo"] => Ok(GameAction::Undo),
+			["redo"] => Ok(GameAction::Redo),
+			_ => Err(format!("Couldn't read action \"{}\"", s)),
+		}//end matching words in action
+	}//end from_str(s)
+}//end impl FromStr for GameAction
+
+/// # encode_state(state)
+///
+/// Writes out the whole state of the game as lines of text, which can be read back in with apply_state_lines().
+pub(crate) fn encode_state(state: &GameState) -> Vec<String> {
+	let mut lines = Vec::new();
+	let board = &state.board;
+	lines.extend(encode_rules(&state.rules));
+	lines.push(format!("board {} {}", board.board.rows(), board.board.cols()));
+	lines.push(format!("sun {}", sun_direction_code(board.sun.direction)));
+	lines.push(format!("moon {}", encode_moon(&board.moon)));
+	lines.push(format!("round {} {} {} {} {} {}", state.round, state.max_rounds, state.turns_this_round, state.current_player, state.first_player, state.game_over as u8));
+	lines.push(format!("setup {}", state.starting_trees_left));
+	lines.push(format!("stacks {}", state.scoring_stacks.iter().map(|stack| encode_list(stack.iter())).collect::<Vec<String>>().join(" ")));
+	lines.push(format!("players {}", state.players.len()));
+	for (index, player) in state.players.iter().enumerate() {
+		lines.push(encode_player(index, player));
+	}//end adding a line for each player
+	for row in 0..board.board.rows() {
+		for col in 0..board.board.cols() {
+			lines.push(encode_spot(row, col, board.board.get(row, col).unwrap()));
+		}//end looping over cols
+	}//end looping over rows
+	lines.push(format!("logsize {}", state.log.len()));
+	for (index, entry) in state.log.iter().enumerate() {
+		lines.push(format!("log {} {}", index, encode_log_entry(entry)));
+	}//end adding a line for each log entry
+	return lines;
+}//end encode_state(state)
+
+/// # encode_rules(rules)
+///
+/// Writes out every setting in the rules as a line starting with "rule", followed by its key and value as they'd appear in a rules file.
+pub(crate) fn encode_rules(rules: &RuleSet) -> Vec<String> {
+	return rules.entries().iter().map(|(key, value)| format!("rule {} {}", key, toml_text(value))).collect();
+}//end encode_rules(rules)
+
+/// # decode_rule(rules, line)
+///
+/// Reads a line written by encode_rules() and changes that setting in rules.
+pub(crate) fn decode_rule(rules: &mut RuleSet, line: &str) -> Result<(), String> {
+	let bad_line = || format!("Couldn't read rule \"{}\"", line);
+	let mut parts = line.splitn(3, ' ');
+	if parts.next() != Some("rule") {
+		return Err(bad_line());
+	}//end if this isn't a rule line
+	let key = parts.next().ok_or_else(bad_line)?;
+	let value = toml_value(parts.next().ok_or_else(bad_line)?).map_err(|msg| format!("{}. Details:\n{}", bad_line(), msg))?;
+	return rules.set(key, &value);
+}//end decode_rule(rules, line)
+
+/// # state_diff(old, new)
+///
+/// Returns only the lines of new which are different from old, so that applying them on top of old will give new.
+///
+/// If the size of the board or the number of players changed, then every line is returned instead.
+pub(crate) fn state_diff(old: &GameState, new: &GameState) -> Vec<String> {
+	let old_lines = encode_state(old);
+	let new_lines = encode_state(new);
+	if old.board.board.size() != new.board.board.size() || old.players.len() != new.players.len() {
+		return new_lines;
+	}//end if the shape of things changed
+	return new_lines.into_iter().filter(|line| !old_lines.contains(line)).collect();
+}//end state_diff(old, new)
+
+/// # apply_state_lines(state, lines)
+///
+/// Reads each line and overwrites the matching part of the given state.
+///
+/// ## return
+/// Returns an error if any line couldn't be read. Lines before the bad one will have already been applied.
+pub(crate) fn apply_state_lines(state: &mut GameState, lines: &[String]) -> Result<(), String> {
+	for line in lines {
+		let words: Vec<&str> = line.split_whitespace().collect();
+		let bad_line = || format!("Couldn't read state line \"{}\"", line);
+		match words.as_slice() {
+			["board", rows, cols] => {
+				let rows = parse_num(rows)?;
+				let cols = parse_num(cols)?;
+				if state.board.board.size() != (rows, cols) {
+					state.board.board = Grid::new(rows, cols);
+				}//end if board needs to be resized
+			},
+			["rule", ..] => decode_rule(&mut state.rules, line)?,
+			["sun", code] => state.board.sun = Sun::new(sun_direction_from_code(parse_num(code)?)?),
+			["moon", moon @ ..] => state.board.moon = decode_moon(moon)?,
+			["round", round, max_rounds, turns, current, first, over] => {
+				state.round = parse_num(round)?;
+				state.max_rounds = parse_num(max_rounds)?;
+				state.turns_this_round = parse_num(turns)?;
+				state.current_player = parse_num(current)?;
+				state.first_player = parse_num(first)?;
+				state.game_over = parse_num(over)? == 1;
+			},
+			["setup", left] => state.starting_trees_left = parse_num(left)?,
+			["stacks", stacks @ ..] => {
+				state.scoring_stacks = stacks.iter().map(|stack| parse_list(stack, parse_num)).collect::<Result<Vec<Vec<usize>>, String>>()?;
+			},
+			["players", count] => {
+				let count = parse_num(count)?;
+				state.players.resize(count, Player::new("", (0,0,0)));
+			},
+			["player", index, ..] => {
+				let index = parse_num(index)?;
+				let player = decode_player(&words).ok_or_else(bad_line)?;
+				*state.players.get_mut(index).ok_or_else(bad_line)? = player;
+			},
+			["spot", row, col, ..] => {
+				let row = parse_num(row)?;
+				let col = parse_num(col)?;
+				let spot = decode_spot(&words).ok_or_else(bad_line)?;
+				*state.board.board.get_mut(row, col).ok_or_else(bad_line)? = spot;
+			},
+			["logsize", size] => state.log.truncate(parse_num(size)?),
+			["log", index, ..] => {
+				let index = parse_num(index)?;
+				let entry = decode_log_entry(&words[2..].join(" "))?;
+				match index.cmp(&state.log.len()) {
+					std::cmp::Ordering::Less => state.log[index] = entry,
+					std::cmp::Ordering::Equal => state.log.push(entry),
+					std::cmp::Ordering::Greater => return Err(bad_line()),
+				}//end matching where entry goes in the log
+			},
+			[] => {},
+			_ => return Err(bad_line()),
+		}//end matching first words of line
+	}//end looping over each line
+	state.rehash();
+	return Ok(());
+}//end apply_state_lines(state, lines)
+
+/// # encode_player(index, player)
+///
+/// Writes a player as a single line. The name goes last, since it might have spaces in it.
+fn encode_player(index: usize, player: &Player) -> String {
+	let board = &player.player_board;
+	let columns = [&board.seeds, &board.small, &board.medium, &board.large]
+		.iter()
+		.map(|column| encode_list(column.iter().map(|slot| format!("{}{}", slot.price, if slot.filled {"+"} else {"-"}))))
+		.collect::<Vec<String>>()
+		.join(" ");
+	let available = encode_list(player.available.iter().map(|tree| tree.size));
+	let tokens = encode_list(player.scoring_tokens.iter().map(|token| format!("{}:{}", token.0.as_usize(), token.1)));
+	return format!("player {} {} {} {} {} {} {} {}", index, encode_color(player.color), player.light_points, player.lunar_points, columns, available, tokens, player.name);
+}//end encode_player(index, player)
+
+/// # decode_player(words)
+///
+/// Reads a player back from the words of a line written by encode_player().
+fn decode_player(words: &[&str]) -> Option<Player> {
+	let color = decode_color(words.get(2)?)?;
+	let decode_column = |word: &str| parse_list(word, |slot| {
+		let (price, filled) = slot.strip_suffix('+').map(|price| (price, true))
+			.or_else(|| slot.strip_suffix('-').map(|price| (price, false)))
+			.ok_or(format!("\"{}\" should be a price followed by + or -", slot))?;
+		Ok(StoreSlot {price: parse_num(price)?, filled})
+	}).ok();
+	let player_board = PlayerBoard {
+		seeds: decode_column(words.get(5)?)?,
+		small: decode_column(words.get(6)?)?,
+		medium: decode_column(words.get(7)?)?,
+		large: decode_column(words.get(8)?)?,
+	};//end struct construction
+	let available = parse_list(words.get(9)?, |size| Ok(Tree {color, size: parse_tree_size(size)?})).ok()?;
+	let scoring_tokens = parse_list(words.get(10)?, |token| {
+		let (fertility, value) = token.split_once(':').ok_or("missing :".to_string())?;
+		Ok((fertility_from_code(parse_num(fertility)?)?, parse_num(value)?))
+	}).ok()?;
+	return Some(Player {
+		name: words.get(11..).unwrap_or_default().join(" "),
+		color,
+		light_points: parse_num(words.get(3)?).ok()?,
+		lunar_points: parse_num(words.get(4)?).ok()?,
+		player_board,
+		available,
+		scoring_tokens,
+	});//end struct construction
+}//end decode_player(words)
+
+/// # encode_spot(row, col, spot)
+///
+/// Writes a single board spot as a line.
+fn encode_spot(row: usize, col: usize, spot: &BoardSpot) -> String {
+	let tree = match spot.tree {
+		Some(tree) => format!("{}:{}", encode_color(tree.color), tree.size),
+		None => "_".to_string(),
+	};//end matching whether there's a tree
+	let animal = match spot.animal {
+		Some(animal) => animal.to_string(),
+		None => "_".to_string(),
+	};//end matching whether there's an animal
+	return format!("spot {} {} {} {} {} {} {}", row, col, piece_type_code(spot.piece_type), tree, animal, spot.fertility.as_usize(), spot.expended as u8);
+}//end encode_spot(row, col, spot)
+
+/// # decode_spot(words)
+///
+/// Reads a single board spot back from the words of a line written by encode_spot().
+fn decode_spot(words: &[&str]) -> Option<BoardSpot> {
+	let tree = match *words.get(4)? {
+		"_" => None,
+		tree => {
+			let (color, size) = tree.split_once(':')?;
+			Some(Tree {color: decode_color(color)?, size: parse_tree_size(size).ok()?})
+		},
+	};//end matching whether there's a tree
+	let animal = match *words.get(5)? {
+		"_" => None,
+		animal => Some(parse_animal(animal).ok()?),
+	};//end matching whether there's an animal
+	return Some(BoardSpot {
+		piece_type: piece_type_from_code(parse_num(words.get(3)?).ok()?).ok()?,
+		tree,
+		animal,
+		fertility: fertility_from_code(parse_num(words.get(6)?).ok()?).ok()?,
+		expended: parse_num(words.get(7)?).ok()? == 1,
+	});//end struct construction
+}//end decode_spot(words)
+
+/// # encode_log_entry(entry)
+///
+/// Writes a single log entry as a line, which can be read back in with decode_log_entry().
+pub(crate) fn encode_log_entry(entry: &LogEntry) -> String {
+	match entry {
+		LogEntry::Action {player, action} => format!("action {} {}", player, action),
+		LogEntry::LightGained {round, gained} => format!("light {} {}", round, encode_list(gained.iter())),
+		LogEntry::LunarGained {round, gained} => format!("lunar {} {}", round, encode_list(gained.iter())),
+		LogEntry::ElderTreeBonus {gained} => format!("elderbonus {}", encode_list(gained.iter())),
+		LogEntry::TokenTaken {player, fertility, value} => format!("token {} {} {}", player, fertility.as_usize(), value),
+		LogEntry::SunMoved(direction) => format!("sun {}", sun_direction_code(*direction)),
+		LogEntry::MoonMoved(moon) => format!("moon {}", encode_moon(moon)),
+		LogEntry::GameOver => "over".to_string(),
+	}//end matching entry
+}//end encode_log_entry(entry)
+
+/// # decode_log_entry(line)
+///
+/// Reads a single log entry written by encode_log_entry().
+pub(crate) fn decode_log_entry(line: &str) -> Result<LogEntry, String> {
+	let words: Vec<&str> = line.split_whitespace().collect();
+	match words.as_slice() {
+		["action", player, action @ ..] => Ok(LogEntry::Action {player: parse_num(player)?, action: action.join(" ").parse()?}),
+		["light", round, gained] => Ok(LogEntry::LightGained {round: parse_num(round)?, gained: parse_list(gained, parse_num)?}),
+		["lunar", round, gained] => Ok(LogEntry::LunarGained {round: parse_num(round)?, gained: parse_list(gained, parse_num)?}),
+		["elderbonus", gained] => Ok(LogEntry::ElderTreeBonus {gained: parse_list(gained, parse_num)?}),
+		["token", player, fertility, value] => Ok(LogEntry::TokenTaken {
+			player: parse_num(player)?,
+			fertility: fertility_from_code(parse_num(fertility)?)?,
+			value: parse_num(value)?,
+		}),
+		["sun", code] => Ok(LogEntry::SunMoved(sun_direction_from_code(parse_num(code)?)?)),
+		["moon", moon @ ..] => Ok(LogEntry::MoonMoved(decode_moon(moon)?)),
+		["over"] => Ok(LogEntry::GameOver),
+		_ => Err(format!("Couldn't read log entry \"{}\"", line)),
+	}//end matching words in entry
+}//end decode_log_entry(line)
+
+/// # encode_moon(moon)
+///
+/// Writes the position of the moon as words separated by spaces.
+fn encode_moon(moon: &Moon) -> String {
+	return format!("{} {} {} {} {} {} {}", moon_direction_code(moon.direction), moon.row1, moon.col1, moon.row2, moon.col2, moon.full_moon as u8, moon.grid_side_length);
+}//end encode_moon(moon)
+
+/// # decode_moon(words)
+///
+/// Reads the moon back from the words written by encode_moon().
+fn decode_moon(words: &[&str]) -> Result<Moon, String> {
+	match words {
+		[direction, row1, col1, row2, col2, full, side] => Ok(Moon {
+			direction: moon_direction_from_code(parse_num(direction)?)?,
+			row1: parse_num(row1)?,
+			col1: parse_num(col1)?,
+			row2: parse_num(row2)?,
+			col2: parse_num(col2)?,
+			full_moon: parse_num(full)? == 1,
+			grid_side_length: parse_num(side)?,
+		}),//end struct construction
+		_ => Err(format!("Couldn't read moon \"{}\"", words.join(" "))),
+	}//end matching words of moon
+}//end decode_moon(words)
+
+/// # encode_list(items)
+///
+/// Writes a list as a single word, with items separated by commas. An empty list is written as "_".
+fn encode_list<T: Display>(items: impl Iterator<Item = T>) -> String {
+	let items: Vec<String> = items.map(|item| item.to_string()).collect();
+	if items.is_empty() {
+		return "_".to_string();
+	}//end if there's nothing in the list
+	return items.join(",");
+}//end encode_list(items)
+
+/// # parse_list(word, parse_item)
+///
+/// Reads a list written by encode_list(), using parse_item to read each item.
+fn parse_list<T>(word: &str, parse_item: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+	if word == "_" {
+		return Ok(Vec::new());
+	}//end if the list is empty
+	return word.split(',').map(parse_item).collect();
+}//end parse_list(word, parse_item)
+
+/// # parse_num(word)
+///
+/// Reads a number, giving an error message that says what couldn't be read.
+pub(crate) fn parse_num(word: &str) -> Result<usize, String> {
+	return word.parse().map_err(|_| format!("\"{}\" isn't a number", word));
+}//end parse_num(word)
+
+/// # parse_coord(word)
+///
+/// Reads a coordinate written like "row,col".
+pub(crate) fn parse_coord(word: &str) -> Result<(usize, usize), String> {
+	let (row, col) = word.split_once(',').ok_or(format!("\"{}\" isn't a row,col coordinate", word))?;
+	return Ok((parse_num(row)?, parse_num(col)?));
+}//end parse_coord(word)
+
+/// # encode_color(color)
+pub(crate) fn encode_color(color: (u8,u8,u8)) -> String {
+	return format!("{},{},{}", color.0, color.1, color.2);
+}//end encode_color(color)
+
+/// # decode_color(word)
+pub(crate) fn decode_color(word: &str) -> Option<(u8,u8,u8)> {
+	let parts: Vec<u8> = word.split(',').map(|part| part.parse().ok()).collect::<Option<Vec<u8>>>()?;
+	match parts.as_slice() {
+		[r, g, b] => Some((*r, *g, *b)),
+		_ => None,
+	}//end matching number of color parts
+}//end decode_color(word)
+
+/// # parse_tree_size(word)
+///
+/// Reads a tree size in the same format that Display writes it.
+pub(crate) fn parse_tree_size(word: &str) -> Result<TreeSize, String> {
+	match word {
+		"Seed" => Ok(TreeSize::Seed),
+		"Small" => Ok(TreeSize::Small),
+		"Medium" => Ok(TreeSize::Medium),
+		"Large" => Ok(TreeSize::Large),
+		_ => Err(format!("\"{}\" isn't a tree size", word)),
+	}//end matching word
+}//end parse_tree_size(word)
+
+/// # parse_animal(word)
+///
+/// Reads an animal in the same format that Display writes it.
+fn parse_animal(word: &str) -> Result<Animal, String> {
+	let animals = [Animal::Boar, Animal::Hedgehog, Animal::Squirrel, Animal::Fox, Animal::Owl, Animal::Badger, Animal::Beaver, Animal::Turtle];
+	return animals.into_iter().find(|animal| animal.to_string() == word).ok_or(format!("\"{}\" isn't an animal", word));
+}//end parse_animal(word)
+
+/// # fertility_from_code(code)
+fn fertility_from_code(code: usize) -> Result<Fertility, String> {
+	match code {
+		1 => Ok(Fertility::OneLeaf),
+		2 => Ok(Fertility::TwoLeaf),
+		3 => Ok(Fertility::ThreeLeaf),
+		4 => Ok(Fertility::FourLeaf),
+		_ => Err(format!("{} isn't a fertility", code)),
+	}//end matching code
+}//end fertility_from_code(code)
+
+/// # piece_type_code(piece_type)
+fn piece_type_code(piece_type: PieceType) -> usize {
+	match piece_type {
+		PieceType::Empty => 0,
+		PieceType::Tree => 1,
+		PieceType::Animal => 2,
+		PieceType::Dam => 3,
+		PieceType::Moonstone => 4,
+		PieceType::GreatElderTree => 5,
+	}//end matching piece type
+}//end piece_type_code(piece_type)
+
+/// # piece_type_from_code(code)
+fn piece_type_from_code(code: usize) -> Result<PieceType, String> {
+	match code {
+		0 => Ok(PieceType::Empty),
+		1 => Ok(PieceType::Tree),
+		2 => Ok(PieceType::Animal),
+		3 => Ok(PieceType::Dam),
+		4 => Ok(PieceType::Moonstone),
+		5 => Ok(PieceType::GreatElderTree),
+		_ => Err(format!("{} isn't a piece type", code)),
+	}//end matching code
+}//end piece_type_from_code(code)
+
+/// # sun_direction_code(direction)
+fn sun_direction_code(direction: SunDirection) -> usize {
+	match direction {
+		SunDirection::North => 0,
+		SunDirection::Northeast => 1,
+		SunDirection::East => 2,
+		SunDirection::Southeast => 3,
+		SunDirection::South => 4,
+		SunDirection::Southwest => 5,
+		SunDirection::West => 6,
+		SunDirection::Northwest => 7,
+	}//end matching direction
+}//end sun_direction_code(direction)
+
+/// # sun_direction_from_code(code)
+fn sun_direction_from_code(code: usize) -> Result<SunDirection, String> {
+	match code {
+		0 => Ok(SunDirection::North),
+		1 => Ok(SunDirection::Northeast),
+		2 => Ok(SunDirection::East),
+		3 => Ok(SunDirection::Southeast),
+		4 => Ok(SunDirection::South),
+		5 => Ok(SunDirection::Southwest),
+		6 => Ok(SunDirection::West),
+		7 => Ok(SunDirection::Northwest),
+		_ => Err(format!("{} isn't a sun direction", code)),
+	}//end matching code
+}//end sun_direction_from_code(code)
+
+/// # moon_direction_code(direction)
+fn moon_direction_code(direction: MoonDirection) -> usize {
+	match direction {
+		MoonDirection::South => 0,
+		MoonDirection::East => 1,
+		MoonDirection::North => 2,
+		MoonDirection::West => 3,
+	}//end matching direction
+}//end moon_direction_code(direction)
+
+/// # moon_direction_from_code(code)
+fn moon_direction_from_code(code: usize) -> Result<MoonDirection, String> {
+	match code {
+		0 => Ok(MoonDirection::South),
+		1 => Ok(MoonDirection::East),
+		2 => Ok(MoonDirection::North),
+		3 => Ok(MoonDirection::West),
+		_ => Err(format!("{} isn't a moon direction", code)),
+	}//end matching code
+}//end moon_direction_from_code(code)
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::tests::{players, started_game};
+
+	#[test]
+	fn bad_player_board_slots_are_rejected() {
+		let state = started_game(players(2));
+		let line = encode_player(0, &state.players[0]);
+		let words: Vec<&str> = line.split(' ').collect();
+		assert_eq!(decode_player(&words), Some(state.players[0].clone()));
+
+		for column in ["1+,,2-", "1+,2*", "1+,2é", "+"] {
+			let mut bad = words.clone();
+			bad[5] = column;
+			assert_eq!(decode_player(&bad), None, "{}", column);
+		}//end trying each bad column
+		let mut copy = state.clone();
+		let bad_line = line.replacen(words[5], "1+,,2-", 1);
+		assert!(apply_state_lines(&mut copy, &[bad_line]).is_err());
+	}//end bad_player_board_slots_are_rejected()
+}//end mod tests
@@ -0,0 +1,1048 @@
+use super::{Board, Fertility, LogEntry, PieceType, Player, RuleSet, Tree, TreeSize, get_adjacent_coords, player::PlayerBoard, zobrist::{full_hash, player_hash, progress_hash, spot_hash, stacks_hash}};
+
+/// # GameAction
+///
+/// Each of the things a player can do during their turn.
+///
+/// Every change to the game made by a player should go through one of these, so that everything a player does is checked against the rules in one place.
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum GameAction {
+	/// Buy a tree of this size from the player board and put it in the available area.
+	Buy(TreeSize),
+	/// Plant a seed from the available area, using the tree at `from` to throw it onto the empty spot at `to`.
+	PlantSeed {from: (usize,usize), to: (usize,usize)},
+	/// Grow the tree at (row,col) up by one size, using a tree from the available area.
+	Grow(usize,usize),
+	/// Collect the large tree at (row,col), taking a scoring token based on that spot's fertility.
+	Collect(usize,usize),
+	/// End the current player's turn.
+	EndTurn,
+	/// Put the Great Elder Tree on the empty four leaf spot at (row,col), moving any animal there to a spot next to it. This can only be done while setting up the game.
+	PlaceGreatElderTree(usize,usize),
+	/// Put a moonstone on the empty spot at (row,col). This can only be done while setting up the game.
+	PlaceMoonstone(usize,usize),
+	/// Put a small tree from the available area on the empty edge spot at (row,col), then pass to the next player. This can only be done while setting up the game.
+	PlaceStartingTree(usize,usize),
+	/// Take back the last action made this turn.
+	Undo,
+	/// Carry out the last action that was taken back with Undo.
+	Redo,
+}//end enum GameAction
+
+/// # TurnSnapshot
+///
+/// Everything an action can change during a player's turn, saved from just before that action so that it can be undone.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TurnSnapshot {
+	/// the board before the action
+	board: Board,
+	/// the current player before the action, including their light points and player board
+	player: Player,
+	/// the scoring stacks before the action
+	scoring_stacks: Vec<Vec<usize>>,
+	/// the length of the log before the action
+	log_size: usize,
+	/// the hash of the game before the action
+	hash: u64,
+}//end struct TurnSnapshot
+
+/// # GameState
+///
+/// This struct holds the whole state of a game in progress, including the board and every player.
+///
+/// The rules of the game are carried out through apply(), which is what the gui should call whenever a player tries to do something.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GameState {
+	/// the board everything is played on
+	pub(crate) board: Board,
+	/// every player in the game, in turn order
+	pub(crate) players: Vec<Player>,
+	/// index of the player whose turn it is
+	pub(crate) current_player: usize,
+	/// index of the player who goes first this round
+	pub(crate) first_player: usize,
+	/// number of turns which have been finished so far this round
+	pub(crate) turns_this_round: usize,
+	/// the current round, starting at 1
+	pub(crate) round: usize,
+	/// the number of rounds to play before the game ends
+	pub(crate) max_rounds: usize,
+	/// stacks of scoring tokens, one stack for each fertility. The last token in each stack is the top of that stack.
+	pub(crate) scoring_stacks: Vec<Vec<usize>>,
+	/// whether or not the last round has finished
+	pub(crate) game_over: bool,
+	/// everything that has happened so far this game, in order
+	pub(crate) log: Vec<LogEntry>,
+	/// snapshots from before each action taken this turn, with the most recent last
+	pub(crate) undo_stack: Vec<TurnSnapshot>,
+	/// actions which were undone this turn and can be redone, with the most recently undone last
+	pub(crate) redo_stack: Vec<GameAction>,
+	/// zobrist hash of the game, which apply() keeps up to date. See zobrist() for what it covers.
+	pub(crate) hash: u64,
+	/// the numbers used for costs, shade, scoring and so on, which can be changed for house rules
+	pub(crate) rules: RuleSet,
+	/// starting trees which still need to be placed before the first turn. Nothing else can be done until this reaches 0.
+	pub(crate) starting_trees_left: usize,
+}//end struct GameState
+
+#[allow(dead_code)]
+impl GameState {
+	/// # new(players)
+	///
+	/// Creates a new game for the given players, with a fresh board.
+	///
+	/// The game starts with each player placing their starting trees, see place_starting_tree(). The photosynthesis phase for the first round runs once they're all placed.
+	///
+	/// The game is played with the official rules, so it lasts for three full revolutions of the sun.
+	pub fn new(players: Vec<Player>) -> GameState {
+		return GameState::with_rules(players, RuleSet::default());
+	}//end new(players)
+
+	/// # with_rules(players, rules)
+	///
+	/// Same as new(), but playing with the given rules. Each player's board and available area are refilled from the rules, and the board is the size the rules ask for.
+	pub fn with_rules(mut players: Vec<Player>, rules: RuleSet) -> GameState {
+		let mut board = Board::with_size(rules.board_size);
+		board.initialize_board();
+
+		let mut scoring_stacks = rules.scoring_stacks.to_vec();
+		// the four leaf stack is only used with enough players
+		if players.len() < rules.four_leaf_min_players {
+			scoring_stacks[3].clear();
+		}//end if we don't have enough players for four leaf tokens
+		let prices = &rules.store_prices;
+		for player in players.iter_mut() {
+			player.player_board = PlayerBoard::from_prices(&prices[0], &prices[1], &prices[2], &prices[3]);
+			player.available.clear();
+			for (size, count) in [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large].into_iter().zip(rules.starting_available) {
+				player.available.extend((0..count).map(|_| Tree {color: player.color, size}));
+			}//end adding each size of tree to available area
+		}//end filling each player board and available area
+
+		let starting_trees_left = players.len() * rules.starting_trees;
+		let mut state = GameState {
+			board,
+			players,
+			current_player: 0,
+			first_player: 0,
+			turns_this_round: 0,
+			round: 1,
+			max_rounds: rules.revolutions * 8,
+			scoring_stacks,
+			game_over: false,
+			log: Vec::new(),
+			undo_stack: Vec::new(),
+			redo_stack: Vec::new(),
+			hash: 0,
+			starting_trees_left,
+			rules,
+		};//end struct construction
+		if state.starting_trees_left == 0 {
+			state.photosynthesis();
+		}//end if there's no setting up to do
+		state.rehash();
+		return state;
+	}//end with_rules(players, rules)
+
+	/// # current(&self)
+	///
+	/// Returns the player whose turn it is.
+	pub fn current(&self) -> &Player {
+		return self.players.get(self.current_player).unwrap();
+	}//end current(&self)
+
+	/// # current_mut(&mut self)
+	///
+	/// Returns the player whose turn it is.
+	pub fn current_mut(&mut self) -> &mut Player {
+		return self.players.get_mut(self.current_player).unwrap();
+	}//end current_mut(&mut self)
+
+	/// # owner_of(&self, tree)
+	///
+	/// Returns the index of the player who owns the given tree, based on the color of the tree.
+	pub fn owner_of(&self, tree: &Tree) -> Option<usize> {
+		return self.players.iter().position(|player| player.color == tree.color);
+	}//end owner_of(&self, tree)
+
+	/// # zobrist(&self)
+	///
+	/// Returns a hash of the game, covering the board, the sun and moon, every player's points and trees, the scoring stacks, and whose turn it is.
+	///
+	/// This is kept up to date as actions are applied, so it's quick enough to call for every position in a search.
+	/// If the state is changed without going through apply(), call rehash() afterwards.
+	pub fn zobrist(&self) -> u64 {
+		return self.hash;
+	}//end zobrist(&self)
+
+	/// # rehash(&mut self)
+	///
+	/// Works out the hash again from scratch, for after the state has been changed without going through apply().
+	pub fn rehash(&mut self) {
+		self.hash = full_hash(self);
+	}//end rehash(&mut self)
+
+	/// # action_hash(&self, spots)
+	///
+	/// Helper method giving the hash of just the parts of the game a single action can change, which are the given spots, the current player and the scoring stacks.
+	fn action_hash(&self, spots: &[(usize,usize)]) -> u64 {
+		let cols = self.board.board.cols();
+		let mut hash = player_hash(self.current(), self.current_player) ^ stacks_hash(&self.scoring_stacks);
+		for (row, col) in spots {
+			if let Some(spot) = self.board.board.get(*row, *col) {
+				hash ^= spot_hash(spot, row * cols + col, &self.players);
+			}//end if spot is on the board
+		}//end looping over spots the action touches
+		return hash;
+	}//end action_hash(&self, spots)
+
+	/// # passing_hash(&self, spots)
+	///
+	/// Helper method giving the hash of the parts of the game that passing to another player can change.
+	/// That's where the game is up to, every player, since a new round gives everyone light, and the given spots.
+	fn passing_hash(&self, spots: &[(usize,usize)]) -> u64 {
+		let cols = self.board.board.cols();
+		let mut hash = progress_hash(self) ^ stacks_hash(&self.scoring_stacks);
+		for (index, player) in self.players.iter().enumerate() {
+			hash ^= player_hash(player, index);
+		}//end looping over each player
+		for (row, col) in spots {
+			if let Some(spot) = self.board.board.get(*row, *col) {
+				hash ^= spot_hash(spot, row * cols + col, &self.players);
+			}//end if spot is on the board
+		}//end looping over spots the action touches
+		return hash;
+	}//end passing_hash(&self, spots)
+
+	/// # apply(&mut self, action)
+	///
+	/// Carries out the given action for the current player.
+	///
+	/// Every action that is allowed gets added to the log, before anything that happens because of it.
+	/// Undo and Redo aren't logged, instead they take actions back out of the log or put them back in.
+	///
+	/// ## return
+	/// Returns an error message describing why the action isn't allowed, if it isn't. In that case, the state of the game is left unchanged.
+	pub fn apply(&mut self, action: GameAction) -> Result<(), String> {
+		match action {
+			GameAction::Undo => return self.undo(),
+			GameAction::Redo => return self.redo(),
+			_ => {},
+		}//end matching whether action moves through history
+		self.redo_stack.clear();
+		return self.apply_to_history(action);
+	}//end apply(&mut self, action)
+
+	/// # can_undo(&self)
+	///
+	/// Returns whether there's an action this turn that can be taken back.
+	pub fn can_undo(&self) -> bool {
+		return !self.undo_stack.is_empty();
+	}//end can_undo(&self)
+
+	/// # can_redo(&self)
+	///
+	/// Returns whether there's an undone action that can be carried out again.
+	pub fn can_redo(&self) -> bool {
+		return !self.redo_stack.is_empty();
+	}//end can_redo(&self)
+
+	/// # undo(&mut self)
+	///
+	/// Takes back the last action made this turn, restoring the board, the current player and the scoring stacks to how they were before it.
+	fn undo(&mut self) -> Result<(), String> {
+		let snapshot = self.undo_stack.pop().ok_or("There's nothing to undo this turn.".to_string())?;
+		if let Some(LogEntry::Action {action, ..}) = self.log.get(snapshot.log_size) {
+			self.redo_stack.push(*action);
+		}//end if we know which action is being undone
+		self.board = snapshot.board;
+		*self.current_mut() = snapshot.player;
+		self.scoring_stacks = snapshot.scoring_stacks;
+		self.log.truncate(snapshot.log_size);
+		self.hash = snapshot.hash;
+		return Ok(());
+	}//end undo(&mut self)
+
+	/// # redo(&mut self)
+	///
+	/// Carries out the last action that was undone this turn.
+	fn redo(&mut self) -> Result<(), String> {
+		let action = self.redo_stack.pop().ok_or("There's nothing to redo.".to_string())?;
+		let result = self.apply_to_history(action);
+		if result.is_err() {
+			self.redo_stack.push(action);
+		}//end if action couldn't be redone
+		return result;
+	}//end redo(&mut self)
+
+	/// # apply_to_history(&mut self, action)
+	///
+	/// Helper method which carries out an action, saving a snapshot so it can be undone until the end of the turn.
+	fn apply_to_history(&mut self, action: GameAction) -> Result<(), String> {
+		if self.game_over {
+			return Err("The game is already over.".to_string());
+		}//end if game has already ended
+		if self.starting_trees_left > 0 && matches!(action, GameAction::Buy(_) | GameAction::PlantSeed {..} | GameAction::Grow(..) | GameAction::Collect(..) | GameAction::EndTurn) {
+			return Err(format!("{} needs to place a starting tree on the edge of the board first.", self.current().name));
+		}//end if players are still placing their starting trees
+
+		let log_size = self.log.len();
+		let snapshot = TurnSnapshot {
+			board: self.board.clone(),
+			player: self.current().clone(),
+			scoring_stacks: self.scoring_stacks.clone(),
+			log_size,
+			hash: self.hash,
+		};//end struct construction
+		let touched = match action {
+			GameAction::PlantSeed {from, to} => vec![from, to],
+			GameAction::Grow(row, col) | GameAction::Collect(row, col) | GameAction::PlaceMoonstone(row, col) | GameAction::PlaceStartingTree(row, col) => vec![(row, col)],
+			// an animal on the Great Elder Tree's spot moves next to it
+			GameAction::PlaceGreatElderTree(row, col) => {
+				let (rows, cols) = self.board.board.size();
+				let mut spots = if row < rows && col < cols {get_adjacent_coords(row, col, rows - 1, cols - 1, true)} else {Vec::new()};
+				spots.push((row, col));
+				spots
+			},
+			// ending a turn clears every expended spot
+			GameAction::EndTurn => {
+				let cols = self.board.board.cols();
+				self.board.board.iter().enumerate().filter(|(_, spot)| spot.is_expended()).map(|(index, _)| (index / cols, index % cols)).collect()
+			},
+			_ => Vec::new(),
+		};//end matching which spots the action could change
+		// ending a turn or placing a starting tree passes to another player, which can change more than the current player
+		let passes_turn = matches!(action, GameAction::EndTurn | GameAction::PlaceStartingTree(..));
+		let hash_of = |state: &GameState| if passes_turn {state.passing_hash(&touched)} else {state.action_hash(&touched)};
+		let hash_before = hash_of(self);
+		// debug builds check that passing the turn changes the hash the same way as working it out from scratch, even if it was already stale
+		let full_before = if cfg!(debug_assertions) && passes_turn {full_hash(self)} else {0};
+		self.log.push(LogEntry::Action {player: self.current_player, action});
+		let result = match action {
+			GameAction::Buy(size) => self.buy(size),
+			GameAction::PlantSeed {from, to} => self.plant_seed(from, to),
+			GameAction::Grow(row, col) => self.grow(row, col),
+			GameAction::Collect(row, col) => self.collect(row, col),
+			GameAction::PlaceGreatElderTree(row, col) => self.place_great_elder_tree(row, col),
+			GameAction::PlaceMoonstone(row, col) => self.place_moonstone(row, col),
+			GameAction::PlaceStartingTree(row, col) => self.place_starting_tree(row, col),
+			GameAction::EndTurn => {
+				self.end_turn();
+				Ok(())
+			},
+			GameAction::Undo | GameAction::Redo => Err("Undo and redo can't be saved in the history.".to_string()),
+		};//end matching action
+		match result {
+			Ok(()) => {
+				self.hash ^= hash_before ^ hash_of(self);
+				debug_assert!(!passes_turn || hash_before ^ hash_of(self) == full_before ^ full_hash(self), "the hash wasn't kept up to date by {}", action);
+				if !passes_turn {
+					self.undo_stack.push(snapshot);
+				}//end if action can be undone
+			},
+			Err(_) => self.log.truncate(log_size),
+		}//end matching whether action was allowed
+		return result;
+	}//end apply_to_history(&mut self, action)
+
+	/// # spend_light(&mut self, cost)
+	///
+	/// Helper method to take light points from the current player, or give an error message if they don't have enough.
+	fn spend_light(&mut self, cost: usize) -> Result<(), String> {
+		let player = self.current_mut();
+		if player.light_points < cost {
+			return Err(format!("{} needs {} light points, but only has {}.", player.name, cost, player.light_points));
+		}//end if player can't afford this
+		player.light_points -= cost;
+		return Ok(());
+	}//end spend_light(&mut self, cost)
+
+	/// # owned_tree_at(&self, row, col)
+	///
+	/// Helper method which gets the tree at the given spot, checking that it belongs to the current player and hasn't been used this turn.
+	fn owned_tree_at(&self, row: usize, col: usize) -> Result<Tree, String> {
+		let spot = self.board.board.get(row, col).ok_or("That spot isn't on the board.".to_string())?;
+		let tree = spot.tree.ok_or("There isn't a tree on that spot.".to_string())?;
+		if tree.color != self.current().color {
+			return Err("That tree belongs to another player.".to_string());
+		}//end if tree isn't owned by current player
+		if spot.is_expended() {
+			return Err("That spot has already been used this turn.".to_string());
+		}//end if spot is expended
+		return Ok(tree);
+	}//end owned_tree_at(&self, row, col)
+
+	/// # buy(&mut self, size)
+	///
+	/// Buys a tree from the current player's board, paying the price of the cheapest filled slot.
+	fn buy(&mut self, size: TreeSize) -> Result<(), String> {
+		let price = match self.current().player_board.next_price(size) {
+			Some(price) => price,
+			None => return Err(format!("There are no {} trees left to buy.", size)),
+		};//end matching whether there's a tree left to buy
+		self.spend_light(price)?;
+
+		let player = self.current_mut();
+		player.player_board.take(size);
+		player.available.push(Tree {color: player.color, size});
+		return Ok(());
+	}//end buy(&mut self, size)
+
+	/// # plant_seed(&mut self, from, to)
+	///
+	/// Plants a seed from the available area on an empty spot within range of one of the current player's trees.
+	///
+	/// A tree can throw a seed as many spaces away as its height, so a small tree can seed one space away, and a large tree three spaces away.
+	///
+	/// Any player can also use the Great Elder Tree to throw a seed, as far as a large tree could. Like any other spot, it can only be used once per turn.
+	///
+	/// Ranges and the cost of planting come from the rules.
+	fn plant_seed(&mut self, from: (usize,usize), to: (usize,usize)) -> Result<(), String> {
+		let (source_name, range) = match self.board.board.get(from.0, from.1) {
+			Some(spot) if spot.piece_type == PieceType::GreatElderTree => {
+				if spot.is_expended() {
+					return Err("The Great Elder Tree has already been used this turn.".to_string());
+				}//end if great elder tree is expended
+				("The Great Elder Tree".to_string(), self.rules.elder_seed_range)
+			},
+			_ => {
+				let source_tree = self.owned_tree_at(from.0, from.1)?;
+				(format!("A {} tree", source_tree.size), self.rules.seed_ranges[source_tree.size.size()])
+			},
+		};//end matching what's throwing the seed
+		let distance = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1));
+		if distance == 0 || distance > range {
+			return Err(format!("{} can only plant a seed up to {} spaces away.", source_name, range));
+		}//end if target is out of range
+
+		let target = self.board.board.get(to.0, to.1).ok_or("That spot isn't on the board.".to_string())?;
+		if target.tree.is_some() || target.piece_type != PieceType::Empty {
+			return Err("Seeds can only be planted on empty spots.".to_string());
+		}//end if target spot isn't empty
+		if target.is_expended() {
+			return Err("That spot has already been used this turn.".to_string());
+		}//end if target is expended
+		if self.current().available_count(TreeSize::Seed) == 0 {
+			return Err("There are no seeds in your available area.".to_string());
+		}//end if there's no seed to plant
+
+		self.spend_light(self.rules.plant_cost)?;
+		let color = self.current().color;
+		self.current_mut().take_available(TreeSize::Seed);
+
+		let target = self.board.board.get_mut(to.0, to.1).unwrap();
+		target.tree = Some(Tree {color, size: TreeSize::Seed});
+		target.piece_type = PieceType::Tree;
+		target.expend();
+		self.board.board.get_mut(from.0, from.1).unwrap().expend();
+		return Ok(());
+	}//end plant_seed(&mut self, from, to)
+
+	/// # grow(&mut self, row, col)
+	///
+	/// Grows the tree at the given spot by one size, replacing it with a tree from the available area.
+	///
+	/// The light cost is equal to the height of the new tree, and the old tree goes back onto the player board.
+	fn grow(&mut self, row: usize, col: usize) -> Result<(), String> {
+		let tree = self.owned_tree_at(row, col)?;
+		let new_size = match tree.size {
+			TreeSize::Seed => TreeSize::Small,
+			TreeSize::Small => TreeSize::Medium,
+			TreeSize::Medium => TreeSize::Large,
+			TreeSize::Large => return Err("Large trees can't grow any more. Try collecting it instead.".to_string()),
+		};//end matching size to grow into
+		if self.current().available_count(new_size) == 0 {
+			return Err(format!("There are no {} trees in your available area.", new_size));
+		}//end if there's no tree to grow into
+
+		self.spend_light(self.rules.grow_costs[new_size.size()])?;
+		let player = self.current_mut();
+		player.take_available(new_size);
+		player.player_board.return_tree(tree.size);
+
+		let spot = self.board.board.get_mut(row, col).unwrap();
+		spot.tree = Some(Tree {color: tree.color, size: new_size});
+		spot.expend();
+		return Ok(());
+	}//end grow(&mut self, row, col)
+
+	/// # collect(&mut self, row, col)
+	///
+	/// Collects the large tree at the given spot, for 4 light points under the official rules.
+	///
+	/// The player takes the top scoring token matching the fertility of the spot, or from the next lowest stack with tokens left.
+	fn collect(&mut self, row: usize, col: usize) -> Result<(), String> {
+		let tree = self.owned_tree_at(row, col)?;
+		if tree.size != TreeSize::Large {
+			return Err("Only large trees can be collected.".to_string());
+		}//end if tree isn't large
+
+		self.spend_light(self.rules.collect_cost)?;
+		let fertility = self.board.board.get(row, col).unwrap().fertility;
+		let token = self.take_scoring_token(fertility);
+		let player = self.current_mut();
+		player.player_board.return_tree(TreeSize::Large);
+		if let Some(token) = token {
+			player.scoring_tokens.push(token);
+			self.log.push(LogEntry::TokenTaken {player: self.current_player, fertility: token.0, value: token.1});
+		}//end if there was a token left to take
+
+		let spot = self.board.board.get_mut(row, col).unwrap();
+		spot.tree = None;
+		spot.piece_type = PieceType::Empty;
+		spot.expend();
+		return Ok(());
+	}//end collect(&mut self, row, col)
+
+	/// # is_setting_up(&self)
+	///
+	/// Returns whether the game is still being set up, which is until every starting tree has been placed and the first player does something other than placing pieces on their first turn.
+	pub fn is_setting_up(&self) -> bool {
+		let only_placed_pieces = self.log.iter().all(|entry| match entry {
+			LogEntry::Action {action, ..} => matches!(action, GameAction::PlaceGreatElderTree(..) | GameAction::PlaceMoonstone(..) | GameAction::PlaceStartingTree(..)),
+			_ => true,
+		});
+		return self.round == 1 && self.turns_this_round == 0 && only_placed_pieces;
+	}//end is_setting_up(&self)
+
+	/// # place_starting_tree(&mut self, row, col)
+	///
+	/// Puts a small tree from the current player's available area on the edge of the board, for free, while setting up the game.
+	///
+	/// Players take turns placing one tree at a time, starting with the first player, until everyone has placed as many as the rules ask for.
+	/// Once the last one is placed, the first player's turn starts with the photosynthesis phase of the first round.
+	fn place_starting_tree(&mut self, row: usize, col: usize) -> Result<(), String> {
+		if self.starting_trees_left == 0 {
+			return Err("Starting trees can only be placed while setting up the game.".to_string());
+		}//end if every starting tree is already placed
+		let spot = self.board.board.get(row, col).ok_or("That spot isn't on the board.".to_string())?;
+		if !self.board.on_edge(row, col) {
+			return Err("Starting trees have to go on the edge of the board.".to_string());
+		}//end if spot isn't on the outer ring
+		if spot.piece_type != PieceType::Empty || spot.tree.is_some() {
+			return Err("Starting trees have to go on an empty spot.".to_string());
+		}//end if spot isn't empty
+		if !self.current_mut().take_available(TreeSize::Small) {
+			return Err("There are no small trees in your available area.".to_string());
+		}//end if there's no tree to place
+
+		let color = self.current().color;
+		let spot = self.board.board.get_mut(row, col).unwrap();
+		spot.tree = Some(Tree {color, size: TreeSize::Small});
+		spot.piece_type = PieceType::Tree;
+		// the next player can't undo this player's placement
+		self.undo_stack.clear();
+		self.redo_stack.clear();
+
+		self.starting_trees_left -= 1;
+		if self.starting_trees_left == 0 {
+			self.current_player = self.first_player;
+			self.photosynthesis();
+		}//end if that was the last starting tree
+		else {
+			self.current_player = (self.current_player + 1) % self.players.len();
+		}//end else it's the next player's turn to place one
+		return Ok(());
+	}//end place_starting_tree(&mut self, row, col)
+
+	/// # place_great_elder_tree(&mut self, row, col)
+	///
+	/// Puts the Great Elder Tree on the board while setting up the game.
+	///
+	/// There can only be one Great Elder Tree, and it has to go on an empty four leaf spot, which is in the middle of the board.
+	/// Animals can't share a spot with it, so an animal already on that spot moves to the first spot next to it which can hold an animal.
+	fn place_great_elder_tree(&mut self, row: usize, col: usize) -> Result<(), String> {
+		if !self.is_setting_up() {
+			return Err("The Great Elder Tree can only be placed while setting up the game.".to_string());
+		}//end if game has already started
+		if self.board.board.iter().any(|spot| spot.piece_type == PieceType::GreatElderTree) {
+			return Err("The Great Elder Tree is already on the board.".to_string());
+		}//end if there's already a great elder tree
+		let spot = self.board.board.get(row, col).ok_or("That spot isn't on the board.".to_string())?;
+		if spot.fertility != Fertility::FourLeaf {
+			return Err("The Great Elder Tree has to go on a four leaf spot.".to_string());
+		}//end if spot isn't in the middle
+		if spot.piece_type != PieceType::Empty || spot.tree.is_some() {
+			return Err("The Great Elder Tree has to go on an empty spot.".to_string());
+		}//end if spot isn't empty
+		if let Some(animal) = spot.animal {
+			let (rows, cols) = self.board.board.size();
+			let (to_row, to_col) = get_adjacent_coords(row, col, rows - 1, cols - 1, true).into_iter()
+				.find(|(adjacent_row, adjacent_col)| self.board.board.get(*adjacent_row, *adjacent_col).unwrap().can_hold_animal())
+				.ok_or(format!("The {} on that spot has nowhere next to it to move to, so the Great Elder Tree can't go there.", animal))?;
+			self.board.board.get_mut(to_row, to_col).unwrap().animal = Some(animal);
+		}//end if an animal has to move out of the way
+
+		let spot = self.board.board.get_mut(row, col).unwrap();
+		spot.animal = None;
+		spot.piece_type = PieceType::GreatElderTree;
+		return Ok(());
+	}//end place_great_elder_tree(&mut self, row, col)
+
+	/// # place_moonstone(&mut self, row, col)
+	///
+	/// Puts a moonstone on the board while setting up the game.
+	///
+	/// Moonstones can't go on the outer ring of the board, since that's where the moon travels, and the rules limit how many there can be.
+	/// They can go next to each other, in which case light from the moon is passed along the whole chain.
+	fn place_moonstone(&mut self, row: usize, col: usize) -> Result<(), String> {
+		if !self.is_setting_up() {
+			return Err("Moonstones can only be placed while setting up the game.".to_string());
+		}//end if game has already started
+		if self.board.board.iter().filter(|spot| spot.piece_type == PieceType::Moonstone).count() >= self.rules.max_moonstones {
+			return Err(format!("There can't be more than {} moonstones on the board.", self.rules.max_moonstones));
+		}//end if every moonstone has been placed
+		let spot = self.board.board.get(row, col).ok_or("That spot isn't on the board.".to_string())?;
+		if self.board.on_edge(row, col) {
+			return Err("Moonstones can't go on the edge of the board, since that's where the moon travels.".to_string());
+		}//end if spot is on the outer ring
+		if spot.piece_type != PieceType::Empty || spot.tree.is_some() || spot.animal.is_some() {
+			return Err("Moonstones have to go on an empty spot.".to_string());
+		}//end if spot isn't empty
+
+		self.board.board.get_mut(row, col).unwrap().piece_type = PieceType::Moonstone;
+		return Ok(());
+	}//end place_moonstone(&mut self, row, col)
+
+	/// # great_elder_tree_bonus(&mut self)
+	///
+	/// Gives each player lunar points for every one of their trees next to the Great Elder Tree, since the elder shelters the trees around it.
+	///
+	/// This happens once, when the game ends.
+	///
+	/// ## return
+	/// Returns the number of lunar points each player gained, parallel to self.players.
+	pub fn great_elder_tree_bonus(&mut self) -> Vec<usize> {
+		let mut gained = vec![0; self.players.len()];
+		let rows = self.board.board.rows();
+		let cols = self.board.board.cols();
+		for row in 0..rows {
+			for col in 0..cols {
+				if self.board.board.get(row, col).unwrap().piece_type != PieceType::GreatElderTree {
+					continue;
+				}//end if this isn't the great elder tree
+				for (adjacent_row, adjacent_col) in get_adjacent_coords(row, col, rows - 1, cols - 1, true) {
+					if let Some(tree) = self.board.board.get(adjacent_row, adjacent_col).unwrap().tree {
+						if let Some(owner) = self.owner_of(&tree) {
+							gained[owner] += self.rules.elder_points;
+						}//end if we know who owns this tree
+					}//end if there's a tree next to the great elder tree
+				}//end looping over spots next to the great elder tree
+			}//end looping over cols
+		}//end looping over rows
+
+		if gained.iter().any(|lunar| *lunar > 0) {
+			for (player, lunar) in self.players.iter_mut().zip(gained.iter()) {
+				player.lunar_points += lunar;
+			}//end adding lunar points to each player
+			self.log.push(LogEntry::ElderTreeBonus {gained: gained.clone()});
+		}//end if anyone gained anything
+		return gained;
+	}//end great_elder_tree_bonus(&mut self)
+
+	/// # take_scoring_token(&mut self, fertility)
+	///
+	/// Takes the top scoring token from the stack for this fertility. If that stack is empty, then the next lowest stack is tried instead.
+	///
+	/// Returns None if there were no tokens left to take.
+	fn take_scoring_token(&mut self, fertility: Fertility) -> Option<(Fertility, usize)> {
+		let fertilities = [Fertility::OneLeaf, Fertility::TwoLeaf, Fertility::ThreeLeaf, Fertility::FourLeaf];
+		for index in (0..fertility.as_usize()).rev() {
+			if let Some(value) = self.scoring_stacks.get_mut(index).unwrap().pop() {
+				return Some((fertilities[index], value));
+			}//end if this stack had a token
+		}//end looking at each stack from this fertility down
+		return None;
+	}//end take_scoring_token(&mut self, fertility)
+
+	/// # end_turn(&mut self)
+	///
+	/// Finishes the current player's turn, clearing expended spots and moving on to the next player.
+	///
+	/// If every player has had a turn this round, then a new round is started.
+	fn end_turn(&mut self) {
+		for spot in self.board.board.iter_mut() {
+			spot.expended = false;
+		}//end clearing expended spots
+		self.undo_stack.clear();
+		self.redo_stack.clear();
+
+		self.turns_this_round += 1;
+		if self.turns_this_round >= self.players.len() {
+			self.end_round();
+		}//end if everyone has had their turn
+		else {
+			self.current_player = (self.current_player + 1) % self.players.len();
+		}//end else it's just the next player's turn
+	}//end end_turn(&mut self)
+
+	/// # end_round(&mut self)
+	///
+	/// Finishes a round, passing the first player marker and moving the sun and moon before the next photosynthesis and moonlight phases.
+	///
+	/// If this was the last round, then the game ends instead.
+	fn end_round(&mut self) {
+		if self.round >= self.max_rounds {
+			self.game_over = true;
+			self.great_elder_tree_bonus();
+			self.log.push(LogEntry::GameOver);
+			return;
+		}//end if that was the last round
+
+		self.round += 1;
+		self.turns_this_round = 0;
+		self.first_player = (self.first_player + 1) % self.players.len();
+		self.current_player = self.first_player;
+		self.board.pass_sun_and_moon(self.board.moon.grid_side_length + self.rules.moon_extra_steps);
+		self.log.push(LogEntry::SunMoved(self.board.sun.direction));
+		self.log.push(LogEntry::MoonMoved(self.board.moon));
+		self.photosynthesis();
+		self.moonlight();
+	}//end end_round(&mut self)
+
+	/// # photosynthesis(&mut self)
+	///
+	/// Gives each player light points for each of their trees that isn't in shade. Under the official rules, a tree gives light points equal to its height.
+	///
+	/// Players can't have more than 20 light points, or whatever limit the rules give.
+	///
+	/// ## return
+	/// Returns the number of light points each player gained, parallel to self.players.
+	pub fn photosynthesis(&mut self) -> Vec<usize> {
+		let shaded = self.board.sun_shaded_with(&self.rules.shade);
+		let mut gained = vec![0; self.players.len()];
+		for row in 0..self.board.board.rows() {
+			for col in 0..self.board.board.cols() {
+				if *shaded.get(row, col).unwrap() {
+					continue;
+				}//end if this spot is in shade
+				if let Some(tree) = self.board.board.get(row, col).unwrap().tree {
+					if let Some(owner) = self.owner_of(&tree) {
+						gained[owner] += self.rules.light_yields[tree.size.size()];
+					}//end if we know who owns this tree
+				}//end if there's a tree here
+			}//end looping over cols
+		}//end looping over rows
+
+		for (player, light) in self.players.iter_mut().zip(gained.iter_mut()) {
+			let new_total = (player.light_points + *light).min(self.rules.max_light.max(player.light_points));
+			*light = new_total - player.light_points;
+			player.light_points = new_total;
+		}//end adding light to each player
+		self.log.push(LogEntry::LightGained {round: self.round, gained: gained.clone()});
+		return gained;
+	}//end photosynthesis(&mut self)
+
+	/// # moonlight(&mut self)
+	///
+	/// Gives each player lunar points for each of their trees lit by the moon. Under the official rules, each tree gives 1 lunar point under a half moon, and 2 under a full moon.
+	///
+	/// Each lit moonstone also gives lunar points for every tree around it, so a tree between two lit moonstones gets points from both.
+	///
+	/// ## return
+	/// Returns the number of lunar points each player gained, parallel to self.players.
+	pub fn moonlight(&mut self) -> Vec<usize> {
+		let lit = self.board.moon_lit();
+		let points_per_tree = if self.board.moon.full_moon {self.rules.full_moon_points} else {self.rules.half_moon_points};
+		let mut gained = vec![0; self.players.len()];
+		let rows = self.board.board.rows();
+		let cols = self.board.board.cols();
+		for row in 0..rows {
+			for col in 0..cols {
+				if !*lit.get(row, col).unwrap() {
+					continue;
+				}//end if moon doesn't reach this spot
+				let this_spot = self.board.board.get(row, col).unwrap();
+				if let Some(tree) = this_spot.tree {
+					if let Some(owner) = self.owner_of(&tree) {
+						gained[owner] += points_per_tree;
+					}//end if we know who owns this tree
+				}//end if there's a tree here
+				else if this_spot.piece_type == PieceType::Moonstone {
+					// a lit moonstone gives an extra point for each tree around it
+					for (adjacent_row, adjacent_col) in get_adjacent_coords(row, col, rows - 1, cols - 1, true) {
+						if let Some(tree) = self.board.board.get(adjacent_row, adjacent_col).unwrap().tree {
+							if let Some(owner) = self.owner_of(&tree) {
+								gained[owner] += self.rules.moonstone_points;
+							}//end if we know who owns this tree
+						}//end if there's a tree next to the moonstone
+					}//end looping over spots next to the moonstone
+				}//end else if this is a lit moonstone
+			}//end looping over cols
+		}//end looping over rows
+
+		for (player, lunar) in self.players.iter_mut().zip(gained.iter()) {
+			player.lunar_points += lunar;
+		}//end adding lunar points to each player
+		self.log.push(LogEntry::LunarGained {round: self.round, gained: gained.clone()});
+		return gained;
+	}//end moonlight(&mut self)
+}//end impl for GameState
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{Animal, default_player_color};
+	use crate::game::tests::{players, started_game};
+
+	/// # two_player_game()
+	///
+	/// Helper function which sets up a game where the first player has a small tree in the middle of the board and 10 light points.
+	fn two_player_game() -> GameState {
+		let mut state = started_game(players(2));
+		let color = state.current().color;
+		let spot = state.board.board.get_mut(3, 3).unwrap();
+		spot.tree = Some(Tree {color, size: TreeSize::Small});
+		spot.piece_type = PieceType::Tree;
+		state.current_mut().light_points = 10;
+		return state;
+	}//end two_player_game()
+
+	#[test]
+	fn undo_restores_everything_an_action_changed() {
+		let mut state = two_player_game();
+		let before = state.clone();
+
+		state.apply(GameAction::Buy(TreeSize::Medium)).unwrap();
+		state.apply(GameAction::Grow(3, 3)).unwrap();
+		assert_eq!(state.board.board.get(3, 3).unwrap().tree.unwrap().size, TreeSize::Medium);
+		assert!(state.board.board.get(3, 3).unwrap().is_expended());
+
+		state.apply(GameAction::Undo).unwrap();
+		assert_eq!(state.board.board.get(3, 3).unwrap().tree.unwrap().size, TreeSize::Small);
+		assert!(!state.board.board.get(3, 3).unwrap().is_expended());
+		state.apply(GameAction::Undo).unwrap();
+		assert_eq!(state.board, before.board);
+		assert_eq!(state.players, before.players);
+		assert_eq!(state.log, before.log);
+		assert!(state.apply(GameAction::Undo).is_err());
+	}//end undo_restores_everything_an_action_changed()
+
+	#[test]
+	fn redo_carries_out_undone_actions_again() {
+		let mut state = two_player_game();
+		state.apply(GameAction::Buy(TreeSize::Medium)).unwrap();
+		state.apply(GameAction::Grow(3, 3)).unwrap();
+		let after = state.clone();
+
+		state.apply(GameAction::Undo).unwrap();
+		state.apply(GameAction::Undo).unwrap();
+		state.apply(GameAction::Redo).unwrap();
+		state.apply(GameAction::Redo).unwrap();
+		assert_eq!(state.board, after.board);
+		assert_eq!(state.players, after.players);
+		assert_eq!(state.log, after.log);
+		assert!(state.apply(GameAction::Redo).is_err());
+
+		// a new action means the undone ones can't be redone any more
+		state.apply(GameAction::Undo).unwrap();
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		assert!(!state.can_redo());
+	}//end redo_carries_out_undone_actions_again()
+
+	#[test]
+	fn history_is_cleared_at_end_of_turn() {
+		let mut state = two_player_game();
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		state.apply(GameAction::EndTurn).unwrap();
+		assert!(!state.can_undo());
+		assert!(state.apply(GameAction::Undo).is_err());
+		assert_eq!(state.current_player, 1);
+	}//end history_is_cleared_at_end_of_turn()
+
+	#[test]
+	fn whole_game_moves_sun_and_moon_every_round() {
+		let mut state = two_player_game();
+		while !state.game_over {
+			state.apply(GameAction::EndTurn).unwrap();
+		}//end playing until the game ends
+		assert_eq!(state.round, state.max_rounds);
+		let moon_moves = state.log.iter().filter(|entry| matches!(entry, LogEntry::MoonMoved(_))).count();
+		assert_eq!(moon_moves, state.max_rounds - 1);
+		assert_eq!(state.log.last(), Some(&LogEntry::GameOver));
+	}//end whole_game_moves_sun_and_moon_every_round()
+
+	#[test]
+	fn moonlight_gives_lunar_points_for_lit_trees() {
+		let mut state = two_player_game();
+		let color = state.players[1].color;
+		// the moon starts in the top right corner, shining down and to the left from row 0, col 5
+		let spot = state.board.board.get_mut(2, 3).unwrap();
+		spot.tree = Some(Tree {color, size: TreeSize::Seed});
+		spot.piece_type = PieceType::Tree;
+
+		assert_eq!(state.moonlight(), vec![0, 1]);
+		state.board.moon.full_moon = true;
+		assert_eq!(state.moonlight(), vec![0, 2]);
+		assert_eq!(state.players[1].lunar_points, 3);
+		assert_eq!(state.players[1].score(), 3);
+	}//end moonlight_gives_lunar_points_for_lit_trees()
+
+	#[test]
+	fn great_elder_tree_only_placed_once_while_setting_up() {
+		let mut state = started_game(players(2));
+		assert!(state.apply(GameAction::PlaceGreatElderTree(0, 0)).is_err());
+		state.apply(GameAction::PlaceGreatElderTree(3, 3)).unwrap();
+		assert_eq!(state.board.board.get(3, 3).unwrap().piece_type, PieceType::GreatElderTree);
+		assert!(!state.board.board.get(3, 3).unwrap().can_hold_animal());
+
+		assert!(state.apply(GameAction::PlaceGreatElderTree(3, 4)).is_err());
+		state.apply(GameAction::Undo).unwrap();
+		state.current_mut().light_points = 2;
+		state.apply(GameAction::PlaceGreatElderTree(3, 3)).unwrap();
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		assert!(!state.is_setting_up());
+
+		// once something else has been done, setup is over even if the great elder tree is taken back
+		state.apply(GameAction::Undo).unwrap();
+		state.apply(GameAction::Undo).unwrap();
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		assert!(state.apply(GameAction::PlaceGreatElderTree(3, 3)).is_err());
+	}//end great_elder_tree_only_placed_once_while_setting_up()
+
+	#[test]
+	fn anyone_can_plant_from_great_elder_tree_once_per_turn() {
+		let mut state = started_game(players(2));
+		state.apply(GameAction::PlaceGreatElderTree(3, 3)).unwrap();
+		state.current_mut().light_points = 10;
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+
+		// from the middle of the board, the great elder tree can reach every corner
+		state.apply(GameAction::PlantSeed {from: (3, 3), to: (0, 6)}).unwrap();
+		assert_eq!(state.board.board.get(0, 6).unwrap().tree, Some(Tree {color: state.current().color, size: TreeSize::Seed}));
+		assert!(state.apply(GameAction::PlantSeed {from: (3, 3), to: (6, 6)}).is_err());
+
+		state.apply(GameAction::EndTurn).unwrap();
+		state.current_mut().light_points = 10;
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		state.apply(GameAction::PlantSeed {from: (3, 3), to: (6, 6)}).unwrap();
+	}//end anyone_can_plant_from_great_elder_tree_once_per_turn()
+
+	#[test]
+	fn great_elder_tree_rewards_trees_around_it_at_game_end() {
+		let mut state = started_game(players(2));
+		state.apply(GameAction::PlaceGreatElderTree(3, 3)).unwrap();
+		for (row, col, owner) in [(2, 2, 0), (3, 4, 0), (4, 3, 1), (1, 3, 1)] {
+			let color = state.players[owner].color;
+			let spot = state.board.board.get_mut(row, col).unwrap();
+			spot.tree = Some(Tree {color, size: TreeSize::Seed});
+			spot.piece_type = PieceType::Tree;
+		}//end placing trees around the great elder tree
+
+		assert_eq!(state.great_elder_tree_bonus(), vec![2, 1]);
+		assert_eq!(state.log.last(), Some(&LogEntry::ElderTreeBonus {gained: vec![2, 1]}));
+		assert_eq!(state.players[0].lunar_points, 2);
+	}//end great_elder_tree_rewards_trees_around_it_at_game_end()
+
+	#[test]
+	fn great_elder_tree_moves_animals_out_of_its_way() {
+		let mut state = started_game(players(2));
+		state.board.board.get_mut(3, 3).unwrap().animal = Some(Animal::Owl);
+		state.rehash();
+		let before = state.clone();
+		state.apply(GameAction::PlaceGreatElderTree(3, 3)).unwrap();
+		assert_eq!(state.board.board.get(3, 3).unwrap().animal, None);
+		let moved: Vec<(usize, usize)> = get_adjacent_coords(3, 3, 6, 6, true).into_iter().filter(|(row, col)| state.board.board.get(*row, *col).unwrap().animal == Some(Animal::Owl)).collect();
+		assert_eq!(moved.len(), 1);
+		assert_eq!(state.zobrist(), full_hash(&state));
+		state.apply(GameAction::Undo).unwrap();
+		assert_eq!(state.board, before.board);
+
+		// with every spot around it taken, there's nowhere for the animal to go
+		for (row, col) in get_adjacent_coords(3, 3, 6, 6, true) {
+			state.board.board.get_mut(row, col).unwrap().animal = Some(Animal::Fox);
+		}//end filling every spot around the middle
+		assert!(state.apply(GameAction::PlaceGreatElderTree(3, 3)).is_err());
+		assert_eq!(state.board.board.get(3, 3).unwrap().piece_type, PieceType::Empty);
+	}//end great_elder_tree_moves_animals_out_of_its_way()
+
+	#[test]
+	fn moonstones_only_placed_inside_the_edge_while_setting_up() {
+		let mut state = started_game(players(2));
+		assert!(state.apply(GameAction::PlaceMoonstone(0, 3)).is_err());
+		assert!(state.apply(GameAction::PlaceMoonstone(3, 6)).is_err());
+		state.apply(GameAction::PlaceGreatElderTree(3, 3)).unwrap();
+		assert!(state.apply(GameAction::PlaceMoonstone(3, 3)).is_err());
+		for (row, col) in [(1, 4), (2, 5), (3, 5), (5, 5)] {
+			state.apply(GameAction::PlaceMoonstone(row, col)).unwrap();
+		}//end placing every moonstone
+		assert!(state.apply(GameAction::PlaceMoonstone(1, 1)).is_err());
+		assert!(!state.board.board.get(1, 4).unwrap().can_hold_animal());
+
+		state.apply(GameAction::Undo).unwrap();
+		state.apply(GameAction::EndTurn).unwrap();
+		assert!(state.apply(GameAction::PlaceMoonstone(5, 5)).is_err());
+	}//end moonstones_only_placed_inside_the_edge_while_setting_up()
+
+	#[test]
+	fn chained_moonstones_reward_trees_around_them() {
+		let mut state = started_game(players(2));
+		// the moon starts shining down and to the left from row 0, col 5, so only the moonstone at row 1, col 4 is hit directly
+		for (row, col) in [(1, 4), (2, 5), (3, 5), (5, 5)] {
+			state.apply(GameAction::PlaceMoonstone(row, col)).unwrap();
+		}//end placing a chain of moonstones, and one off on its own
+		for (row, col, owner) in [(2, 4, 0), (4, 5, 1), (5, 4, 0)] {
+			let color = state.players[owner].color;
+			let spot = state.board.board.get_mut(row, col).unwrap();
+			spot.tree = Some(Tree {color, size: TreeSize::Seed});
+			spot.piece_type = PieceType::Tree;
+		}//end placing trees around the moonstones
+
+		let lit = state.board.moon_lit();
+		for (row, col) in [(1, 4), (2, 5), (3, 5), (2, 4), (4, 5), (4, 6)] {
+			assert!(*lit.get(row, col).unwrap(), "row {}, col {} should be lit", row, col);
+		}//end checking spots reached through the chain
+		assert!(!*lit.get(5, 5).unwrap());
+		assert!(!*lit.get(5, 4).unwrap());
+
+		// the tree at row 2, col 4 is lit, and touches all three lit moonstones
+		// the tree at row 4, col 5 is lit, and touches one lit moonstone
+		// the tree at row 5, col 4 only touches the moonstone the moon doesn't reach
+		assert_eq!(state.moonlight(), vec![1 + 3, 1 + 1]);
+		state.board.moon.full_moon = true;
+		assert_eq!(state.moonlight(), vec![2 + 3, 2 + 1]);
+	}//end chained_moonstones_reward_trees_around_them()
+
+	#[test]
+	fn starting_trees_go_on_the_edge_in_turn_order() {
+		let mut state = GameState::new(players(2));
+		assert_eq!(state.starting_trees_left, 4);
+		assert!(state.apply(GameAction::Buy(TreeSize::Seed)).is_err());
+		assert!(state.apply(GameAction::EndTurn).is_err());
+		assert!(state.apply(GameAction::PlaceStartingTree(3, 3)).is_err());
+
+		// placing a tree passes to the next player, and can't be taken back
+		state.apply(GameAction::PlaceStartingTree(0, 0)).unwrap();
+		assert_eq!(state.current_player, 1);
+		assert!(!state.can_undo());
+		assert!(state.apply(GameAction::PlaceStartingTree(0, 0)).is_err());
+		state.apply(GameAction::PlaceStartingTree(6, 6)).unwrap();
+		state.apply(GameAction::PlaceStartingTree(0, 6)).unwrap();
+		assert!(state.is_setting_up());
+		state.apply(GameAction::PlaceStartingTree(6, 3)).unwrap();
+
+		// once everything is placed, the first player starts with light from their trees
+		assert_eq!(state.starting_trees_left, 0);
+		assert_eq!(state.current_player, 0);
+		assert_eq!(state.players[0].available_count(TreeSize::Small), 2);
+		assert_eq!(state.board.board.get(6, 3).unwrap().tree, Some(Tree {color: default_player_color(1), size: TreeSize::Small}));
+		assert!(matches!(state.log.last(), Some(LogEntry::LightGained {round: 1, ..})));
+		assert!(state.apply(GameAction::PlaceStartingTree(0, 3)).is_err());
+		assert!(state.players.iter().any(|player| player.light_points > 0));
+	}//end starting_trees_go_on_the_edge_in_turn_order()
+
+	#[test]
+	fn board_size_comes_from_the_rules() {
+		let state = GameState::with_rules(players(2), RuleSet {board_size: 9, ..RuleSet::default()});
+		assert_eq!(state.board.board.size(), (9, 9));
+		assert_eq!(state.board.moon.ring_count(), 32);
+		let fertility = |row, col| state.board.board.get(row, col).unwrap().fertility;
+		assert_eq!(fertility(0, 4), Fertility::OneLeaf);
+		assert_eq!(fertility(1, 1), Fertility::TwoLeaf);
+		assert_eq!(fertility(2, 6), Fertility::ThreeLeaf);
+		assert_eq!(fertility(3, 5), Fertility::FourLeaf);
+		assert_eq!(fertility(4, 4), Fertility::FourLeaf);
+	}//end board_size_comes_from_the_rules()
+}//end mod tests
@@ -0,0 +1,370 @@
+use grid::Grid;
+
+use super::{Board, PieceType, RuleSet, ShadeRules, SunDirection};
+
+/// The most spots a board can have and still fit in a bitboard.
+pub(crate) const MAX_BITBOARD_SPOTS: usize = 64;
+
+/// # ShadeBits
+///
+/// The pieces on a board which matter for sunlight, stored as bitboards.
+///
+/// Each bitboard has one bit per spot, with the spot at (row,col) stored in bit row * cols + col.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Default)]
+pub struct ShadeBits {
+	/// spots holding a tree, indexed by TreeSize::size(), so seeds are at index 0
+	pub(crate) trees: [u64; 4],
+	/// the spot holding the Great Elder Tree, if there is one
+	pub(crate) great_elder_tree: u64,
+	/// spots holding a moonstone
+	pub(crate) moonstones: u64,
+}//end struct ShadeBits
+
+#[allow(dead_code)]
+impl ShadeBits {
+	/// # from_board(board)
+	///
+	/// Pulls the pieces which cast or receive shadows out of a board.
+	///
+	/// The board needs to have at most MAX_BITBOARD_SPOTS spots, which LightEvaluator::new() checks for.
+	pub fn from_board(board: &Board) -> ShadeBits {
+		let mut bits = ShadeBits::default();
+		for (index, spot) in board.board.iter().enumerate() {
+			let bit = 1u64 << index;
+			if let Some(tree) = spot.tree {
+				bits.trees[tree.size.size()] |= bit;
+			}//end if there's a tree here
+			else if spot.piece_type == PieceType::GreatElderTree {
+				bits.great_elder_tree |= bit;
+			}//end else if the great elder tree is here
+			else if spot.piece_type == PieceType::Moonstone {
+				bits.moonstones |= bit;
+			}//end else if a moonstone is here
+		}//end looping over each spot in row-major order
+		return bits;
+	}//end from_board(board)
+
+	/// # all_trees(&self)
+	///
+	/// Returns every spot holding a tree of any size.
+	pub fn all_trees(&self) -> u64 {
+		return self.trees[0] | self.trees[1] | self.trees[2] | self.trees[3];
+	}//end all_trees(&self)
+}//end impl for ShadeBits
+
+/// # owned_bits(board, color)
+///
+/// Returns a bitboard of every spot holding a tree of the given color, laid out the same way as ShadeBits.
+#[allow(dead_code)]
+pub(crate) fn owned_bits(board: &Board, color: (u8,u8,u8)) -> u64 {
+	let mut owned = 0;
+	for (index, spot) in board.board.iter().enumerate() {
+		if spot.tree.is_some_and(|tree| tree.color == color) {
+			owned |= 1u64 << index;
+		}//end if this player owns the tree here
+	}//end looping over each spot in row-major order
+	return owned;
+}//end owned_bits(board, color)
+
+/// # LightEvaluator
+///
+/// Works out which spots are in shade much faster than Board::sun_shaded(), so that it can be called over and over during a search.
+///
+/// When it's made, it works out which spots every possible shadow covers for a board of one size.
+/// After that, finding the shade on a board is just combining the shadows of each piece with a few bitwise ors.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LightEvaluator {
+	/// number of rows on the boards this can evaluate
+	pub(crate) rows: usize,
+	/// number of cols on the boards this can evaluate
+	pub(crate) cols: usize,
+	/// every spot on the board
+	pub(crate) all_spots: u64,
+	/// shade heights and lengths of the rules being played with
+	pub(crate) shade: ShadeRules,
+	/// light points each tree size earns, indexed by TreeSize::size()
+	pub(crate) light_yields: [usize; 4],
+	/// longest shadow that fits on the board. Longer shadows just stop at the edge.
+	max_length: usize,
+	/// indexed by sun direction, then by spot, then by shadow length from 0 to max_length. Holds the spots covered by a shadow of that length cast from that spot.
+	shadows: Vec<u64>,
+}//end struct LightEvaluator
+
+#[allow(dead_code)]
+impl LightEvaluator {
+	/// # new(rows, cols)
+	///
+	/// Works out the shadow tables for a board with the given size, under the official rules.
+	///
+	/// Gives an error if the board has more than MAX_BITBOARD_SPOTS spots, since it wouldn't fit in a bitboard.
+	pub fn new(rows: usize, cols: usize) -> Result<LightEvaluator, String> {
+		return LightEvaluator::with_rules(rows, cols, &RuleSet::default());
+	}//end new(rows, cols)
+
+	/// # with_rules(rows, cols, rules)
+	///
+	/// Same as new(), but using the shade and light numbers from the given rules.
+	pub fn with_rules(rows: usize, cols: usize, rules: &RuleSet) -> Result<LightEvaluator, String> {
+		let spots = rows * cols;
+		if spots == 0 || spots > MAX_BITBOARD_SPOTS {
+			return Err(format!("A {}x{} board can't be evaluated with bitboards. Boards need between 1 and {} spots.", rows, cols, MAX_BITBOARD_SPOTS));
+		}//end if board won't fit in a u64
+
+		let max_length = rows.max(cols);
+		let stride = max_length + 1;
+		let mut shadows = vec![0u64; 8 * spots * stride];
+		for direction in 0..8 {
+			let (row_step, col_step) = direction_step(direction);
+			for row in 0..rows {
+				for col in 0..cols {
+					let mut covered = 0u64;
+					let mut length = 0;
+					let mut cur_row = row as isize + row_step;
+					let mut cur_col = col as isize + col_step;
+					let table = &mut shadows[(direction * spots + row * cols + col) * stride..][..stride];
+					while cur_row >= 0 && cur_col >= 0 && cur_row < rows as isize && cur_col < cols as isize {
+						covered |= 1u64 << (cur_row as usize * cols + cur_col as usize);
+						length += 1;
+						table[length] = covered;
+						cur_row += row_step;
+						cur_col += col_step;
+					}//end walking to the edge of the board
+					// shadows longer than the rest of the line just stop at the edge
+					for long_shadow in table.iter_mut().skip(length + 1) {
+						*long_shadow = covered;
+					}//end filling in shadows cut off by the edge
+				}//end looping over cols
+			}//end looping over rows
+		}//end looping over each sun direction
+
+		let all_spots = if spots == MAX_BITBOARD_SPOTS {u64::MAX} else {(1u64 << spots) - 1};
+		return Ok(LightEvaluator {rows, cols, all_spots, shade: rules.shade, light_yields: rules.light_yields, max_length, shadows});
+	}//end with_rules(rows, cols, rules)
+
+	/// # shaded(&self, bits, direction)
+	///
+	/// Returns a bitboard of the spots in shade when the sun points in the given direction.
+	///
+	/// This follows the same rules as Board::sun_shaded_with(), so a tree taller than every shadow falling on it isn't counted as shaded.
+	pub fn shaded(&self, bits: &ShadeBits, direction: SunDirection) -> u64 {
+		let spots = self.rows * self.cols;
+		let stride = self.max_length + 1;
+		let table = &self.shadows[direction_index(direction) * spots * stride..][..spots * stride];
+		let shade = &self.shade;
+
+		// union of the shadows cast by each kind of piece, along with how tall those shadows are
+		let casters = [
+			(bits.trees[0], shade.tree_heights[0], shade.tree_lengths[0]),
+			(bits.trees[1], shade.tree_heights[1], shade.tree_lengths[1]),
+			(bits.trees[2], shade.tree_heights[2], shade.tree_lengths[2]),
+			(bits.trees[3], shade.tree_heights[3], shade.tree_lengths[3]),
+			(bits.great_elder_tree, shade.elder_height, shade.elder_reach(self.rows, self.cols)),
+			(bits.moonstones, shade.moonstone_height, shade.moonstone_length),
+		];
+		let mut cover = [(0u64, 0usize); 6];
+		for (kind, (pieces, height, length)) in casters.into_iter().enumerate() {
+			let length = length.min(self.max_length);
+			let mut pieces = if length == 0 {0} else {pieces};
+			cover[kind].1 = height;
+			while pieces != 0 {
+				cover[kind].0 |= table[pieces.trailing_zeros() as usize * stride + length];
+				pieces &= pieces - 1;
+			}//end adding each piece's shadow
+		}//end looping over each kind of piece
+
+		// a spot is shaded by anything at least as tall as what's on it, and empty spots are shaded by anything at all
+		let bare = self.all_spots & !(bits.all_trees() | bits.great_elder_tree);
+		let mut shaded = bare & cover.iter().fold(0, |all, (covered, _)| all | covered);
+		for size in 0..4 {
+			let tall_enough = cover.iter()
+				.filter(|(_, height)| *height >= shade.tree_heights[size])
+				.fold(0, |all, (covered, _)| all | covered);
+			shaded |= bits.trees[size] & tall_enough;
+		}//end looping over each tree size
+		return shaded;
+	}//end shaded(&self, bits, direction)
+
+	/// # light_income(&self, bits, shaded, owned)
+	///
+	/// Returns the light points earned by the trees in owned which aren't in shaded.
+	pub fn light_income(&self, bits: &ShadeBits, shaded: u64, owned: u64) -> usize {
+		let lit = owned & !shaded;
+		return (0..4).map(|size| self.light_yields[size] * (bits.trees[size] & lit).count_ones() as usize).sum();
+	}//end light_income(&self, bits, shaded, owned)
+
+	/// # shaded_grid(&self, board)
+	///
+	/// Works out the shade on a board, giving the same grid as Board::sun_shaded().
+	pub fn shaded_grid(&self, board: &Board) -> Result<Grid<bool>, String> {
+		if board.board.rows() != self.rows || board.board.cols() != self.cols {
+			return Err(format!("This evaluator is for {}x{} boards, but the board is {}x{}.", self.rows, self.cols, board.board.rows(), board.board.cols()));
+		}//end if board is the wrong size
+		let shaded = self.shaded(&ShadeBits::from_board(board), board.sun.direction);
+		return Ok(self.to_grid(shaded));
+	}//end shaded_grid(&self, board)
+
+	/// # to_grid(&self, bits)
+	///
+	/// Turns a bitboard back into a grid of booleans, parallel to the board.
+	pub fn to_grid(&self, bits: u64) -> Grid<bool> {
+		let mut grid: Grid<bool> = Grid::new(self.rows, self.cols);
+		for (index, cell) in grid.iter_mut().enumerate() {
+			*cell = bits & (1u64 << index) != 0;
+		}//end looping over each spot in row-major order
+		return grid;
+	}//end to_grid(&self, bits)
+}//end impl for LightEvaluator
+
+/// # direction_index(direction)
+///
+/// Helper function to give each sun direction a place in the shadow tables.
+fn direction_index(direction: SunDirection) -> usize {
+	match direction {
+		SunDirection::North => 0,
+		SunDirection::Northeast => 1,
+		SunDirection::East => 2,
+		SunDirection::Southeast => 3,
+		SunDirection::South => 4,
+		SunDirection::Southwest => 5,
+		SunDirection::West => 6,
+		SunDirection::Northwest => 7,
+	}//end matching direction
+}//end direction_index(direction)
+
+/// # direction_step(index)
+///
+/// Helper function giving the (row,col) change for one step in the direction with the given index from direction_index().
+fn direction_step(index: usize) -> (isize, isize) {
+	match index {
+		0 => (-1,0),
+		1 => (-1,1),
+		2 => (0,1),
+		3 => (1,1),
+		4 => (1,0),
+		5 => (1,-1),
+		6 => (0,-1),
+		_ => (-1,-1),
+	}//end matching index
+}//end direction_step(index)
+
+#[cfg(test)]
+mod tests {
+	use std::time::Instant;
+
+	use super::*;
+	use crate::game::{Tree, TreeSize};
+	use crate::game::tests::{ALL_SUN_DIRECTIONS, XorShift, empty_board, place};
+
+	/// # random_board(rng, rows, cols)
+	///
+	/// Makes a board with a random mix of trees, and sometimes moonstones and a Great Elder Tree.
+	fn random_board(rng: &mut XorShift, rows: usize, cols: usize) -> Board {
+		let sizes = [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large];
+		let mut board = empty_board(rows, cols, ALL_SUN_DIRECTIONS[rng.below(8)]);
+		for _ in 0..rng.below(rows * cols) {
+			place(&mut board, rng.below(rows), rng.below(cols), sizes[rng.below(4)]);
+		}//end placing random trees
+		for _ in 0..rng.below(3) {
+			let spot = board.board.get_mut(rng.below(rows), rng.below(cols)).unwrap();
+			spot.tree = None;
+			spot.piece_type = PieceType::Moonstone;
+		}//end placing random moonstones
+		if rng.below(2) == 0 {
+			let spot = board.board.get_mut(rng.below(rows), rng.below(cols)).unwrap();
+			spot.tree = None;
+			spot.piece_type = PieceType::GreatElderTree;
+		}//end if this board gets a great elder tree
+		return board;
+	}//end random_board(rng, rows, cols)
+
+	#[test]
+	fn evaluator_agrees_with_sun_shaded_on_random_boards() {
+		let mut rng = XorShift(0x9E3779B97F4A7C15);
+		for (rows, cols) in [(7,7), (8,8), (5,9), (9,4), (1,6), (3,3)] {
+			let evaluator = LightEvaluator::new(rows, cols).unwrap();
+			for _ in 0..500 {
+				let board = random_board(&mut rng, rows, cols);
+				assert_eq!(evaluator.shaded_grid(&board).unwrap(), board.sun_shaded(), "{:?}", board);
+			}//end trying random boards
+		}//end trying each board size
+		assert!(LightEvaluator::new(9, 9).is_err());
+	}//end evaluator_agrees_with_sun_shaded_on_random_boards()
+
+	#[test]
+	fn evaluator_follows_house_rules() {
+		let mut rng = XorShift(0x2545F4914F6CDD1D);
+		let mut odd_rules = RuleSet::default();
+		odd_rules.shade.tree_heights = [0, 2, 2, 5];
+		odd_rules.shade.tree_lengths = [1, 0, 4, 2];
+		odd_rules.shade.elder_length = 2;
+		odd_rules.shade.moonstone_length = 0;
+		for rules in [RuleSet::preset("expert").unwrap(), odd_rules] {
+			let evaluator = LightEvaluator::with_rules(7, 7, &rules).unwrap();
+			for _ in 0..500 {
+				let board = random_board(&mut rng, 7, 7);
+				assert_eq!(evaluator.shaded_grid(&board).unwrap(), board.sun_shaded_with(&rules.shade), "{:?}", board);
+			}//end trying random boards
+		}//end trying each set of rules
+	}//end evaluator_follows_house_rules()
+
+	#[test]
+	fn light_income_only_counts_owned_lit_trees() {
+		let mut board = empty_board(7, 7, SunDirection::East);
+		place(&mut board, 3, 0, TreeSize::Large);
+		place(&mut board, 3, 2, TreeSize::Medium);
+		place(&mut board, 3, 5, TreeSize::Medium);
+		place(&mut board, 5, 5, TreeSize::Small);
+		board.board.get_mut(5, 5).unwrap().tree = Some(Tree {color: (1,1,1), size: TreeSize::Small});
+
+		let evaluator = LightEvaluator::new(7, 7).unwrap();
+		let bits = ShadeBits::from_board(&board);
+		let shaded = evaluator.shaded(&bits, board.sun.direction);
+		// the medium tree two spots from the large tree is shaded, and shades the spots up to the other medium tree
+		assert_eq!(evaluator.light_income(&bits, shaded, owned_bits(&board, (0,0,0))), 3 + 2);
+		assert_eq!(evaluator.light_income(&bits, shaded, owned_bits(&board, (1,1,1))), 1);
+	}//end light_income_only_counts_owned_lit_trees()
+
+	/// # benchmark_light_evaluation
+	///
+	/// Times sun_shaded() against the evaluator on the same boards, and checks the evaluator is faster even counting the time to build its bits.
+	/// Timings depend on the machine, so this is ignored by default. Run it with
+	/// `cargo test --release benchmark_light_evaluation -- --ignored`
+	#[test]
+	#[ignore]
+	fn benchmark_light_evaluation() {
+		let mut rng = XorShift(0xD1B54A32D192ED03);
+		let boards: Vec<Board> = (0..2000).map(|_| random_board(&mut rng, 7, 7)).collect();
+		let evaluator = LightEvaluator::new(7, 7).unwrap();
+		let bits: Vec<ShadeBits> = boards.iter().map(ShadeBits::from_board).collect();
+		let rounds = 50;
+
+		let start = Instant::now();
+		let mut checksum = 0usize;
+		for _ in 0..rounds {
+			for board in &boards {
+				checksum += board.sun_shaded().iter().filter(|shaded| **shaded).count();
+			}//end evaluating each board
+		}//end repeating for steadier timing
+		let grid_time = start.elapsed();
+
+		let start = Instant::now();
+		let mut bit_checksum = 0usize;
+		for _ in 0..rounds {
+			for (board, bits) in boards.iter().zip(bits.iter()) {
+				bit_checksum += evaluator.shaded(bits, board.sun.direction).count_ones() as usize;
+			}//end evaluating each board
+		}//end repeating for steadier timing
+		let bit_time = start.elapsed();
+
+		let start = Instant::now();
+		for _ in 0..rounds {
+			for board in &boards {
+				std::hint::black_box(ShadeBits::from_board(board));
+			}//end converting each board
+		}//end repeating for steadier timing
+		let convert_time = start.elapsed();
+
+		assert_eq!(checksum, bit_checksum);
+		assert!(bit_time + convert_time < grid_time, "sun_shaded took {:?}, but LightEvaluator took {:?} plus {:?} building ShadeBits", grid_time, bit_time, convert_time);
+	}//end benchmark_light_evaluation()
+}//end mod tests
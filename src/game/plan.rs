@@ -0,0 +1,188 @@
+use grid::Grid;
+
+use super::{GameAction, GameState};
+
+/// # Plan
+///
+/// A turn being thought through before it's carried out.
+///
+/// Actions in a plan are carried out on a copy of the game, so the real game isn't changed until the whole plan is committed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Plan {
+	/// the game as it was when planning started
+	pub(crate) start: GameState,
+	/// the game as it would be after every action in the plan
+	pub(crate) preview: GameState,
+	/// the actions in the plan, in the order they'll be carried out
+	pub(crate) actions: Vec<GameAction>,
+}//end struct Plan
+
+#[allow(dead_code)]
+impl Plan {
+	/// # new(state)
+	///
+	/// Starts a new plan for the current player, with no actions in it yet.
+	pub fn new(state: &GameState) -> Plan {
+		Plan {
+			start: state.clone(),
+			preview: state.clone(),
+			actions: Vec::new(),
+		}//end struct construction
+	}//end new(state)
+
+	/// # add(&mut self, action)
+	///
+	/// Adds an action to the end of the plan, if the rules allow it after everything already planned.
+	///
+	/// Undo takes the last action back out of the plan, and can't reach back to actions taken before the plan started.
+	/// Ending the turn can't be planned, since the plan only covers one turn.
+	pub fn add(&mut self, action: GameAction) -> Result<(), String> {
+		match action {
+			GameAction::EndTurn => return Err("Commit or discard the plan before ending your turn.".to_string()),
+			GameAction::PlaceStartingTree(..) => return Err("Starting trees can't be planned, since placing one passes to the next player.".to_string()),
+			GameAction::Undo => {
+				if self.actions.is_empty() {
+					return Err("There's nothing in the plan to undo.".to_string());
+				}//end if undo would reach past the start of the plan
+				self.preview.apply(GameAction::Undo)?;
+				self.actions.pop();
+			},
+			GameAction::Redo => {
+				let redo_action = *self.preview.redo_stack.last().ok_or("There's nothing to redo.".to_string())?;
+				self.preview.apply(GameAction::Redo)?;
+				self.actions.push(redo_action);
+			},
+			_ => {
+				self.preview.apply(action)?;
+				self.actions.push(action);
+			},
+		}//end matching action
+		return Ok(());
+	}//end add(&mut self, action)
+
+	/// # light_cost(&self)
+	///
+	/// Returns the number of light points the current player would spend carrying out the plan.
+	pub fn light_cost(&self) -> usize {
+		return self.start.current().light_points.saturating_sub(self.preview.current().light_points);
+	}//end light_cost(&self)
+
+	/// # next_round_shade(&self)
+	///
+	/// Returns which spots would be in shade once the sun moves at the end of this round, if the plan were carried out.
+	///
+	/// The grid is parallel to the board, in the same way as Board::sun_shaded().
+	pub fn next_round_shade(&self) -> Grid<bool> {
+		let mut board = self.preview.board.clone();
+		board.sun = board.sun.next();
+		return board.sun_shaded_with(&self.preview.rules.shade);
+	}//end next_round_shade(&self)
+
+	/// # projected_income(&self)
+	///
+	/// Returns the number of light points the current player would gain in the next photosynthesis phase, if the plan were carried out.
+	///
+	/// This doesn't take the limit of 20 light points into account, and it assumes other players don't change the board before then.
+	pub fn projected_income(&self) -> usize {
+		let shaded = self.next_round_shade();
+		let color = self.preview.current().color;
+		let mut income = 0;
+		for row in 0..self.preview.board.board.rows() {
+			for col in 0..self.preview.board.board.cols() {
+				if *shaded.get(row, col).unwrap() {
+					continue;
+				}//end if this spot would be in shade
+				if let Some(tree) = self.preview.board.board.get(row, col).unwrap().tree {
+					if tree.color == color {
+						income += self.preview.rules.light_yields[tree.size.size()];
+					}//end if tree belongs to current player
+				}//end if there's a tree here
+			}//end looping over cols
+		}//end looping over rows
+		return income;
+	}//end projected_income(&self)
+
+	/// # check(&self, state)
+	///
+	/// Checks that every action in the plan can still be carried out on the given game, without changing it.
+	pub fn check(&self, state: &GameState) -> Result<(), String> {
+		let mut copy = state.clone();
+		for action in &self.actions {
+			copy.apply(*action).map_err(|msg| format!("The plan can't be carried out: {}", msg))?;
+		}//end trying each action
+		return Ok(());
+	}//end check(&self, state)
+
+	/// # commit(&self, state)
+	///
+	/// Carries out every action in the plan on the given game. Either every action is carried out, or none of them are.
+	pub fn commit(&self, state: &mut GameState) -> Result<(), String> {
+		let mut copy = state.clone();
+		for action in &self.actions {
+			copy.apply(*action).map_err(|msg| format!("The plan can't be carried out: {}", msg))?;
+		}//end carrying out each action
+		*state = copy;
+		return Ok(());
+	}//end commit(&self, state)
+}//end impl for Plan
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{PieceType, Tree, TreeSize};
+	use crate::game::tests::{players, started_game};
+
+	#[test]
+	fn plan_leaves_game_alone_until_committed() {
+		let mut state = started_game(players(2));
+		let color = state.current().color;
+		let spot = state.board.board.get_mut(3, 3).unwrap();
+		spot.tree = Some(Tree {color, size: TreeSize::Small});
+		spot.piece_type = PieceType::Tree;
+		state.current_mut().light_points = 10;
+		let before = state.clone();
+
+		let mut plan = Plan::new(&state);
+		plan.add(GameAction::Buy(TreeSize::Medium)).unwrap();
+		plan.add(GameAction::Grow(3, 3)).unwrap();
+		assert!(plan.add(GameAction::Grow(3, 3)).is_err());
+		assert!(plan.add(GameAction::EndTurn).is_err());
+		assert_eq!(plan.actions.len(), 2);
+		assert_eq!(plan.light_cost(), 3 + 2);
+		assert_eq!(state, before);
+
+		// nothing else is on the board, so the medium tree can't be shaded
+		assert_eq!(plan.projected_income(), 2);
+
+		plan.commit(&mut state).unwrap();
+		assert_eq!(state.board, plan.preview.board);
+		assert_eq!(state.players, plan.preview.players);
+	}//end plan_leaves_game_alone_until_committed()
+
+	#[test]
+	fn undo_takes_actions_out_of_plan() {
+		let mut state = started_game(players(2));
+		state.current_mut().light_points = 10;
+
+		let mut plan = Plan::new(&state);
+		plan.add(GameAction::Buy(TreeSize::Seed)).unwrap();
+		plan.add(GameAction::Undo).unwrap();
+		assert!(plan.actions.is_empty());
+		assert_eq!(plan.light_cost(), 0);
+		plan.add(GameAction::Redo).unwrap();
+		assert_eq!(plan.actions, vec![GameAction::Buy(TreeSize::Seed)]);
+	}//end undo_takes_actions_out_of_plan()
+
+	#[test]
+	fn undo_cant_reach_past_start_of_plan() {
+		let mut state = started_game(players(2));
+		state.current_mut().light_points = 10;
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+
+		// the seed was bought before planning started, so undoing it isn't part of the plan
+		let mut plan = Plan::new(&state);
+		assert!(plan.add(GameAction::Undo).is_err());
+		assert_eq!(plan.preview, state);
+		assert_eq!(plan.light_cost(), 0);
+	}//end undo_cant_reach_past_start_of_plan()
+}//end mod tests
@@ -0,0 +1,211 @@
+use super::{Tree, TreeSize, Fertility};
+
+/// # Player
+///
+/// This struct stores everything that belongs to a single player.
+///
+/// The color of a player is the same color used by each Tree that player owns, so it is used to figure out who owns what on the board.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Player {
+	/// The name shown for this player in the gui.
+	pub(crate) name: String,
+	/// The color of this player's trees. This is how trees on the board are matched back to their owner.
+	pub(crate) color: (u8,u8,u8),
+	/// Light points this player has available to spend.
+	pub(crate) light_points: usize,
+	/// Lunar points this player has available to spend.
+	pub(crate) lunar_points: usize,
+	/// The trees this player is able to buy, along with the price of each slot.
+	pub(crate) player_board: PlayerBoard,
+	/// The trees this player has already bought and can place on the board.
+	pub(crate) available: Vec<Tree>,
+	/// Scoring tokens this player has collected, stored as the fertility of the stack they came from and the value of the token.
+	pub(crate) scoring_tokens: Vec<(Fertility, usize)>,
+}//end struct Player
+
+#[allow(dead_code)]
+impl Player {
+	/// # new(name, color)
+	///
+	/// Creates a new player with a full player board and the starting trees in their available area.
+	pub fn new(name: &str, color: (u8,u8,u8)) -> Player {
+		let mut available = Vec::new();
+		for size in [TreeSize::Seed, TreeSize::Seed, TreeSize::Small, TreeSize::Small, TreeSize::Small, TreeSize::Small, TreeSize::Medium] {
+			available.push(Tree {color, size});
+		}//end adding each starting tree to available area
+
+		Player {
+			name: name.to_string(),
+			color,
+			light_points: 0,
+			lunar_points: 0,
+			player_board: PlayerBoard::default(),
+			available,
+			scoring_tokens: Vec::new(),
+		}//end struct construction
+	}//end new(name, color)
+
+	/// # score(&self)
+	///
+	/// Returns the total value of every scoring token this player has collected, plus their lunar points.
+	pub fn score(&self) -> usize {
+		return self.scoring_tokens.iter().map(|token| token.1).sum::<usize>() + self.lunar_points;
+	}//end score(&self)
+
+	/// # available_count(&self, size)
+	///
+	/// Returns how many trees of the given size are in this player's available area.
+	pub fn available_count(&self, size: TreeSize) -> usize {
+		return self.available.iter().filter(|tree| tree.size == size).count();
+	}//end available_count(&self, size)
+
+	/// # take_available(&mut self, size)
+	///
+	/// Removes a single tree of the given size from this player's available area.
+	///
+	/// Returns false if there was no such tree to take.
+	pub fn take_available(&mut self, size: TreeSize) -> bool {
+		match self.available.iter().position(|tree| tree.size == size) {
+			Some(index) => {
+				self.available.remove(index);
+				return true;
+			},
+			None => return false,
+		}//end matching whether we have a tree of that size
+	}//end take_available(&mut self, size)
+}//end impl for Player
+
+/// # default_player_color(index)
+///
+/// Returns a distinct color for each of up to four players, none of which blend in with the green of the board.
+pub fn default_player_color(index: usize) -> (u8,u8,u8) {
+	match index % 4 {
+		0 => (230,126,34),
+		1 => (241,196,15),
+		2 => (52,152,219),
+		_ => (155,89,182),
+	}//end matching player index
+}//end default_player_color(index)
+
+/// # StoreSlot
+///
+/// A single slot on a player board. Each slot has its own price, and is either filled with a tree or empty.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct StoreSlot {
+	/// The number of light points it costs to buy the tree in this slot.
+	pub(crate) price: usize,
+	/// Whether or not there is a tree in this slot.
+	pub(crate) filled: bool,
+}//end struct StoreSlot
+
+/// # PlayerBoard
+///
+/// This struct represents the player board, which is the store that each player buys trees from.
+///
+/// Each tree size has its own column of slots. Trees are always bought from the cheapest filled slot,
+/// and trees returned from the board fill the cheapest empty slot. If there is no empty slot, the returned tree is removed from the game.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct PlayerBoard {
+	pub(crate) seeds: Vec<StoreSlot>,
+	pub(crate) small: Vec<StoreSlot>,
+	pub(crate) medium: Vec<StoreSlot>,
+	pub(crate) large: Vec<StoreSlot>,
+}//end struct PlayerBoard
+
+#[allow(dead_code)]
+impl PlayerBoard {
+	/// # from_prices(seeds, small, medium, large)
+	///
+	/// Creates a full player board with a slot for each price given.
+	pub fn from_prices(seeds: &[usize], small: &[usize], medium: &[usize], large: &[usize]) -> PlayerBoard {
+		let to_slots = |prices: &[usize]| prices.iter().map(|price| StoreSlot {price: *price, filled: true}).collect::<Vec<StoreSlot>>();
+		PlayerBoard {
+			seeds: to_slots(seeds),
+			small: to_slots(small),
+			medium: to_slots(medium),
+			large: to_slots(large),
+		}//end struct construction
+	}//end from_prices(seeds, small, medium, large)
+
+	/// # column(&self, size)
+	///
+	/// Returns the column of slots for the given tree size.
+	pub fn column(&self, size: TreeSize) -> &Vec<StoreSlot> {
+		match size {
+			TreeSize::Seed => &self.seeds,
+			TreeSize::Small => &self.small,
+			TreeSize::Medium => &self.medium,
+			TreeSize::Large => &self.large,
+		}//end matching size
+	}//end column(&self, size)
+
+	/// # column_mut(&mut self, size)
+	///
+	/// Returns the column of slots for the given tree size.
+	pub fn column_mut(&mut self, size: TreeSize) -> &mut Vec<StoreSlot> {
+		match size {
+			TreeSize::Seed => &mut self.seeds,
+			TreeSize::Small => &mut self.small,
+			TreeSize::Medium => &mut self.medium,
+			TreeSize::Large => &mut self.large,
+		}//end matching size
+	}//end column_mut(&mut self, size)
+
+	/// # next_price(&self, size)
+	///
+	/// Returns the price of the next tree of this size that would be bought, or None if there are none left to buy.
+	pub fn next_price(&self, size: TreeSize) -> Option<usize> {
+		return self.column(size).iter().filter(|slot| slot.filled).map(|slot| slot.price).min();
+	}//end next_price(&self, size)
+
+	/// # take(&mut self, size)
+	///
+	/// Takes a tree of the given size out of the cheapest filled slot.
+	///
+	/// Returns the price of the slot the tree was taken from, or None if there were no trees of that size to take.
+	pub fn take(&mut self, size: TreeSize) -> Option<usize> {
+		let price = self.next_price(size)?;
+		let slot = self.column_mut(size).iter_mut().find(|slot| slot.filled && slot.price == price).unwrap();
+		slot.filled = false;
+		return Some(price);
+	}//end take(&mut self, size)
+
+	/// # return_tree(&mut self, size)
+	///
+	/// Puts a tree of the given size back into the cheapest empty slot.
+	///
+	/// Returns false if there was no empty slot, in which case the tree is removed from the game.
+	pub fn return_tree(&mut self, size: TreeSize) -> bool {
+		let column = self.column_mut(size);
+		let cheapest_empty = column.iter().filter(|slot| !slot.filled).map(|slot| slot.price).min();
+		match cheapest_empty {
+			Some(price) => {
+				column.iter_mut().find(|slot| !slot.filled && slot.price == price).unwrap().filled = true;
+				return true;
+			},
+			None => return false,
+		}//end matching whether we have an empty slot
+	}//end return_tree(&mut self, size)
+
+	/// # trees_to_buy(&self, color)
+	///
+	/// Returns a list of each tree still left on this board, colored with the given color.
+	pub fn trees_to_buy(&self, color: (u8,u8,u8)) -> Vec<Tree> {
+		let mut trees = Vec::new();
+		for size in [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large] {
+			for _ in self.column(size).iter().filter(|slot| slot.filled) {
+				trees.push(Tree {color, size});
+			}//end adding a tree for each filled slot
+		}//end looping over each tree size
+		return trees;
+	}//end trees_to_buy(&self, color)
+}//end impl for PlayerBoard
+
+impl Default for PlayerBoard {
+	/// # default()
+	///
+	/// Creates a full player board with the prices printed on the physical board.
+	fn default() -> Self {
+		PlayerBoard::from_prices(&[1,1,2,2], &[2,2,3,3], &[3,3,4], &[4,5])
+	}//end default()
+}//end impl Default for PlayerBoard
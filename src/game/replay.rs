@@ -0,0 +1,235 @@
+use std::fs;
+
+use super::{Fertility, GameAction, GameState, Moon, Player, RuleSet, SunDirection, encoding::{decode_color, decode_log_entry, decode_rule, encode_color, encode_log_entry, encode_rules}};
+
+/// # LogEntry
+///
+/// A single thing that happened during a game, either an action a player took, or something that happened because of the phases of a round.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum LogEntry {
+	/// The given player carried out an action.
+	Action {player: usize, action: GameAction},
+	/// Each player gained light points during the photosynthesis phase of the given round. Light gained is parallel to the list of players.
+	LightGained {round: usize, gained: Vec<usize>},
+	/// Each player gained lunar points from moonlight in the given round. Lunar points gained are parallel to the list of players.
+	LunarGained {round: usize, gained: Vec<usize>},
+	/// Each player gained lunar points for their trees next to the Great Elder Tree at the end of the game, parallel to the list of players.
+	ElderTreeBonus {gained: Vec<usize>},
+	/// The given player took a scoring token when collecting a tree.
+	TokenTaken {player: usize, fertility: Fertility, value: usize},
+	/// The sun moved to point in a new direction.
+	SunMoved(SunDirection),
+	/// The moon moved to a new position.
+	MoonMoved(Moon),
+	/// The last round finished.
+	GameOver,
+}//end enum LogEntry
+
+#[allow(dead_code)]
+impl LogEntry {
+	/// # describe(&self, players)
+	///
+	/// Returns a description of this entry that can be shown to players.
+	pub fn describe(&self, players: &[Player]) -> String {
+		let name = |index: &usize| players.get(*index).map(|player| player.name.clone()).unwrap_or(format!("Player {}", index + 1));
+		match self {
+			LogEntry::Action {player, action} => {
+				match action {
+					GameAction::Buy(size) => format!("{} bought a {} tree", name(player), size),
+					GameAction::PlantSeed {from, to} => format!("{} planted a seed at row {}, col {} from the tree at row {}, col {}", name(player), to.0, to.1, from.0, from.1),
+					GameAction::Grow(row, col) => format!("{} grew the tree at row {}, col {}", name(player), row, col),
+					GameAction::Collect(row, col) => format!("{} collected the tree at row {}, col {}", name(player), row, col),
+					GameAction::EndTurn => format!("{} ended their turn", name(player)),
+					GameAction::PlaceGreatElderTree(row, col) => format!("{} placed the Great Elder Tree at row {}, col {}", name(player), row, col),
+					GameAction::PlaceMoonstone(row, col) => format!("{} placed a moonstone at row {}, col {}", name(player), row, col),
+					GameAction::PlaceStartingTree(row, col) => format!("{} placed a starting tree at row {}, col {}", name(player), row, col),
+					GameAction::Undo => format!("{} took back their last action", name(player)),
+					GameAction::Redo => format!("{} redid an action", name(player)),
+				}//end matching action
+			},
+			LogEntry::LightGained {round, gained} => {
+				let gains: Vec<String> = gained.iter().enumerate().map(|(index, light)| format!("{} +{}", name(&index), light)).collect();
+				format!("Round {} light: {}", round, gains.join(", "))
+			},
+			LogEntry::LunarGained {round, gained} => {
+				let gains: Vec<String> = gained.iter().enumerate().map(|(index, lunar)| format!("{} +{}", name(&index), lunar)).collect();
+				format!("Round {} moonlight: {}", round, gains.join(", "))
+			},
+			LogEntry::ElderTreeBonus {gained} => {
+				let gains: Vec<String> = gained.iter().enumerate().map(|(index, lunar)| format!("{} +{}", name(&index), lunar)).collect();
+				format!("Great Elder Tree shelter: {}", gains.join(", "))
+			},
+			LogEntry::TokenTaken {player, fertility, value} => format!("{} took a {} scoring token worth {}", name(player), fertility, value),
+			LogEntry::SunMoved(direction) => format!("The sun moved, now {}", direction),
+			LogEntry::MoonMoved(moon) => format!("The moon moved between row {}, col {} and row {}, col {}, {}", moon.row1, moon.col1, moon.row2, moon.col2, moon.direction),
+			LogEntry::GameOver => "The game is over".to_string(),
+		}//end matching self
+	}//end describe(&self, players)
+}//end impl for LogEntry
+
+/// # Replay
+///
+/// Everything needed to play back a game from the start.
+///
+/// Since a new game always starts the same way for the same players and rules, a replay only needs to know who played, which rules they used, and what was logged.
+/// The state at any point can be rebuilt by starting a new game and carrying out each action in order.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Replay {
+	/// name and color of each player, in turn order
+	pub(crate) players: Vec<(String, (u8,u8,u8))>,
+	/// the rules the game was played with
+	pub(crate) rules: RuleSet,
+	/// everything which was logged during the game
+	pub(crate) entries: Vec<LogEntry>,
+}//end struct Replay
+
+#[allow(dead_code)]
+impl Replay {
+	/// # from_state(state)
+	///
+	/// Creates a replay of everything that has happened so far in a game.
+	pub fn from_state(state: &GameState) -> Replay {
+		Replay {
+			players: state.players.iter().map(|player| (player.name.clone(), player.color)).collect(),
+			rules: state.rules.clone(),
+			entries: state.log.clone(),
+		}//end struct construction
+	}//end from_state(state)
+
+	/// # to_lines(&self)
+	///
+	/// Writes this replay out as lines of text, which can be read back in with from_lines().
+	pub fn to_lines(&self) -> Vec<String> {
+		let mut lines = vec!["photosynthesis replay 1".to_string()];
+		for (name, color) in &self.players {
+			lines.push(format!("player {} {}", encode_color(*color), name));
+		}//end adding each player
+		lines.extend(encode_rules(&self.rules));
+		for entry in &self.entries {
+			lines.push(format!("log {}", encode_log_entry(entry)));
+		}//end adding each log entry
+		return lines;
+	}//end to_lines(&self)
+
+	/// # from_lines(lines)
+	///
+	/// Reads a replay written by to_lines(). Replays without any rule lines were played with the official rules.
+	pub fn from_lines(lines: &[String]) -> Result<Replay, String> {
+		if lines.first().map(|line| line.as_str()) != Some("photosynthesis replay 1") {
+			return Err("This doesn't look like a replay file.".to_string());
+		}//end if header is missing
+
+		let mut replay = Replay {players: Vec::new(), rules: RuleSet::default(), entries: Vec::new()};
+		for line in lines.iter().skip(1) {
+			match line.split_once(' ') {
+				Some(("player", rest)) => {
+					let (color, name) = rest.split_once(' ').unwrap_or((rest, ""));
+					let color = decode_color(color).ok_or(format!("Couldn't read player \"{}\"", line))?;
+					replay.players.push((name.to_string(), color));
+				},
+				Some(("rule", _)) => decode_rule(&mut replay.rules, line)?,
+				Some(("log", rest)) => replay.entries.push(decode_log_entry(rest)?),
+				_ if line.trim().is_empty() => {},
+				_ => return Err(format!("Couldn't read replay line \"{}\"", line)),
+			}//end matching type of line
+		}//end looping over each line
+		return Ok(replay);
+	}//end from_lines(lines)
+
+	/// # save(&self, path)
+	///
+	/// Writes this replay to a file.
+	pub fn save(&self, path: &str) -> Result<(), String> {
+		let mut contents = self.to_lines().join("\n");
+		contents += "\n";
+		return fs::write(path, contents).map_err(|e| format!("Couldn't save replay to {}. Details:\n{}", path, e));
+	}//end save(&self, path)
+
+	/// # load(path)
+	///
+	/// Reads a replay from a file.
+	pub fn load(path: &str) -> Result<Replay, String> {
+		let contents = fs::read_to_string(path).map_err(|e| format!("Couldn't open replay {}. Details:\n{}", path, e))?;
+		let lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+		return Replay::from_lines(&lines);
+	}//end load(path)
+
+	/// # step_count(&self)
+	///
+	/// Returns the number of actions in this replay. Step 0 is the start of the game, and step n is just after the nth action.
+	pub fn step_count(&self) -> usize {
+		return self.actions().count();
+	}//end step_count(&self)
+
+	/// # actions(&self)
+	///
+	/// Helper method to go through just the actions in the log.
+	fn actions(&self) -> impl Iterator<Item = GameAction> + '_ {
+		return self.entries.iter().filter_map(|entry| match entry {
+			LogEntry::Action {action, ..} => Some(*action),
+			_ => None,
+		});
+	}//end actions(&self)
+
+	/// # state_at(&self, step)
+	///
+	/// Rebuilds the state of the game as it was after the given number of actions.
+	pub fn state_at(&self, step: usize) -> Result<GameState, String> {
+		let players = self.players.iter().map(|(name, color)| Player::new(name, *color)).collect();
+		let mut state = GameState::with_rules(players, self.rules.clone());
+		for (index, action) in self.actions().take(step).enumerate() {
+			state.apply(action).map_err(|msg| format!("Replay couldn't carry out action {} ({}). Details:\n{}", index + 1, action, msg))?;
+		}//end carrying out each action up to step
+		return Ok(state);
+	}//end state_at(&self, step)
+
+	/// # entries_for_step(&self, step)
+	///
+	/// Returns the log entries that belong to a step, which is the action for that step, and everything that happened because of it.
+	///
+	/// Step 0 gives everything that happened before the first action.
+	pub fn entries_for_step(&self, step: usize) -> Vec<&LogEntry> {
+		let mut actions_seen = 0;
+		let mut entries = Vec::new();
+		for entry in &self.entries {
+			if let LogEntry::Action {..} = entry {
+				actions_seen += 1;
+			}//end if this entry is an action
+			if actions_seen == step {
+				entries.push(entry);
+			}//end if this entry belongs to step
+			else if actions_seen > step {
+				break;
+			}//end else if we've gone past step
+		}//end looping over entries
+		return entries;
+	}//end entries_for_step(&self, step)
+}//end impl for Replay
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::TreeSize;
+	use crate::game::tests::{players, started_game};
+
+	/// # replay_rebuilds_the_same_state
+	///
+	/// Plays a few rounds, then checks that the replay read back from text rebuilds exactly the same game.
+	#[test]
+	fn replay_rebuilds_the_same_state() {
+		let mut state = started_game(players(3));
+		for _ in 0..3 {
+			for _ in 0..3 {
+				// not every purchase will be affordable, which is fine since refused actions aren't logged
+				let _ = state.apply(GameAction::Buy(TreeSize::Seed));
+				let _ = state.apply(GameAction::Buy(TreeSize::Medium));
+				state.apply(GameAction::EndTurn).unwrap();
+			}//end each player taking a turn
+		}//end playing a few rounds
+
+		let replay = Replay::from_lines(&Replay::from_state(&state).to_lines()).unwrap();
+		assert_eq!(replay, Replay::from_state(&state));
+		assert_eq!(replay.state_at(replay.step_count()).unwrap(), state);
+		assert_eq!(replay.state_at(0).unwrap().round, 1);
+	}//end replay_rebuilds_the_same_state()
+}//end mod tests
@@ -0,0 +1,602 @@
+use std::fs;
+use super::TreeSize;
+
+/// # ShadeRules
+///
+/// How tall each piece is, and how far its shadow reaches.
+///
+/// A spot is shaded by a shadow at least as tall as the tree on it. Spots without a tree are shaded by any shadow, except for the Great Elder Tree, which is never shaded.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct ShadeRules {
+	/// height of each tree size, indexed by TreeSize::size()
+	pub(crate) tree_heights: [usize; 4],
+	/// number of spots the shadow of each tree size covers, indexed by TreeSize::size()
+	pub(crate) tree_lengths: [usize; 4],
+	/// height of the Great Elder Tree
+	pub(crate) elder_height: usize,
+	/// number of spots the shadow of the Great Elder Tree covers, where 0 means it reaches all the way to the edge of the board
+	pub(crate) elder_length: usize,
+	/// height of a moonstone
+	pub(crate) moonstone_height: usize,
+	/// number of spots the shadow of a moonstone covers
+	pub(crate) moonstone_length: usize,
+}//end struct ShadeRules
+
+impl Default for ShadeRules {
+	fn default() -> Self {
+		ShadeRules {
+			tree_heights: [0, 1, 2, 3],
+			tree_lengths: [0, 1, 2, 3],
+			elder_height: 4,
+			elder_length: 0,
+			moonstone_height: 1,
+			moonstone_length: 1,
+		}//end struct construction
+	}//end default()
+}//end impl Default for ShadeRules
+
+#[allow(dead_code)]
+impl ShadeRules {
+	/// # elder_reach(&self, rows, cols)
+	///
+	/// Returns the number of spots the shadow of the Great Elder Tree covers on a board of the given size.
+	pub fn elder_reach(&self, rows: usize, cols: usize) -> usize {
+		return if self.elder_length == 0 {rows + cols} else {self.elder_length};
+	}//end elder_reach(&self, rows, cols)
+}//end impl for ShadeRules
+
+/// # RuleSet
+///
+/// Every number in the rules which can be changed for house rules, grouped the same way as the sections of a rules file.
+///
+/// The default is the official rules. A rules file only needs to list the numbers it changes, see from_toml().
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct RuleSet {
+	/// name of this set of rules, to show to players
+	pub(crate) name: String,
+	/// number of spots along each side of the square board
+	pub(crate) board_size: usize,
+	/// small trees each player places on the edge of the board while setting up
+	pub(crate) starting_trees: usize,
+	/// trees each player starts with in their available area, indexed by TreeSize::size(). Starting trees are taken from here.
+	pub(crate) starting_available: [usize; 4],
+	/// shade heights and lengths
+	pub(crate) shade: ShadeRules,
+	/// how many spaces away each tree size can throw a seed, indexed by TreeSize::size()
+	pub(crate) seed_ranges: [usize; 4],
+	/// how many spaces away the Great Elder Tree can throw a seed
+	pub(crate) elder_seed_range: usize,
+	/// light points it costs to plant a seed
+	pub(crate) plant_cost: usize,
+	/// light points it costs to grow into each tree size, indexed by TreeSize::size(). Nothing grows into a seed, so the first one isn't used.
+	pub(crate) grow_costs: [usize; 4],
+	/// light points it costs to collect a large tree
+	pub(crate) collect_cost: usize,
+	/// light points each tree size earns in the photosynthesis phase, indexed by TreeSize::size()
+	pub(crate) light_yields: [usize; 4],
+	/// the most light points a player can hold
+	pub(crate) max_light: usize,
+	/// number of times the sun goes all the way around the board before the game ends
+	pub(crate) revolutions: usize,
+	/// how far the moon moves each round, beyond the length of one side of the board
+	pub(crate) moon_extra_steps: usize,
+	/// lunar points each lit tree gives under a half moon
+	pub(crate) half_moon_points: usize,
+	/// lunar points each lit tree gives under a full moon
+	pub(crate) full_moon_points: usize,
+	/// lunar points a lit moonstone gives for each tree around it
+	pub(crate) moonstone_points: usize,
+	/// lunar points the Great Elder Tree gives at the end of the game for each tree around it
+	pub(crate) elder_points: usize,
+	/// the most moonstones that can be placed while setting up
+	pub(crate) max_moonstones: usize,
+	/// prices of the slots on each player board, for each tree size, indexed by TreeSize::size()
+	pub(crate) store_prices: [Vec<usize>; 4],
+	/// scoring tokens in each stack from bottom to top, for each fertility from one leaf up to four leaf
+	pub(crate) scoring_stacks: [Vec<usize>; 4],
+	/// the four leaf stack is only used with at least this many players
+	pub(crate) four_leaf_min_players: usize,
+	/// light points left over at the end of the game are worth one point for every this many, which is 3 in the official rules. 0 means they aren't worth anything.
+	pub(crate) light_per_point: usize,
+}//end struct RuleSet
+
+impl Default for RuleSet {
+	/// # default()
+	///
+	/// The official rules.
+	fn default() -> Self {
+		RuleSet {
+			name: "Official".to_string(),
+			board_size: 7,
+			starting_trees: 2,
+			starting_available: [2, 4, 1, 0],
+			shade: ShadeRules::default(),
+			seed_ranges: [0, 1, 2, 3],
+			elder_seed_range: 3,
+			plant_cost: 1,
+			grow_costs: [0, 1, 2, 3],
+			collect_cost: 4,
+			light_yields: [0, 1, 2, 3],
+			max_light: 20,
+			revolutions: 3,
+			moon_extra_steps: 2,
+			half_moon_points: 1,
+			full_moon_points: 2,
+			moonstone_points: 1,
+			elder_points: 1,
+			max_moonstones: 4,
+			store_prices: [vec![1,1,2,2], vec![2,2,3,3], vec![3,3,4], vec![4,5]],
+			scoring_stacks: [
+				vec![12,12,12,12,13,13,13,14,14],
+				vec![13,13,14,14,16,16,17],
+				vec![17,17,18,18,19],
+				vec![20,21,22],
+			],
+			four_leaf_min_players: 3,
+			light_per_point: 3,
+		}//end struct construction
+	}//end default()
+}//end impl Default for RuleSet
+
+/// # TomlValue
+///
+/// A value read from a rules file. Only the parts of TOML that rules files need are supported.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TomlValue {
+	Integer(i64),
+	Boolean(bool),
+	Text(String),
+	Array(Vec<TomlValue>),
+}//end enum TomlValue
+
+#[allow(dead_code)]
+impl RuleSet {
+	/// # preset_names()
+	///
+	/// Returns the names of the built in rule sets, which can be passed to preset().
+	pub fn preset_names() -> Vec<&'static str> {
+		return vec!["official", "expert"];
+	}//end preset_names()
+
+	/// # preset(name)
+	///
+	/// Returns one of the built in rule sets.
+	///
+	/// The expert rules have longer shadows, dearer collecting, a lower light limit and a faster moon, over four revolutions of the sun.
+	pub fn preset(name: &str) -> Result<RuleSet, String> {
+		match name.to_lowercase().as_str() {
+			"official" => Ok(RuleSet::default()),
+			"expert" => {
+				let mut rules = RuleSet {name: "Expert".to_string(), ..RuleSet::default()};
+				rules.shade.tree_lengths = [0, 2, 3, 4];
+				rules.collect_cost = 5;
+				rules.max_light = 15;
+				rules.revolutions = 4;
+				rules.moon_extra_steps = 3;
+				Ok(rules)
+			},
+			_ => Err(format!("There aren't any built in rules called \"{}\". Try one of: {}", name, RuleSet::preset_names().join(", "))),
+		}//end matching name
+	}//end preset(name)
+
+	/// # load(path)
+	///
+	/// Reads a rules file. Anything the file doesn't mention is left as in the official rules.
+	///
+	/// The rules are checked with validate() once the whole file has been read.
+	pub fn load(path: &str) -> Result<RuleSet, String> {
+		let contents = fs::read_to_string(path).map_err(|e| format!("Couldn't open rules file {}. Details:\n{}", path, e))?;
+		return RuleSet::from_toml(&contents).map_err(|msg| format!("Couldn't read rules file {}. Details:\n{}", path, msg));
+	}//end load(path)
+
+	/// # save(&self, path)
+	///
+	/// Writes these rules to a file, which can be read back with load().
+	pub fn save(&self, path: &str) -> Result<(), String> {
+		return fs::write(path, self.to_toml()).map_err(|e| format!("Couldn't save rules to {}. Details:\n{}", path, e));
+	}//end save(&self, path)
+
+	/// # from_toml(text)
+	///
+	/// Reads rules from the text of a rules file, starting from the official rules and changing whatever the file lists.
+	///
+	/// A file can start from a preset instead by setting `base = "expert"` before any section.
+	pub fn from_toml(text: &str) -> Result<RuleSet, String> {
+		let entries = parse_toml(text)?;
+		let mut rules = match entries.iter().find(|(key, _)| key == "base") {
+			Some((_, TomlValue::Text(base))) => RuleSet::preset(base)?,
+			Some(_) => return Err("base needs to be the name of a preset, in quotes.".to_string()),
+			None => RuleSet::default(),
+		};//end matching which rules to start from
+		for (key, value) in &entries {
+			if key != "base" {
+				rules.set(key, value)?;
+			}//end if this isn't where we started from
+		}//end setting each value
+		rules.validate()?;
+		return Ok(rules);
+	}//end from_toml(text)
+
+	/// # validate(&self)
+	///
+	/// Checks that a game could actually be played with these rules.
+	///
+	/// Settings are checked here rather than in set(), since some of them depend on each other and a file can list them in any order.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.revolutions == 0 {
+			return Err("game.revolutions should be at least 1, or the game would be over before it starts.".to_string());
+		}//end if there wouldn't be any rounds
+		if self.starting_trees > self.starting_available[TreeSize::Small.size()] {
+			return Err(format!("setup.starting_trees is {}, but players only start with {} small trees available (the second number in setup.available).",
+				self.starting_trees, self.starting_available[TreeSize::Small.size()]));
+		}//end if players couldn't place all their starting trees
+		return Ok(());
+	}//end validate(&self)
+
+	/// # to_toml(&self)
+	///
+	/// Writes out every number in these rules as the text of a rules file.
+	pub fn to_toml(&self) -> String {
+		let mut text = String::new();
+		let mut section = "";
+		for (key, value) in self.entries() {
+			let (this_section, name) = key.split_once('.').unwrap_or(("", key));
+			if this_section != section {
+				text += &format!("\n[{}]\n", this_section);
+				section = this_section;
+			}//end if we're starting a new section
+			text += &format!("{} = {}\n", name, toml_text(&value));
+		}//end writing each entry
+		return text.trim_start().to_string();
+	}//end to_toml(&self)
+
+	/// # entries(&self)
+	///
+	/// Lists every setting, keyed by section and name, in the order they're written to a file.
+	pub fn entries(&self) -> Vec<(&'static str, TomlValue)> {
+		let number = |n: usize| TomlValue::Integer(n as i64);
+		let list = |numbers: &[usize]| TomlValue::Array(numbers.iter().map(|n| TomlValue::Integer(*n as i64)).collect());
+		let lists = |all: &[Vec<usize>; 4]| TomlValue::Array(all.iter().map(|numbers| list(numbers)).collect());
+		return vec![
+			("name", TomlValue::Text(self.name.clone())),
+			("setup.board_size", number(self.board_size)),
+			("setup.starting_trees", number(self.starting_trees)),
+			("setup.available", list(&self.starting_available)),
+			("shade.tree_heights", list(&self.shade.tree_heights)),
+			("shade.tree_lengths", list(&self.shade.tree_lengths)),
+			("shade.elder_height", number(self.shade.elder_height)),
+			("shade.elder_length", number(self.shade.elder_length)),
+			("shade.moonstone_height", number(self.shade.moonstone_height)),
+			("shade.moonstone_length", number(self.shade.moonstone_length)),
+			("seeding.ranges", list(&self.seed_ranges)),
+			("seeding.elder_range", number(self.elder_seed_range)),
+			("costs.plant", number(self.plant_cost)),
+			("costs.grow", list(&self.grow_costs)),
+			("costs.collect", number(self.collect_cost)),
+			("light.yields", list(&self.light_yields)),
+			("light.max", number(self.max_light)),
+			("game.revolutions", number(self.revolutions)),
+			("moon.extra_steps", number(self.moon_extra_steps)),
+			("moon.half_moon_points", number(self.half_moon_points)),
+			("moon.full_moon_points", number(self.full_moon_points)),
+			("moon.moonstone_points", number(self.moonstone_points)),
+			("moon.elder_points", number(self.elder_points)),
+			("moon.max_moonstones", number(self.max_moonstones)),
+			("store.prices", lists(&self.store_prices)),
+			("scoring.stacks", lists(&self.scoring_stacks)),
+			("scoring.four_leaf_min_players", number(self.four_leaf_min_players)),
+			("scoring.light_per_point", number(self.light_per_point)),
+		];
+	}//end entries(&self)
+
+	/// # set(&mut self, key, value)
+	///
+	/// Changes a single setting, keyed by section and name in the same way as entries().
+	pub fn set(&mut self, key: &str, value: &TomlValue) -> Result<(), String> {
+		let number = || as_number(key, value);
+		let four = || as_four(key, value);
+		let four_lists = || as_four_lists(key, value);
+		match key {
+			"name" => match value {
+				TomlValue::Text(name) => self.name = name.clone(),
+				_ => return Err(format!("{} should be some text in quotes.", key)),
+			},
+			"setup.board_size" => {
+				let size = number()?;
+				if size < 3 {
+					return Err(format!("{} should be at least 3.", key));
+				}//end if board is too small to play on
+				self.board_size = size;
+			},
+			"setup.starting_trees" => self.starting_trees = number()?,
+			"setup.available" => self.starting_available = four()?,
+			"shade.tree_heights" => self.shade.tree_heights = four()?,
+			"shade.tree_lengths" => self.shade.tree_lengths = four()?,
+			"shade.elder_height" => self.shade.elder_height = number()?,
+			"shade.elder_length" => self.shade.elder_length = number()?,
+			"shade.moonstone_height" => self.shade.moonstone_height = number()?,
+			"shade.moonstone_length" => self.shade.moonstone_length = number()?,
+			"seeding.ranges" => self.seed_ranges = four()?,
+			"seeding.elder_range" => self.elder_seed_range = number()?,
+			"costs.plant" => self.plant_cost = number()?,
+			"costs.grow" => self.grow_costs = four()?,
+			"costs.collect" => self.collect_cost = number()?,
+			"light.yields" => self.light_yields = four()?,
+			"light.max" => self.max_light = number()?,
+			"game.revolutions" => self.revolutions = number()?,
+			"moon.extra_steps" => self.moon_extra_steps = number()?,
+			"moon.half_moon_points" => self.half_moon_points = number()?,
+			"moon.full_moon_points" => self.full_moon_points = number()?,
+			"moon.moonstone_points" => self.moonstone_points = number()?,
+			"moon.elder_points" => self.elder_points = number()?,
+			"moon.max_moonstones" => self.max_moonstones = number()?,
+			"store.prices" => self.store_prices = four_lists()?,
+			"scoring.stacks" => self.scoring_stacks = four_lists()?,
+			"scoring.four_leaf_min_players" => self.four_leaf_min_players = number()?,
+			"scoring.light_per_point" => self.light_per_point = number()?,
+			_ => return Err(format!("There isn't a rule called {}.", key)),
+		}//end matching key
+		return Ok(());
+	}//end set(&mut self, key, value)
+}//end impl for RuleSet
+
+/// # as_number(key, value)
+///
+/// Helper function to read a setting which should be a whole number.
+fn as_number(key: &str, value: &TomlValue) -> Result<usize, String> {
+	match value {
+		TomlValue::Integer(n) => usize::try_from(*n).map_err(|_| format!("{} should be a whole number, and can't be negative.", key)),
+		_ => Err(format!("{} should be a whole number.", key)),
+	}//end matching value
+}//end as_number(key, value)
+
+/// # as_list(key, value)
+///
+/// Helper function to read a setting which should be a list of whole numbers.
+fn as_list(key: &str, value: &TomlValue) -> Result<Vec<usize>, String> {
+	match value {
+		TomlValue::Array(items) => items.iter().map(|item| as_number(key, item)).collect::<Result<Vec<usize>, String>>()
+			.map_err(|_| format!("{} should be a list of whole numbers.", key)),
+		_ => Err(format!("{} should be a list of whole numbers.", key)),
+	}//end matching value
+}//end as_list(key, value)
+
+/// # as_four(key, value)
+///
+/// Helper function to read a setting which should have one whole number for each tree size.
+fn as_four(key: &str, value: &TomlValue) -> Result<[usize; 4], String> {
+	let numbers = as_list(key, value)?;
+	return numbers.try_into().map_err(|_| format!("{} should be a list of 4 whole numbers, one for each tree size.", key));
+}//end as_four(key, value)
+
+/// # as_four_lists(key, value)
+///
+/// Helper function to read a setting which should have a list of whole numbers for each tree size or fertility.
+fn as_four_lists(key: &str, value: &TomlValue) -> Result<[Vec<usize>; 4], String> {
+	let wrong = || format!("{} should be four lists of whole numbers.", key);
+	let lists = match value {
+		TomlValue::Array(items) => items.iter().map(|item| as_list(key, item).map_err(|_| wrong())).collect::<Result<Vec<Vec<usize>>, String>>()?,
+		_ => return Err(wrong()),
+	};//end matching value
+	return lists.try_into().map_err(|_| wrong());
+}//end as_four_lists(key, value)
+
+/// # parse_toml(text)
+///
+/// Reads the simple TOML used by rules files, giving each value along with its key, prefixed by its section like "section.key".
+///
+/// Supports comments, [section] headers, and values which are whole numbers (which may be negative), true or false, text in double quotes (where \" is a quote, \\ a backslash and \n a new line), or lists of those in square brackets.
+pub(crate) fn parse_toml(text: &str) -> Result<Vec<(String, TomlValue)>, String> {
+	let mut entries = Vec::new();
+	let mut section = String::new();
+	for (line_number, line) in text.lines().enumerate() {
+		let bad_line = |msg: &str| format!("Line {}: {}", line_number + 1, msg);
+		let line = strip_comment(line).trim();
+		if line.is_empty() {
+			continue;
+		}//end if there's nothing on this line
+		if let Some(name) = line.strip_prefix('[') {
+			section = name.strip_suffix(']').ok_or(bad_line("section header is missing a ]"))?.trim().to_string();
+			continue;
+		}//end if this is a section header
+		let (key, value) = line.split_once('=').ok_or(bad_line("expected key = value"))?;
+		let key = key.trim();
+		if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+			return Err(bad_line(&format!("\"{}\" isn't a valid key", key)));
+		}//end if key has odd characters in it
+		let (value, rest) = parse_toml_value(value.trim()).map_err(|msg| bad_line(&msg))?;
+		if !rest.trim().is_empty() {
+			return Err(bad_line(&format!("unexpected \"{}\" after value", rest.trim())));
+		}//end if there's something after the value
+		let full_key = if section.is_empty() {key.to_string()} else {format!("{}.{}", section, key)};
+		entries.push((full_key, value));
+	}//end looping over each line
+	return Ok(entries);
+}//end parse_toml(text)
+
+/// # strip_comment(line)
+///
+/// Helper function to cut off a comment at the end of a line, leaving any # inside quotes alone.
+fn strip_comment(line: &str) -> &str {
+	let mut in_quotes = false;
+	let mut escaped = false;
+	for (index, c) in line.char_indices() {
+		match c {
+			_ if escaped => escaped = false,
+			'\\' if in_quotes => escaped = true,
+			'"' => in_quotes = !in_quotes,
+			'#' if !in_quotes => return &line[..index],
+			_ => {},
+		}//end matching character
+	}//end looping over characters
+	return line;
+}//end strip_comment(line)
+
+/// # parse_toml_value(text)
+///
+/// Helper function to read one value from the start of text, returning the value and whatever is left after it.
+fn parse_toml_value(text: &str) -> Result<(TomlValue, &str), String> {
+	let text = text.trim_start();
+	if let Some(rest) = text.strip_prefix('[') {
+		let mut items = Vec::new();
+		let mut rest = rest.trim_start();
+		loop {
+			if let Some(after) = rest.strip_prefix(']') {
+				return Ok((TomlValue::Array(items), after));
+			}//end if list is finished
+			let (item, after) = parse_toml_value(rest)?;
+			items.push(item);
+			rest = after.trim_start();
+			if let Some(after) = rest.strip_prefix(',') {
+				rest = after.trim_start();
+			}//end if there's a comma before the next item
+			else if !rest.starts_with(']') {
+				return Err("list items need commas between them, and a ] at the end".to_string());
+			}//end else if list isn't finished properly
+		}//end looping over items in list
+	}//end if this is a list
+	if let Some(rest) = text.strip_prefix('"') {
+		let mut contents = String::new();
+		let mut chars = rest.char_indices();
+		while let Some((index, c)) = chars.next() {
+			match c {
+				'"' => return Ok((TomlValue::Text(contents), &rest[index + 1..])),
+				'\\' => match chars.next() {
+					Some((_, '\\')) => contents.push('\\'),
+					Some((_, '"')) => contents.push('"'),
+					Some((_, 'n')) => contents.push('\n'),
+					Some((_, other)) => return Err(format!("\\{} isn't an escape this file understands. Use \\\\ for a backslash", other)),
+					None => break,
+				},//end matching escaped character
+				_ => contents.push(c),
+			}//end matching character
+		}//end looping over characters in text
+		return Err("text is missing its closing quote".to_string());
+	}//end if this is text
+	let sign = if text.starts_with('-') {1} else {0};
+	let end = text[sign..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).map_or(text.len(), |end| sign + end);
+	let (word, rest) = text.split_at(end);
+	match word {
+		"true" => return Ok((TomlValue::Boolean(true), rest)),
+		"false" => return Ok((TomlValue::Boolean(false), rest)),
+		_ => {},
+	}//end matching booleans
+	let number = word.replace('_', "").parse::<i64>().map_err(|_| format!("\"{}\" isn't a whole number, true, false, text or a list", word))?;
+	return Ok((TomlValue::Integer(number), rest));
+}//end parse_toml_value(text)
+
+/// # toml_value(text)
+///
+/// Reads a single value written by toml_text(), with nothing else after it.
+pub(crate) fn toml_value(text: &str) -> Result<TomlValue, String> {
+	let (value, rest) = parse_toml_value(text)?;
+	if !rest.trim().is_empty() {
+		return Err(format!("unexpected \"{}\" after value", rest.trim()));
+	}//end if there's something after the value
+	return Ok(value);
+}//end toml_value(text)
+
+/// # toml_text(value)
+///
+/// Helper function to write a value the way it would appear in a rules file.
+pub(crate) fn toml_text(value: &TomlValue) -> String {
+	match value {
+		TomlValue::Integer(n) => n.to_string(),
+		TomlValue::Boolean(b) => b.to_string(),
+		TomlValue::Text(text) => format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")),
+		TomlValue::Array(items) => format!("[{}]", items.iter().map(toml_text).collect::<Vec<String>>().join(", ")),
+	}//end matching value
+}//end toml_text(value)
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rules_file_only_changes_what_it_lists() {
+		let text = "
+			# house rules for long games
+			base = \"official\"
+			name = \"Long # Game\"
+
+			[game]
+			revolutions = 4 # one more time around
+
+			[shade]
+			tree_lengths = [0, 1, 2, 4]
+
+			[scoring]
+			stacks = [[1, 2], [3], [], [4, 5, 6]]
+		";
+		let rules = RuleSet::from_toml(text).unwrap();
+		assert_eq!(rules.name, "Long # Game");
+		assert_eq!(rules.revolutions, 4);
+		assert_eq!(rules.shade.tree_lengths, [0, 1, 2, 4]);
+		assert_eq!(rules.scoring_stacks, [vec![1, 2], vec![3], vec![], vec![4, 5, 6]]);
+		assert_eq!(rules.shade.tree_heights, RuleSet::default().shade.tree_heights);
+		assert_eq!(rules.collect_cost, 4);
+	}//end rules_file_only_changes_what_it_lists()
+
+	#[test]
+	fn presets_survive_a_trip_through_a_file() {
+		for name in RuleSet::preset_names() {
+			let rules = RuleSet::preset(name).unwrap();
+			assert_eq!(RuleSet::from_toml(&rules.to_toml()).unwrap(), rules, "{}", rules.to_toml());
+		}//end trying each preset
+		let expert = RuleSet::from_toml("base = \"expert\"\n[light]\nmax = 12").unwrap();
+		assert_eq!(expert.revolutions, 4);
+		assert_eq!(expert.max_light, 12);
+	}//end presets_survive_a_trip_through_a_file()
+
+	#[test]
+	fn bad_rules_files_say_what_went_wrong() {
+		for (text, problem) in [
+			("[costs]\ngrow = [1, 2]", "costs.grow should be a list of 4"),
+			("[costs]\nplant = \"one\"", "costs.plant should be a whole number"),
+			("[light]\nbrightness = 3", "There isn't a rule called light.brightness"),
+			("[light\nmax = 3", "Line 1"),
+			("base = \"easy\"", "There aren't any built in rules called \"easy\""),
+			("[store]\nprices = [[1], [2], [3]]", "four lists"),
+			("[costs]\ncollect = -4", "costs.collect should be a whole number, and can't be negative"),
+			("[game]\nrevolutions = 0", "game.revolutions should be at least 1"),
+			("[setup]\nstarting_trees = 3\navailable = [2, 2, 1, 0]", "setup.starting_trees is 3"),
+		] {
+			let msg = RuleSet::from_toml(text).unwrap_err();
+			assert!(msg.contains(problem), "{:?} gave {:?}", text, msg);
+		}//end trying each bad file
+	}//end bad_rules_files_say_what_went_wrong()
+
+	#[test]
+	fn games_remember_their_rules() {
+		use crate::game::{GameState, Replay, encoding::{apply_state_lines, encode_state}, tests::players};
+		let players = players(2);
+		let mut rules = RuleSet::preset("expert").unwrap();
+		rules.name = "Quoted # name".to_string();
+		let state = GameState::with_rules(players.clone(), rules.clone());
+		assert_eq!(state.max_rounds, 32);
+
+		let mut loaded = GameState::new(players);
+		apply_state_lines(&mut loaded, &encode_state(&state)).unwrap();
+		assert_eq!(loaded.rules, rules);
+		let replay = Replay::from_lines(&Replay::from_state(&state).to_lines()).unwrap();
+		assert_eq!(replay.state_at(0).unwrap().rules, rules);
+	}//end games_remember_their_rules()
+
+	#[test]
+	fn text_with_quotes_and_backslashes_survives_a_trip_through_a_file() {
+		let rules = RuleSet {name: "Bob's \"fast\" rules # C:\\games\\\nsecond line".to_string(), ..RuleSet::default()};
+		let text = rules.to_toml();
+		assert_eq!(RuleSet::from_toml(&text).unwrap(), rules, "{}", text);
+		let value = TomlValue::Text(rules.name.clone());
+		assert_eq!(toml_value(&toml_text(&value)).unwrap(), value);
+		assert!(toml_value("\"unfinished \\\"").is_err());
+	}//end text_with_quotes_and_backslashes_survives_a_trip_through_a_file()
+
+	#[test]
+	fn numbers_can_be_negative() {
+		let value = TomlValue::Array(vec![TomlValue::Integer(-40), TomlValue::Integer(1_000)]);
+		assert_eq!(toml_value("[-40, 1_000]").unwrap(), value);
+		assert_eq!(toml_value(&toml_text(&value)).unwrap(), value);
+		assert!(toml_value("-").is_err());
+	}//end numbers_can_be_negative()
+}//end mod tests
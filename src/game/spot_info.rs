@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+use super::{Animal, Fertility, GameState, PieceType, TreeSize};
+
+/// # SpotInfo
+///
+/// Everything the game knows about one spot on the board, gathered up to be shown to the players.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SpotInfo {
+	/// row of the spot
+	pub(crate) row: usize,
+	/// column of the spot
+	pub(crate) col: usize,
+	/// how fertile the spot is
+	pub(crate) fertility: Fertility,
+	/// the type of piece on the spot
+	pub(crate) piece_type: PieceType,
+	/// size of the tree on the spot, if there is one
+	pub(crate) tree: Option<TreeSize>,
+	/// name of the player who owns the tree, if anyone does
+	pub(crate) owner: Option<String>,
+	/// the forest animal on the spot, if there is one
+	pub(crate) animal: Option<Animal>,
+	/// whether an action has been taken from the spot this turn
+	pub(crate) expended: bool,
+	/// whether the spot is in shade from the sun where it is now
+	pub(crate) shaded: bool,
+	/// whether the moon reaches the spot where it is now
+	pub(crate) moon_lit: bool,
+	/// light points the tree would give its owner once the sun moves at the end of this round, assuming nothing else changes
+	pub(crate) light_next_round: usize,
+}//end struct SpotInfo
+
+#[allow(dead_code)]
+impl SpotInfo {
+	/// # new(state, row, col)
+	///
+	/// Gathers up everything about the spot at row, col. Returns None if there's no spot there.
+	pub fn new(state: &GameState, row: usize, col: usize) -> Option<SpotInfo> {
+		let spot = *state.board.board.get(row, col)?;
+		let shaded = *state.board.sun_shaded_with(&state.rules.shade).get(row, col).unwrap();
+		let moon_lit = *state.board.moon_lit().get(row, col).unwrap();
+
+		let mut next_board = state.board.clone();
+		next_board.sun = next_board.sun.next();
+		let shaded_next_round = *next_board.sun_shaded_with(&state.rules.shade).get(row, col).unwrap();
+		let light_next_round = match spot.tree {
+			Some(tree) if !shaded_next_round && state.owner_of(&tree).is_some() => state.rules.light_yields[tree.size.size()],
+			_ => 0,
+		};//end matching whether there's a lit tree to earn light
+
+		return Some(SpotInfo {
+			row,
+			col,
+			fertility: spot.fertility,
+			piece_type: spot.piece_type,
+			tree: spot.tree.map(|tree| tree.size),
+			owner: spot.tree.and_then(|tree| state.owner_of(&tree)).map(|owner| state.players[owner].name.clone()),
+			animal: spot.animal,
+			expended: spot.is_expended(),
+			shaded,
+			moon_lit,
+			light_next_round,
+		});//end struct construction
+	}//end new(state, row, col)
+}//end impl for SpotInfo
+
+impl Display for SpotInfo {
+	/// Writes one line for each thing known about the spot.
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let yes_no = |value: bool| if value {"yes"} else {"no"};
+		writeln!(f, "Row {}, column {}", self.row, self.col)?;
+		writeln!(f, "Fertility: {}", self.fertility)?;
+		writeln!(f, "Piece: {}", self.piece_type)?;
+		match (self.tree, &self.owner) {
+			(Some(size), Some(owner)) => writeln!(f, "Tree: {}, owned by {}", size, owner)?,
+			(Some(size), None) => writeln!(f, "Tree: {}, not owned by anyone playing", size)?,
+			(None, _) => writeln!(f, "Tree: none")?,
+		}//end matching whether there's a tree
+		match self.animal {
+			Some(animal) => writeln!(f, "Animal: {}", animal)?,
+			None => writeln!(f, "Animal: none")?,
+		}//end matching whether there's an animal
+		writeln!(f, "Expended this turn: {}", yes_no(self.expended))?;
+		writeln!(f, "In shade: {}", yes_no(self.shaded))?;
+		writeln!(f, "Lit by the moon: {}", yes_no(self.moon_lit))?;
+		write!(f, "Light next round: {}", self.light_next_round)
+	}//end fmt(&self, f)
+}//end impl Display for SpotInfo
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{Tree, default_player_color};
+	use crate::game::tests::{players, started_game};
+
+	#[test]
+	fn spot_info_describes_trees_and_empty_spots() {
+		let mut state = started_game(players(2));
+		let spot = state.board.board.get_mut(3, 3).unwrap();
+		spot.tree = Some(Tree {color: default_player_color(1), size: TreeSize::Large});
+		spot.piece_type = PieceType::Tree;
+		spot.expend();
+
+		// nothing else is on the board, so the tree can't be shaded
+		let info = SpotInfo::new(&state, 3, 3).unwrap();
+		assert_eq!(info.fertility, Fertility::FourLeaf);
+		assert_eq!(info.tree, Some(TreeSize::Large));
+		assert_eq!(info.owner.as_deref(), Some("Player 2"));
+		assert!(info.expended);
+		assert!(!info.shaded);
+		assert_eq!(info.moon_lit, *state.board.moon_lit().get(3, 3).unwrap());
+		assert_eq!(info.light_next_round, state.rules.light_yields[3]);
+		assert!(info.to_string().contains("Tree: Large, owned by Player 2"));
+
+		let empty = SpotInfo::new(&state, 0, 0).unwrap();
+		assert_eq!(empty.fertility, Fertility::OneLeaf);
+		assert_eq!((empty.tree, empty.owner, empty.light_next_round), (None, None, 0));
+		assert!(SpotInfo::new(&state, 7, 0).is_none());
+	}//end spot_info_describes_trees_and_empty_spots()
+}//end mod tests
@@ -0,0 +1,152 @@
+use super::{Fertility, GameState, LogEntry};
+
+/// # PlayerSummary
+///
+/// How one player's score adds up at the end of the game.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlayerSummary {
+	/// name of the player
+	pub(crate) name: String,
+	/// color of the player's trees
+	pub(crate) color: (u8,u8,u8),
+	/// values of the scoring tokens this player collected, for each fertility from one leaf up to four leaf
+	pub(crate) tokens: [Vec<usize>; 4],
+	/// lunar points this player has, including the Great Elder Tree bonus
+	pub(crate) lunar_points: usize,
+	/// light points this player had left over
+	pub(crate) light_left: usize,
+	/// points the leftover light is worth under the rules
+	pub(crate) light_score: usize,
+	/// seeds and trees this player has on the board, which breaks ties
+	pub(crate) trees_on_board: usize,
+	/// total score, counting tokens, lunar points and leftover light
+	pub(crate) total: usize,
+	/// where this player finished, starting from 1. Players who are still tied after the tiebreak share a place.
+	pub(crate) place: usize,
+}//end struct PlayerSummary
+
+/// # GameSummary
+///
+/// The results of a game, with everything needed to show how each player got their score.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GameSummary {
+	/// each player's results, in turn order
+	pub(crate) players: Vec<PlayerSummary>,
+	/// light points each player gained in the photosynthesis phase of each round, in round order. Each round is parallel to players.
+	pub(crate) light_income: Vec<(usize, Vec<usize>)>,
+}//end struct GameSummary
+
+#[allow(dead_code)]
+impl PlayerSummary {
+	/// # token_total(&self)
+	///
+	/// Returns the total value of every scoring token this player collected.
+	pub fn token_total(&self) -> usize {
+		return self.tokens.iter().flatten().sum();
+	}//end token_total(&self)
+}//end impl for PlayerSummary
+
+#[allow(dead_code)]
+impl GameSummary {
+	/// # new(state)
+	///
+	/// Adds up each player's score and works out who finished where. This can be called before the game is over to see the standings so far.
+	///
+	/// Players with the same score are split by who has the most seeds and trees on the board, and anyone still tied shares the place.
+	pub fn new(state: &GameState) -> GameSummary {
+		let fertilities = [Fertility::OneLeaf, Fertility::TwoLeaf, Fertility::ThreeLeaf, Fertility::FourLeaf];
+		let mut players: Vec<PlayerSummary> = state.players.iter().enumerate().map(|(index, player)| {
+			let tokens = fertilities.map(|fertility| player.scoring_tokens.iter().filter(|token| token.0 == fertility).map(|token| token.1).collect());
+			let light_score = player.light_points.checked_div(state.rules.light_per_point).unwrap_or(0);
+			let trees_on_board = state.board.board.iter().filter(|spot| spot.tree.is_some_and(|tree| state.owner_of(&tree) == Some(index))).count();
+			PlayerSummary {
+				name: player.name.clone(),
+				color: player.color,
+				tokens,
+				lunar_points: player.lunar_points,
+				light_left: player.light_points,
+				light_score,
+				trees_on_board,
+				total: player.score() + light_score,
+				place: 0,
+			}//end struct construction
+		}).collect();
+
+		let ranks: Vec<(usize,usize)> = players.iter().map(|player| (player.total, player.trees_on_board)).collect();
+		for (player, rank) in players.iter_mut().zip(ranks.iter()) {
+			player.place = 1 + ranks.iter().filter(|other| *other > rank).count();
+		}//end placing each player
+
+		let light_income = state.log.iter().filter_map(|entry| match entry {
+			LogEntry::LightGained {round, gained} => Some((*round, gained.clone())),
+			_ => None,
+		}).collect();
+
+		return GameSummary {
+			players,
+			light_income,
+		};//end struct construction
+	}//end new(state)
+
+	/// # winners(&self)
+	///
+	/// Returns the index of each player in first place. There's more than one if they're still tied after the tiebreak.
+	pub fn winners(&self) -> Vec<usize> {
+		return self.players.iter().enumerate().filter(|(_, player)| player.place == 1).map(|(index, _)| index).collect();
+	}//end winners(&self)
+
+	/// # most_light_in_a_round(&self)
+	///
+	/// Returns the most light points any player gained in one round, which is how tall a chart of light income needs to be.
+	pub fn most_light_in_a_round(&self) -> usize {
+		return self.light_income.iter().flat_map(|(_, gained)| gained.iter().copied()).max().unwrap_or(0);
+	}//end most_light_in_a_round(&self)
+}//end impl for GameSummary
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{PieceType, Tree, TreeSize, default_player_color};
+	use crate::game::tests::{players, started_game};
+
+	#[test]
+	fn summary_breaks_ties_with_trees_on_the_board() {
+		let mut state = started_game(players(3));
+		state.players[0].scoring_tokens = vec![(Fertility::OneLeaf, 12), (Fertility::ThreeLeaf, 18)];
+		state.players[0].light_points = 2;
+		state.players[1].scoring_tokens = vec![(Fertility::FourLeaf, 22)];
+		state.players[1].lunar_points = 1;
+		state.players[1].light_points = 8;
+		state.players[2].scoring_tokens = vec![(Fertility::TwoLeaf, 13)];
+		let spot = state.board.board.get_mut(0, 0).unwrap();
+		spot.tree = Some(Tree {color: default_player_color(1), size: TreeSize::Seed});
+		spot.piece_type = PieceType::Tree;
+		// starting the game already logged the first round, so the log is replaced with a made up one
+		state.log.clear();
+		state.log.push(LogEntry::LightGained {round: 1, gained: vec![2, 5, 0]});
+		state.log.push(LogEntry::LightGained {round: 2, gained: vec![3, 1, 4]});
+
+		// player 1 has 30 points and nothing on the board, while player 2 has 22 + 1 + 8/3 = 25 plus one seed
+		let summary = GameSummary::new(&state);
+		assert_eq!(summary.players[0].tokens, [vec![12], vec![], vec![18], vec![]]);
+		assert_eq!((summary.players[0].light_score, summary.players[0].total), (0, 30));
+		assert_eq!((summary.players[1].light_score, summary.players[1].total), (2, 25));
+		assert_eq!(summary.players.iter().map(|player| player.place).collect::<Vec<usize>>(), vec![1, 2, 3]);
+		assert_eq!(summary.winners(), vec![0]);
+		assert_eq!(summary.light_income, vec![(1, vec![2, 5, 0]), (2, vec![3, 1, 4])]);
+		assert_eq!(summary.most_light_in_a_round(), 5);
+
+		// with the same score, the seed on the board wins it for player 2
+		state.players[1].scoring_tokens = vec![(Fertility::FourLeaf, 27)];
+		let summary = GameSummary::new(&state);
+		assert_eq!((summary.players[0].total, summary.players[1].total), (30, 30));
+		assert_eq!(summary.winners(), vec![1]);
+		assert_eq!(summary.players[0].place, 2);
+
+		// without the seed they share first place
+		state.board.board.get_mut(0, 0).unwrap().tree = None;
+		let summary = GameSummary::new(&state);
+		assert_eq!(summary.winners(), vec![0, 1]);
+		assert_eq!(summary.players[2].place, 3);
+	}//end summary_breaks_ties_with_trees_on_the_board()
+}//end mod tests
@@ -0,0 +1,259 @@
+use super::{BoardSpot, GameState, Player, TreeSize};
+
+/// kinds of things that go into a hash, so that keys for different things never line up
+const PIECE_KEY: u64 = 1;
+const TREE_KEY: u64 = 2;
+const ANIMAL_KEY: u64 = 3;
+const EXPENDED_KEY: u64 = 4;
+const SUN_KEY: u64 = 5;
+const MOON_KEY: u64 = 6;
+const TURN_KEY: u64 = 7;
+const LIGHT_KEY: u64 = 8;
+const LUNAR_KEY: u64 = 9;
+const AVAILABLE_KEY: u64 = 10;
+const STORE_KEY: u64 = 11;
+const TOKENS_KEY: u64 = 12;
+const STACK_KEY: u64 = 13;
+const SETUP_KEY: u64 = 14;
+
+/// # key(kind, a, b)
+///
+/// Returns the random key for one feature of a position, such as a tree of a certain size and owner on a certain spot.
+///
+/// Rather than storing a big table of random numbers, each key is made by scrambling the numbers describing the feature with splitmix64.
+/// This gives keys which are just as good as a table, always the same between runs and computers, and which work for any size of board.
+fn key(kind: u64, a: usize, b: usize) -> u64 {
+	let mut x = (kind << 56) ^ ((a as u64) << 28) ^ (b as u64);
+	x = x.wrapping_add(0x9E3779B97F4A7C15);
+	x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+	return x ^ (x >> 31);
+}//end key(kind, a, b)
+
+/// # spot_hash(spot, index, players)
+///
+/// Returns the hash of everything on one spot. Index is the position of the spot on the board, in row-major order.
+///
+/// Fertility isn't included, since it never changes during a game.
+pub(crate) fn spot_hash(spot: &BoardSpot, index: usize, players: &[Player]) -> u64 {
+	let mut hash = key(PIECE_KEY, index, spot.piece_type as usize);
+	if let Some(tree) = spot.tree {
+		// trees which don't belong to anyone still need a key, so they all share one past the last player
+		let owner = players.iter().position(|player| player.color == tree.color).unwrap_or(players.len());
+		hash ^= key(TREE_KEY, index, owner * 4 + tree.size.size());
+	}//end if there's a tree here
+	if let Some(animal) = spot.animal {
+		hash ^= key(ANIMAL_KEY, index, animal as usize);
+	}//end if there's an animal here
+	if spot.is_expended() {
+		hash ^= key(EXPENDED_KEY, index, 0);
+	}//end if spot is expended
+	return hash;
+}//end spot_hash(spot, index, players)
+
+/// # player_hash(player, index)
+///
+/// Returns the hash of everything a player has, including their light and lunar points, available area, player board and scoring tokens.
+pub(crate) fn player_hash(player: &Player, index: usize) -> u64 {
+	let mut hash = key(LIGHT_KEY, index, player.light_points) ^ key(LUNAR_KEY, index, player.lunar_points);
+	for size in [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large] {
+		let filled = player.player_board.column(size).iter().enumerate()
+			.filter(|(_, slot)| slot.filled)
+			.fold(0, |mask, (slot_index, _)| mask | (1 << slot_index));
+		hash ^= key(AVAILABLE_KEY, index * 4 + size.size(), player.available_count(size));
+		hash ^= key(STORE_KEY, index * 4 + size.size(), filled);
+	}//end looping over each tree size
+	let token_total: usize = player.scoring_tokens.iter().map(|token| token.1).sum();
+	hash ^= key(TOKENS_KEY, index * 64 + player.scoring_tokens.len(), token_total);
+	return hash;
+}//end player_hash(player, index)
+
+/// # stacks_hash(stacks)
+///
+/// Returns the hash of the scoring stacks. Since tokens are always taken from the top, the size of each stack says which tokens are left.
+pub(crate) fn stacks_hash(stacks: &[Vec<usize>]) -> u64 {
+	return stacks.iter().enumerate().fold(0, |hash, (index, stack)| hash ^ key(STACK_KEY, index, stack.len()));
+}//end stacks_hash(stacks)
+
+/// # progress_hash(state)
+///
+/// Returns the hash of where the game is up to, which is the sun and moon, whose turn it is, the round, and how many starting trees are left to place.
+pub(crate) fn progress_hash(state: &GameState) -> u64 {
+	let mut hash = key(SUN_KEY, state.board.sun.direction as usize, 0);
+	hash ^= key(MOON_KEY, state.board.moon.ring_index(), state.board.moon.full_moon as usize);
+	hash ^= key(TURN_KEY, state.current_player, state.first_player);
+	hash ^= key(TURN_KEY, 1 << 20 | state.round, state.turns_this_round * 2 + state.game_over as usize);
+	return hash ^ key(SETUP_KEY, state.starting_trees_left, 0);
+}//end progress_hash(state)
+
+/// # full_hash(state)
+///
+/// Works out the hash of a whole game from scratch. GameState keeps its hash up to date as actions are applied, so this is mostly for after the state has been changed some other way.
+///
+/// The log and undo history aren't part of the hash, since they don't change what can happen next.
+pub(crate) fn full_hash(state: &GameState) -> u64 {
+	let mut hash = progress_hash(state);
+	for (index, spot) in state.board.board.iter().enumerate() {
+		hash ^= spot_hash(spot, index, &state.players);
+	}//end looping over each spot in row-major order
+	for (index, player) in state.players.iter().enumerate() {
+		hash ^= player_hash(player, index);
+	}//end looping over each player
+	return hash ^ stacks_hash(&state.scoring_stacks);
+}//end full_hash(state)
+
+/// # TranspositionTable
+///
+/// Remembers something about positions a search has already looked at, such as a score and the best action found, looked up by hash.
+///
+/// The table has a fixed number of slots, and a new entry always replaces whatever was in its slot, so memory use never grows during a search.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TranspositionTable<V> {
+	/// each slot holds the full hash of the position stored there, so that positions sharing a slot can be told apart
+	slots: Vec<Option<(u64, V)>>,
+	/// number of slots currently holding an entry
+	filled: usize,
+}//end struct TranspositionTable
+
+#[allow(dead_code)]
+impl<V> TranspositionTable<V> {
+	/// # new(size_bits)
+	///
+	/// Creates an empty table with 2^size_bits slots.
+	pub fn new(size_bits: u32) -> TranspositionTable<V> {
+		let mut slots = Vec::new();
+		slots.resize_with(1 << size_bits, || None);
+		return TranspositionTable {slots, filled: 0};
+	}//end new(size_bits)
+
+	/// # slot(&self, hash)
+	///
+	/// Helper method giving the slot a hash belongs in.
+	fn slot(&self, hash: u64) -> usize {
+		return (hash as usize) & (self.slots.len() - 1);
+	}//end slot(&self, hash)
+
+	/// # get(&self, hash)
+	///
+	/// Returns what was stored for the position with this hash, if it's still in the table.
+	pub fn get(&self, hash: u64) -> Option<&V> {
+		match &self.slots[self.slot(hash)] {
+			Some((stored, value)) if *stored == hash => Some(value),
+			_ => None,
+		}//end matching what's in the slot
+	}//end get(&self, hash)
+
+	/// # insert(&mut self, hash, value)
+	///
+	/// Stores a value for the position with this hash, replacing anything else in its slot.
+	pub fn insert(&mut self, hash: u64, value: V) {
+		let slot = self.slot(hash);
+		if self.slots[slot].is_none() {
+			self.filled += 1;
+		}//end if slot was empty
+		self.slots[slot] = Some((hash, value));
+	}//end insert(&mut self, hash, value)
+
+	/// # len(&self)
+	///
+	/// Returns the number of positions stored in the table.
+	pub fn len(&self) -> usize {
+		return self.filled;
+	}//end len(&self)
+
+	/// # is_empty(&self)
+	///
+	/// Returns whether nothing is stored in the table.
+	pub fn is_empty(&self) -> bool {
+		return self.filled == 0;
+	}//end is_empty(&self)
+
+	/// # clear(&mut self)
+	///
+	/// Empties the table, keeping the same number of slots.
+	pub fn clear(&mut self) {
+		self.slots.iter_mut().for_each(|slot| *slot = None);
+		self.filled = 0;
+	}//end clear(&mut self)
+}//end impl for TranspositionTable
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{GameAction, PieceType, Tree, default_player_color};
+	use crate::game::tests::{XorShift, players, started_game};
+
+	/// # random_action(rng)
+	///
+	/// Picks a random action, which might not be allowed.
+	fn random_action(rng: &mut XorShift) -> GameAction {
+		let sizes = [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large];
+		match rng.below(12) {
+			0 | 1 => GameAction::Buy(sizes[rng.below(4)]),
+			2..=4 => GameAction::PlantSeed {from: (rng.below(7), rng.below(7)), to: (rng.below(7), rng.below(7))},
+			5 | 6 => GameAction::Grow(rng.below(7), rng.below(7)),
+			7 => GameAction::Collect(rng.below(7), rng.below(7)),
+			8 => GameAction::Undo,
+			9 => GameAction::Redo,
+			_ => GameAction::EndTurn,
+		}//end matching random number
+	}//end random_action(rng)
+
+	#[test]
+	fn hash_stays_up_to_date_through_a_game() {
+		let mut rng = XorShift(0x5851F42D4C957F2D);
+		let mut state = started_game(players(3));
+		// start everyone off with trees on the edge, so there's something to grow and plant from
+		for (index, (row, col)) in [(0, 0), (0, 6), (6, 3)].into_iter().enumerate() {
+			let spot = state.board.board.get_mut(row, col).unwrap();
+			spot.tree = Some(Tree {color: default_player_color(index), size: TreeSize::Small});
+			spot.piece_type = PieceType::Tree;
+		}//end giving each player a tree
+		state.rehash();
+		assert_eq!(state.zobrist(), full_hash(&state));
+
+		let mut allowed = 0;
+		while !state.game_over {
+			let action = random_action(&mut rng);
+			if state.apply(action).is_ok() {
+				allowed += 1;
+			}//end if action was carried out
+			assert_eq!(state.zobrist(), full_hash(&state), "after {}", action);
+		}//end playing random actions until the game ends
+		assert!(allowed > 100);
+	}//end hash_stays_up_to_date_through_a_game()
+
+	#[test]
+	fn undo_brings_back_the_same_hash() {
+		let mut state = started_game(players(2));
+		state.current_mut().light_points = 10;
+		state.rehash();
+		let before = state.zobrist();
+
+		state.apply(GameAction::Buy(TreeSize::Seed)).unwrap();
+		let bought = state.zobrist();
+		assert_ne!(bought, before);
+		state.apply(GameAction::Undo).unwrap();
+		assert_eq!(state.zobrist(), before);
+		state.apply(GameAction::Redo).unwrap();
+		assert_eq!(state.zobrist(), bought);
+	}//end undo_brings_back_the_same_hash()
+
+	#[test]
+	fn transposition_table_keeps_one_entry_per_slot() {
+		let mut table: TranspositionTable<usize> = TranspositionTable::new(4);
+		assert!(table.is_empty());
+		table.insert(3, 30);
+		table.insert(5, 50);
+		assert_eq!(table.get(3), Some(&30));
+		assert_eq!(table.get(19), None);
+		// 19 lands in the same slot as 3, so it pushes 3 out
+		table.insert(19, 190);
+		assert_eq!(table.get(3), None);
+		assert_eq!(table.get(19), Some(&190));
+		assert_eq!(table.len(), 2);
+		table.clear();
+		assert!(table.is_empty());
+		assert_eq!(table.get(5), None);
+	}//end transposition_table_keeps_one_entry_per_slot()
+}//end mod tests
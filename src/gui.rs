@@ -1,13 +1,29 @@
-use fltk::{window::Window, app::{App, Receiver, Sender, self}, prelude::{WidgetExt, GroupExt, WidgetBase, MenuExt, DisplayExt, ImageExt}, enums::{Color, Align, Shortcut, FrameType}, button::Button, group::{Flex, self}, menu::{SysMenuBar, self, Choice}, text::{TextEditor, TextBuffer, self, TextDisplay}, image::{PngImage, JpegImage}, frame::Frame, widget_extends};
+use fltk::{window::Window, app::{App, Receiver, Sender, self}, prelude::{WidgetExt, WindowExt, GroupExt, WidgetBase, MenuExt, DisplayExt, ImageExt, ValuatorExt}, enums::{Color, Align, Shortcut, FrameType, Event, Font, Key}, button::{Button, ReturnButton}, group::{Flex, self, Group}, menu::{SysMenuBar, self, Choice}, text::{TextBuffer, self, TextDisplay}, image::{PngImage, JpegImage}, frame::Frame, valuator::HorNiceSlider, widget_extends};
 use grid::Grid;
-use fltk_theme::WidgetScheme;
-use fltk_theme::SchemeType;
 use fltk_theme::widget_themes;
-use crate::{Board, game::{Fertility, Tree}};
+use crate::{Board, game::{GameState, Plan, Player, Replay, ShadeRules, SpotInfo}};
 
-use self::gui_utils::{get_default_win_width, get_max_grid_button_width, get_default_grid_width, FlexGrid, get_default_grid_padding, get_default_grid_height, get_default_txt_height, get_default_txt_width, get_default_txt_padding, get_default_menu_height, get_default_win_height, get_max_grid_button_height};
+use self::board_widget::BoardWidget;
+use self::point_track::PointTrack;
+use self::player_board_widget::PlayerBoardWidget;
+use self::sun_marker::SunMarker;
+use self::palette::BoardPalette;
+use self::gui_utils::{BoardLayout, ScaledWidget, get_fitting_win_size, get_min_win_width, get_min_win_height, get_default_win_width, get_max_grid_button_width, FlexGrid, get_default_txt_height, get_default_txt_width, get_default_txt_padding, get_default_menu_height, get_default_win_height, get_max_grid_button_height};
 
 mod gui_utils;
+mod board_widget;
+mod point_track;
+mod player_board_widget;
+mod sun_marker;
+mod setup_wizard;
+mod summary_dialog;
+mod preferences;
+mod palette;
+mod keyboard_help;
+pub use self::setup_wizard::{GameSetup, run_setup_wizard};
+pub use self::summary_dialog::{SummaryChoice, run_summary_dialog};
+pub use self::preferences::{Preferences, run_preferences_dialog};
+pub use self::keyboard_help::run_keyboard_help;
 
 #[warn(missing_docs)]
 pub struct GUI {
@@ -25,17 +41,59 @@ pub struct GUI {
 	pub turn_count:TextBuffer,
 	/// info bar player points 
 	pub player_points:TextBuffer,
-	/// info bar light points
-	pub light_pts:TextBuffer,
-	/// info bar lunar points 
-	pub lunar_pts:TextBuffer,
+	/// track showing every player's light points
+	pub light_track:PointTrack,
+	/// track showing every player's lunar points
+	pub lunar_track:PointTrack,
 	/// UI GUI board display
-	pub board_flex_grid:FlexGrid,
-	/// menu for buying trees
-	pub buying_trees_choice:Choice,
+	pub board_widget:BoardWidget,
+	/// player board, showing the price of each slot and buying trees when clicked
+	pub player_board_widget:PlayerBoardWidget,
 	/// menu for availibe trees
 	pub availible_trees:TextBuffer,
 	pub available_trees_display:TextDisplay,
+	/// info bar indicator for whose turn it is
+	pub current_player_display:Frame,
+	/// menu for choosing what clicking on the board does
+	pub action_choice:Choice,
+	/// screen covering the window between turns, so the next player can take over
+	pub handoff_group:Group,
+	/// message shown on the handoff screen
+	pub handoff_label:Frame,
+	/// controls for stepping through a replay, shown at the bottom of the window
+	pub replay_group:Group,
+	/// timeline for jumping to any step of a replay
+	pub replay_slider:HorNiceSlider,
+	/// description of what happened during the step being shown
+	pub replay_info:TextBuffer,
+	/// projected cost and income of the turn being planned, only shown in plan mode
+	pub plan_info:Frame,
+	/// details about the spot the mouse is over
+	pub hover_info:TextBuffer,
+	/// the game being shown, kept so spots can be described as the mouse moves over them
+	pub shown_state:Option<GameState>,
+	/// soil image behind everything else
+	pub background:Frame,
+	/// image shown in the background, at its full resolution so it can be scaled to any window size
+	pub background_image:Option<JpegImage>,
+	/// markers for each direction the sun can shine from, going clockwise from the southwest corner of the board
+	pub sun_frames:Vec<Frame>,
+	/// image shown in each sun marker, at its full resolution
+	pub sun_image:Option<PngImage>,
+	/// the sun where it's shining from now, over the grayed out sun markers
+	pub sun_marker:SunMarker,
+	/// marker for the moon, above the board
+	pub moon_frame:Frame,
+	/// image shown in the moon marker, at its full resolution
+	pub moon_image:Option<PngImage>,
+	/// info bar and side panel widgets, which are scaled evenly with the window
+	pub scaled_widgets:Vec<ScaledWidget>,
+	/// whether the sun, moon, shadows and point markers move smoothly, instead of jumping to where they belong
+	pub animated:bool,
+	/// colors the board is drawn in
+	pub board_palette:BoardPalette,
+	/// whether the board, tracks and results show symbols which don't rely on telling colors apart
+	pub symbols:bool,
 }//end struct GUI
 
 impl Default for GUI {
@@ -47,31 +105,64 @@ impl Default for GUI {
 		GUI {
 			app: App::default(),
 			main_window: Window::default(),
-			msg_sender: s,
+			msg_sender: s.clone(),
 			msg_receiver: r,
 			menu:SysMenuBar::default(),
 			turn_count:TextBuffer::default(),
 			player_points:TextBuffer::default(),
-			light_pts:TextBuffer::default(),
-			lunar_pts:TextBuffer::default(),
-			board_flex_grid:FlexGrid::default(),
-			buying_trees_choice:Choice::default(),
+			light_track:PointTrack::new("Light", 20),
+			lunar_track:PointTrack::new("Lunar", 20),
+			board_widget:BoardWidget::new(s.clone()),
+			player_board_widget:PlayerBoardWidget::new(s.clone()),
 			availible_trees:TextBuffer::default(),
 			available_trees_display:TextDisplay::default(),
+			current_player_display:Frame::default(),
+			action_choice:Choice::default(),
+			handoff_group:{
+				// groups start taking children as soon as they're created, so we need to end it right away
+				let group = Group::default();
+				group.end();
+				group
+			},
+			handoff_label:Frame::default(),
+			replay_group:{
+				let group = Group::default();
+				group.end();
+				group
+			},
+			replay_slider:HorNiceSlider::default(),
+			replay_info:TextBuffer::default(),
+			plan_info:Frame::default(),
+			hover_info:TextBuffer::default(),
+			shown_state:None,
+			background:Frame::default(),
+			background_image:None,
+			sun_frames:Vec::new(),
+			sun_image:None,
+			sun_marker:SunMarker::new(),
+			moon_frame:Frame::default(),
+			moon_image:None,
+			scaled_widgets:Vec::new(),
+			animated:true,
+			board_palette:BoardPalette::Classic,
+			symbols:false,
 		}//end struct construction
 	}//end default()
 }//end impl Default for GUI
 
 #[warn(missing_docs)]
 impl GUI {
-	/// # initialize(self)
-	pub fn initialize(&mut self) {
+	/// # initialize(self, prefs)
+	/// 
+	/// Sets up the window and info bar, in the widget style and with the animation and board color settings from the preferences.
+	pub fn initialize(&mut self, prefs:&Preferences) {
 		// background 
+		self.background = Frame::default()
+			.with_size(get_default_win_width(), get_default_win_height());
 		match JpegImage::load("imgs/photosynthesis soil.jpg") {
 			Ok(image) => {
-				let mut frame_background = Frame::default()
-				.with_size(get_default_win_width(), get_default_win_height());
-			frame_background.set_image(Some(image));
+				self.background.set_image(Some(image.clone()));
+				self.background_image = Some(image);
 			},
 			Err(e) => {
 				println!("Couldn't load background image for some reason. Details:\n{}", e);
@@ -81,13 +172,24 @@ impl GUI {
 		self.initialize_sun();
 		self.initialize_moon();
 
-		// Widget Scheme 
-		let widget_scheme = WidgetScheme::new(SchemeType::Aqua);
-		widget_scheme.apply();
+		// Widget Scheme and color palette
+		prefs.apply_appearance();
+		self.set_animated(prefs.animated);
+		self.set_board_palette(prefs.board_palette, prefs.symbols);
 
 		// window settings
 		self.main_window.set_size(get_default_win_width(),get_default_win_height());
 		self.main_window.make_resizable(true);
+		self.main_window.size_range(get_min_win_width(), get_min_win_height(), 0, 0);
+		// everything gets placed again once the window is done resizing, so moving the window doesn't need to do anything
+		let resize_sender = self.msg_sender.clone();
+		let mut last_size = (get_default_win_width(), get_default_win_height());
+		self.main_window.resize_callback(move |_, _, _, w, h| {
+			if (w, h) != last_size {
+				last_size = (w, h);
+				resize_sender.send("Window:Resize".to_string());
+			}//end if window changed size
+		});
 		self.main_window.set_label("uwu Photosynthesis");
 		self.main_window.set_label_size(32);
 		self.main_window.set_label_color(Color::Green);
@@ -103,8 +205,8 @@ impl GUI {
 			.with_size(1 * get_default_txt_width() / 3, get_default_txt_height())
 			.with_pos(get_default_txt_padding() * 2, self.menu.height() + self.menu.y() + 25);
 
-		// turn text editor
-		let mut txt1 = TextEditor::default()
+		// turn number, which can't be typed into
+		let mut txt1 = TextDisplay::default()
 			.with_size(get_default_txt_width() / 4,get_default_txt_height())
 			.right_of(&turn_text_display, 0);
 		txt1.set_label_color(Color::from_rgb(106, 168, 79));
@@ -114,50 +216,51 @@ impl GUI {
 			.with_size( 5 * get_default_txt_width() / 4, get_default_txt_height())
 			.right_of(&txt1, get_default_txt_padding());
 
-		// player points text editor
-		let mut txt2 = TextEditor::default()
+		// player points, which can't be typed into
+		let mut txt2 = TextDisplay::default()
 			.with_size(get_default_txt_width() / 2,get_default_txt_height())
 			.right_of(&player_points_display, 0);
 		txt2.set_label_color(Color::from_rgb(106, 168, 79));
-
-		// light points text display 
-		let mut light_points_text_display = TextDisplay::default()
-			.with_size(3 * get_default_txt_width() / 4, get_default_txt_height())
-			.right_of(&txt2, get_default_txt_padding());
-
-		// Light points text editor
-		let mut txt3 = TextEditor::default()
-			.with_size(get_default_txt_width() / 4,get_default_txt_height())
-			.right_of(&light_points_text_display, 0);
-		txt3.set_label_color(Color::from_rgb(106, 168, 79));	
-
-		// lunar points text display 
-		let mut lunar_points_text_display = TextDisplay::default()
-			.with_size(3 * get_default_txt_width() / 4, get_default_txt_height())
-			.right_of(&txt3, get_default_txt_padding());
-
-		// lunar points text editor
-		let mut txt4 = TextEditor::default()
-			.with_size(get_default_txt_width() / 4,get_default_txt_height())
-			.right_of(&lunar_points_text_display, 0);
-		txt4.set_label_color(Color::from_rgb(106, 168, 79));	
 		
 		let mut txt5 = Button::default()
 			.with_size(get_default_txt_width(),get_default_txt_height())
 			.with_label("Toggle Plan/Place")
-			.right_of(&txt4, get_default_txt_padding());
+			.right_of(&txt2, get_default_txt_padding());
 		txt5.set_label_color(Color::White);	
+		txt5.emit(self.msg_sender.clone(), "TogglePlan".to_string());
 
 		let mut txt6 = Button::default()
 			.with_size(3 * get_default_txt_width() / 4,get_default_txt_height())
 			.with_label("End Turn")
 			.right_of(&txt5, get_default_txt_padding());
 		txt6.set_label_color(Color::White);	
+		txt6.emit(self.msg_sender.clone(), "EndTurn".to_string());
+
+		// current player indicator
+		self.current_player_display = Frame::default()
+			.with_size(2 * get_default_txt_width(), get_default_txt_height())
+			.below_of(&turn_text_display, get_default_txt_padding());
+		self.current_player_display.set_frame(FrameType::RoundedBox);
+		self.current_player_display.set_label_color(Color::White);
+		self.current_player_display.set_label_font(Font::HelveticaBold);
+
+		// plan mode indicator
+		self.plan_info = Frame::default()
+			.with_size(4 * get_default_txt_width(), get_default_txt_height())
+			.right_of(&self.current_player_display, get_default_txt_padding());
+		self.plan_info.set_frame(FrameType::RoundedBox);
+		self.plan_info.set_color(Color::from_rgb(68,140,184));
+		self.plan_info.set_label_color(Color::White);
+		self.plan_info.hide();
+
+		// light and lunar tracks, with a marker for every player
+		self.light_track.resize(self.current_player_display.x(), self.current_player_display.y() + get_default_txt_height() + 5, self.current_player_display.width(), get_default_txt_height() - 1);
+		self.lunar_track.resize(self.light_track.x(), self.light_track.y() + get_default_txt_height() + 3, self.light_track.width(), get_default_txt_height() - 1);
+		self.main_window.add(&*self.light_track);
+		self.main_window.add(&*self.lunar_track);
 
 		txt1.set_buffer(self.turn_count.clone());
 		txt2.set_buffer(self.player_points.clone());
-		txt3.set_buffer(self.light_pts.clone());
-		txt4.set_buffer(self.lunar_pts.clone());
 		txt5.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
 		txt5.set_color(Color::from_rgb(68,140,184));
 		txt6.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
@@ -171,95 +274,57 @@ impl GUI {
 		player_points_display_buf.set_text("Player / Computer Points");
 		player_points_display.set_buffer(player_points_display_buf);
 
-		let mut light_points_display_buf = TextBuffer::default();
-		light_points_display_buf.set_text("Light Points");
-		light_points_text_display.set_buffer(light_points_display_buf);
-
-		let mut lunar_points_display_buf = TextBuffer::default();
-		lunar_points_display_buf.set_text("Lunar Points");
-		lunar_points_text_display.set_buffer(lunar_points_display_buf);
-
 		txt1.wrap_mode(text::WrapMode::AtBounds, 0);
 		txt2.wrap_mode(text::WrapMode::AtBounds, 0);
-		txt3.wrap_mode(text::WrapMode::AtBounds, 0);
-		txt4.wrap_mode(text::WrapMode::AtBounds, 0);
 
 		self.main_window.add(&txt1);
 		self.main_window.add(&txt2);
-		self.main_window.add(&txt3);
-		self.main_window.add(&txt4);
 		self.main_window.add(&txt5);
 		self.main_window.add(&txt6);
+		self.main_window.add(&self.current_player_display);
+		self.main_window.add(&self.plan_info);
 
 		self.main_window.add(&turn_text_display);
 		self.main_window.add(&player_points_display);
-		self.main_window.add(&light_points_text_display);
-		self.main_window.add(&lunar_points_text_display);
+
+		// the board keeps keyboard focus, so the arrow keys and Enter always reach it
+		for text_display in [&mut turn_text_display, &mut txt1, &mut player_points_display, &mut txt2] {
+			text_display.clear_visible_focus();
+		}//end keeping focus off each label and value
+		for button in [&mut txt5, &mut txt6] {
+			button.clear_visible_focus();
+		}//end keeping focus off each button
+
+		// remember where the info bar goes at the default size, so it can follow the window
+		for text_display in [&turn_text_display, &txt1, &player_points_display, &txt2] {
+			self.scaled_widgets.push(ScaledWidget::new(text_display));
+		}//end remembering each label and value
+		for widget in [&txt5, &txt6] {
+			self.scaled_widgets.push(ScaledWidget::new(widget));
+		}//end remembering each button
+		self.scaled_widgets.push(ScaledWidget::new(&self.current_player_display));
+		self.scaled_widgets.push(ScaledWidget::new(&self.plan_info));
+		self.scaled_widgets.push(ScaledWidget::new(&*self.light_track));
+		self.scaled_widgets.push(ScaledWidget::new(&*self.lunar_track));
 	}//end initialize(self)
 	
+	/// # initialize_sun(&mut self)
+	/// 
+	/// Adds a sun marker for each direction the sun can shine from, grayed out, and then the bright sun which sits over whichever one the sun is shining from.
+	/// They're placed around the board by apply_layout().
 	pub fn initialize_sun(&mut self) {
 		match PngImage::load("imgs/photosynthesis sun.png") {
 			Ok(image) => {
-
-				let grid_x = get_default_win_width() - get_default_grid_width() - get_default_grid_padding();
-				let grid_y = 175;
-				
-				// southwest sun
-				let mut frame_sw = Frame::default()
-					.with_size(100,100)
-					.with_pos(grid_x - 50, grid_y + get_default_grid_height() - 50);
-				frame_sw.set_image(Some(image.clone()));
-				self.main_window.add(&frame_sw);
-			
-				// west sun
-				let mut frame_w = Frame::default()
-					.with_size(100,100)
-					.with_pos(grid_x - 50, grid_y + (get_default_grid_height() / 2) - 50);
-				frame_w.set_image(Some(image.clone()));
-				self.main_window.add(&frame_w);
-
-				// northwest sun
-				let mut frame_nw = Frame::default()
-				.with_size(100,100)
-				.with_pos(grid_x - 50, grid_y - 50);
-			//image.scale(400, 400, true, true);
-				frame_nw.set_image(Some(image.clone()));
-				self.main_window.add(&frame_nw);
-
-				// north sun
-				let mut frame_n = Frame::default()
-				.with_size(100,100)
-					.with_pos(grid_x + (get_default_grid_width() / 2) - 50, grid_y - 50);
-				frame_n.set_image(Some(image.clone()));
-				self.main_window.add(&frame_n);
-
-				// northeast sun
-				let mut frame_ne = Frame::default()
-					.with_size(100,100)
-					.with_pos(grid_x + get_default_grid_width() - 50, grid_y - 50);
-				frame_ne.set_image(Some(image.clone()));
-				self.main_window.add(&frame_ne);
-
-				// east sun
-				let mut frame_e = Frame::default()
-					.with_size(100,100)
-					.with_pos(grid_x + get_default_grid_width() - 50, grid_y + (get_default_grid_height() / 2) - 50);
-				frame_e.set_image(Some(image.clone()));
-				self.main_window.add(&frame_e);
-
-				// southeast sun
-				let mut frame_se = Frame::default()
-					.with_size(100,100)
-					.with_pos(grid_x + get_default_grid_width() - 50, grid_y + get_default_grid_height() - 50);
-				frame_se.set_image(Some(image.clone()));
-				self.main_window.add(&frame_se);
-
-				// south sun
-				let mut frame_s = Frame::default()
-					.with_size(100,100)
-					.with_pos(grid_x + (get_default_grid_width() / 2) - 50, grid_y + get_default_grid_height() - 50);
-				frame_s.set_image(Some(image.clone()));
-				self.main_window.add(&frame_s);
+				for _ in 0..8 {
+					let mut frame = Frame::default().with_size(100,100);
+					frame.set_image(Some(image.clone()));
+					// inactive frames draw their image grayed out
+					frame.deactivate();
+					self.main_window.add(&frame);
+					self.sun_frames.push(frame);
+				}//end adding each sun marker
+				self.main_window.add(&*self.sun_marker);
+				self.sun_image = Some(image);
 			},
 			Err(e) => {
 				println!("Couldn't load sun image for some reason. Details:\n{}", e);
@@ -267,15 +332,15 @@ impl GUI {
 		}//end matching our image loading result
 	}//end initialize_sun(&mut self)
 	
+	/// # initialize_moon(&mut self)
+	/// 
+	/// Adds the moon marker. It's placed above the board by apply_layout().
 	pub fn initialize_moon(&mut self) {
 		match PngImage::load("imgs/photosynthesis moon full.png") {
-			Ok(mut image) => {
-				let mut frame = Frame::default()
-					.with_size(100,100)
-					.with_pos(get_default_win_width() - get_default_grid_width() + 300, 125);
-				image.scale(200, 200, true, true);
-				frame.set_image(Some(image));
-				self.main_window.add(&frame);
+			Ok(image) => {
+				self.moon_frame = Frame::default().with_size(100,100);
+				self.main_window.add(&self.moon_frame);
+				self.moon_image = Some(image);
 			},
 			Err(e) => {
 				println!("Couldn't load moon image for some reason. Details:\n{}", e);
@@ -283,10 +348,78 @@ impl GUI {
 		}//end matching image loading result
 	}//end initalize_moon(&mut self)
 
-	/// # show(self)
-	pub fn show(&mut self){
+	/// # apply_layout(&mut self)
+	/// 
+	/// Places everything to fit the current size of the window.  
+	/// 
+	/// The board gets as much room as it can while keeping its cells square, the suns and moon are kept around it, and the info bar and side panel are scaled evenly.
+	/// The handoff screen and replay controls already stretch to fit the window, so they're left alone.
+	pub fn apply_layout(&mut self) {
+		let (win_w, win_h) = (self.main_window.width(), self.main_window.height());
+		let layout = BoardLayout::new(win_w, win_h, self.board_widget.rows(), self.board_widget.cols());
+
+		self.background.resize(0, 0, win_w, win_h);
+		if let Some(image) = &self.background_image {
+			let mut image = image.clone();
+			image.scale(win_w, win_h, false, true);
+			self.background.set_image(Some(image));
+		}//end if we have a background image
+		self.menu.resize(0, 0, win_w, get_default_menu_height());
+		for widget in self.scaled_widgets.iter_mut() {
+			widget.apply(layout.scale);
+		}//end scaling each widget
+
+		let (board_x, board_y, board_w, board_h) = layout.board;
+		self.board_widget.resize(board_x, board_y, board_w, board_h);
+
+		if let Some(image) = &self.sun_image {
+			let mut image = image.clone();
+			image.scale(layout.sun_size, layout.sun_size, true, true);
+			for (frame, (x, y)) in self.sun_frames.iter_mut().zip(layout.suns) {
+				frame.resize(x, y, layout.sun_size, layout.sun_size);
+				frame.set_image(Some(image.clone()));
+			}//end placing each sun
+			self.sun_marker.set_layout(layout.suns, layout.sun_size, &image);
+		}//end if we have suns to place
+		if let Some(image) = &self.moon_image {
+			// the moon is drawn bigger than its frame, spilling out around it
+			let (x, y, w, h) = layout.moon;
+			let mut image = image.clone();
+			image.scale(2 * w, 2 * h, true, true);
+			self.moon_frame.resize(x, y, w, h);
+			self.moon_frame.set_image(Some(image));
+		}//end if we have a moon to place
+		self.main_window.redraw();
+	}//end apply_layout(&mut self)
+
+	/// # show(self, geometry)
+	/// 
+	/// Shows the main window at the x, y, width and height it was last closed at, if given and it still fits on a screen.
+	/// Otherwise, the window is as big as fits on the screen, up to the default size.
+	pub fn show(&mut self, geometry:Option<(i32,i32,i32,i32)>){
+		let (win_w, win_h) = get_fitting_win_size();
+		self.main_window.set_size(win_w, win_h);
+		if let Some((x, y, w, h)) = geometry {
+			let on_screen = (0..app::screen_count()).map(app::screen_work_area).any(|(sx, sy, sw, sh)| x >= sx && y >= sy && x < sx + sw && y < sy + sh);
+			let (w, h) = (w.max(get_min_win_width()), h.max(get_min_win_height()));
+			if on_screen {
+				self.main_window.resize(x, y, w, h);
+			}//end if window would be somewhere we can see it
+			else {
+				self.main_window.set_size(w, h);
+			}//end else only the size is kept
+		}//end if we know where the window was last time
 		self.main_window.show();
-	}//end show(self)
+		self.apply_layout();
+		let _ = self.board_widget.take_focus();
+	}//end show(self, geometry)
+
+	/// # geometry(&self)
+	/// 
+	/// Returns the x, y, width and height of the main window, so it can be put back there next time.
+	pub fn geometry(&self) -> (i32,i32,i32,i32) {
+		return (self.main_window.x(), self.main_window.y(), self.main_window.width(), self.main_window.height());
+	}//end geometry(&self)
 
 	#[allow(dead_code)]
 	/// # button_grid_test(self)
@@ -343,6 +476,38 @@ impl GUI {
 			"MenuChoice::Save".to_string(),
 		);
 
+		self.menu.add_emit(
+			"Edit/Undo\t",
+			Shortcut::Ctrl | 'z',
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::Undo".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Edit/Redo\t",
+			Shortcut::Ctrl | 'y',
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::Redo".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Setup/Place Great Elder Tree\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::GreatElderTree".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Setup/Place Moonstones\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::Moonstones".to_string(),
+		);
+
 		self.menu.add_emit(
 			"Change Season/Winter\t",
 			Shortcut::None,
@@ -375,6 +540,93 @@ impl GUI {
 			"MenuChoice::Save".to_string(),
 		);
 
+		self.menu.add_emit(
+			"Network/Host Game\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::Host".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Network/Join Game\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::Join".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Replay/Save Replay\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::SaveReplay".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Replay/Open Replay\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::OpenReplay".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Replay/Watch This Game\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::WatchReplay".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Replay/Close Replay\t",
+			Shortcut::None,
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"Replay:Close".to_string(),
+		);
+
+		self.menu.add_emit(
+			"Edit/Preferences...\t",
+			Shortcut::Ctrl | ',',
+			menu::MenuFlag::Normal,
+			self.msg_sender.clone(),
+			"MenuChoice::Preferences".to_string(),
+		);
+
+		self.menu.add_emit(
+			"View/Animations\t",
+			Shortcut::None,
+			if self.animated {menu::MenuFlag::Toggle | menu::MenuFlag::Value} else {menu::MenuFlag::Toggle},
+			self.msg_sender.clone(),
+			"MenuChoice::ToggleAnimations".to_string(),
+		);
+
+		// plain keys for playing without the mouse, which are listed in the keyboard help
+		for (label, key, msg) in [
+			("Play/Grow Trees\t", Key::from_char('g'), "Action:Grow"),
+			("Play/Plant Seeds\t", Key::from_char('s'), "Action:Seed"),
+			("Play/Collect Trees\t", Key::from_char('c'), "Action:Collect"),
+			("Play/Buy/Seed\t", Key::from_char('1'), "Buy:Seed"),
+			("Play/Buy/Small Tree\t", Key::from_char('2'), "Buy:Small"),
+			("Play/Buy/Medium Tree\t", Key::from_char('3'), "Buy:Medium"),
+			("Play/Buy/Large Tree\t", Key::from_char('4'), "Buy:Large"),
+			("Play/Cancel\t", Key::Escape, "Board:Cancel"),
+			("Play/Toggle Plan\\/Place\t", Key::from_char('p'), "TogglePlan"),
+			("Play/End Turn\t", Key::from_char('e'), "EndTurn"),
+			("Help/Keyboard Shortcuts...\t", Key::F1, "MenuChoice::KeyboardHelp"),
+		] {
+			self.menu.add_emit(
+				label,
+				Shortcut::from_key(key),
+				menu::MenuFlag::Normal,
+				self.msg_sender.clone(),
+				msg.to_string(),
+			);
+		}//end adding each keyboard action
+
 		self.main_window.add(&self.menu);
 	}
 
@@ -386,88 +638,41 @@ impl GUI {
 	/// 
 	/// ## parameters
 	/// board_state : Reference to the object holding the current state of the board.
+	/// shade : rules for how far shadows reach, so the shadows on the board can be shown
 	/// 
 	/// ## result
 	/// Board is displayed in the main window, showing the current state of the board.
 	/// 
-	pub fn initialize_board(&mut self, board_state:&Board) {
-		/*
-		Maybe look to button_grid_test for example of creating grid of buttons
-		and displaying it in a FlexGrid.
-		*/
-
-		let mut board: Grid<Button> = Grid::new(board_state.board.rows(),board_state.board.cols());
-		self.board_flex_grid = FlexGrid::default();
-		self.board_flex_grid.clear_inner_flexes();
-		self.board_flex_grid.clear();
-		
-		// intiailize board 
-		for row in 0..board.rows(){
-			for col in 0..board.cols(){
-				//settings for button
-				let mut new_button = Button::default();
-				new_button.set_label(&format!("row:{}\ncol:{}", row, col));
-				new_button.set_size(get_default_grid_width() / board.cols() as i32, get_default_grid_height() / board.rows() as i32);
-				new_button.set_frame(FrameType::FlatBox);
-
-				if new_button.width() < get_max_grid_button_width() || new_button.height() < get_max_grid_button_height() {
-					new_button.set_label("");
-				}// end if button is too small
-				
-				// fertility button colors
-				new_button.set_color(Color::from_rgb(50, 168, 82));
-				let this_board_spot = board_state.board.get(row, col).unwrap();
-				match this_board_spot.fertility {
-					Fertility::OneLeaf => new_button.set_color(Color::from_rgb(147, 196, 125)),
-					Fertility::TwoLeaf => new_button.set_color(Color::from_rgb(106, 168, 79)),
-					Fertility::ThreeLeaf => new_button.set_color(Color::from_rgb(56, 118, 29)),
-					Fertility::FourLeaf => new_button.set_color(Color::from_rgb(39, 78, 19)),
-				}
-
-				// add buttton click event
-				new_button.emit(self.msg_sender.clone(), format!("uwu board:{},{}", row, col));
-
-				// add button into grid space
-				let grid_spot = board.get_mut(row, col).unwrap();
-				*grid_spot = new_button;
-			}// end looping for columns
-		}// end looping for rows 
-
-		// initialize flex grid
-		self.board_flex_grid.initialize_flex(board_state.board.rows(),board_state.board.cols());
-		self.board_flex_grid.fill_flex(&board);
-
-		// make flex show up 
-		self.board_flex_grid.set_pos(get_default_win_width() - get_default_grid_width() - 100, 175);
-		self.main_window.add(&*self.board_flex_grid);
-		self.board_flex_grid.recalc();
-	}//end initialize_board(&mut self, board)
+	pub fn initialize_board(&mut self, board_state:&Board, shade:&ShadeRules) {
+		let (board_x, board_y, board_w, board_h) = BoardLayout::new(self.main_window.width(), self.main_window.height(), board_state.board.rows(), board_state.board.cols()).board;
+		self.board_widget.resize(board_x, board_y, board_w, board_h);
+		// adding the board again moves it in front of the background
+		self.main_window.add(&*self.board_widget);
+		self.update_board(board_state, shade);
+	}//end initialize_board(&mut self, board, shade)
+
+	/// # update_board(&mut self, board_state, shade)
+	/// 
+	/// Updates the board display to show the trees and pieces currently on the board, along with the shadows and the moon.  
+	/// 
+	/// Each tree is drawn in the color of the player who owns it.
+	pub fn update_board(&mut self, board_state:&Board, shade:&ShadeRules) {
+		self.board_widget.set_board(board_state, board_state.sun_shaded_with(shade));
+		self.sun_marker.set_direction(board_state.sun.direction);
+	}//end update_board(&mut self, board_state, shade)
 
-	/// # initialize_tree_lists(self, to_buy, available)
+	/// # initialize_tree_lists(self, player)
 	/// 
-	/// Takes list of trees which are to buy or available and initializes menu elements and lists.
-	pub fn initialize_tree_lists(&mut self, to_buy: Vec<Tree>, available: Vec<Tree>) {
-		// Buying trees choice menu
-		self.buying_trees_choice = Choice::default()
-			.with_size(135,30)
-			.with_pos(100, 175);
-		self.update_tree_lists(to_buy, available);
-		self.buying_trees_choice.set_color(Color::from_rgb(56, 118, 29));
-		self.buying_trees_choice.set_text_color(Color::White);
-		self.main_window.add(&self.buying_trees_choice);
-
-		// buying trees label text display
-		let mut buying_trees_text_display = TextDisplay::default()
-			.with_size(80, 30)
-			.left_of(&self.buying_trees_choice, 0);
-		let mut buying_trees_display_buf = TextBuffer::default();
-		buying_trees_display_buf.set_text("Items to buy");
-		buying_trees_text_display.set_buffer(buying_trees_display_buf);
+	/// Adds the player board and available area to the side panel, showing the given player's trees, along with the menu for what clicking the board does.
+	pub fn initialize_tree_lists(&mut self, player: &Player) {
+		// player board to buy trees from
+		self.player_board_widget.resize(get_default_txt_padding() * 2, 175, 215, 125);
+		self.main_window.add(&*self.player_board_widget);
 
 		// available trees text display
 		self.available_trees_display = TextDisplay::default()
 			.with_size(135,150)
-			.below_of(&self.buying_trees_choice,100)
+			.with_pos(100, 305)
 			.with_label("Available Area")
 			.with_align(Align::LeftTop);
 		
@@ -481,63 +686,293 @@ impl GUI {
 
 
 		self.available_trees_display.set_buffer(self.availible_trees.clone());
-		self.available_trees_display.set_color(Color::from_rgb(147, 196, 125))
-	}//end initialize_tree_lists(self, to_buy, available)
+		self.available_trees_display.set_color(Color::from_rgb(147, 196, 125));
+		self.update_tree_lists(player);
 
-	pub fn update_tree_lists(&mut self, to_buy: Vec<Tree>, available: Vec<Tree>) {
-		// clear any previous options
-		self.buying_trees_choice.clear();
+		// choice menu for what happens when clicking on the board
+		self.action_choice = Choice::default()
+			.with_size(135,30)
+			.below_of(&self.available_trees_display, 40);
+		self.action_choice.set_color(Color::from_rgb(56, 118, 29));
+		self.action_choice.set_text_color(Color::White);
+		for (label, msg) in [("Grow Tree", "Action:Grow"), ("Plant Seed", "Action:Seed"), ("Collect Tree", "Action:Collect")] {
+			self.action_choice.add_emit(
+				label,
+				Shortcut::None,
+				menu::MenuFlag::Normal,
+				self.msg_sender.clone(),
+				msg.to_string()
+			);
+		}//end adding each action
+		self.action_choice.set_value(0);
+		self.main_window.add(&self.action_choice);
+
+		// action choice label text display
+		let mut action_label_text_display = TextDisplay::default()
+			.with_size(80, 30)
+			.left_of(&self.action_choice, 0);
+		let mut action_label_buf = TextBuffer::default();
+		action_label_buf.set_text("Board Click");
+		action_label_text_display.set_buffer(action_label_buf);
+
+		// details about whichever spot the mouse is over
+		let mut hover_info_display = TextDisplay::default()
+			.with_size(215, 125)
+			.with_pos(20, 540);
+		hover_info_display.set_buffer(self.hover_info.clone());
+		hover_info_display.set_color(Color::from_rgb(147, 196, 125));
+		hover_info_display.set_text_size(12);
+		self.hover_info.set_text("Point at a spot on the board to see more about it.");
+		self.main_window.add(&hover_info_display);
+		self.scaled_widgets.push(ScaledWidget::new(&hover_info_display));
+
+		// the board keeps keyboard focus, so the arrow keys and Enter always reach it
+		self.action_choice.clear_visible_focus();
+		hover_info_display.clear_visible_focus();
+		for text_display in [&mut self.available_trees_display, &mut available_trees_label_text_display, &mut action_label_text_display] {
+			text_display.clear_visible_focus();
+		}//end keeping focus off each text display
+
+		// remember where the side panel goes at the default size, so it can follow the window
+		self.scaled_widgets.push(ScaledWidget::new(&*self.player_board_widget));
+		self.scaled_widgets.push(ScaledWidget::new(&self.action_choice));
+		for text_display in [&self.available_trees_display, &available_trees_label_text_display, &action_label_text_display] {
+			self.scaled_widgets.push(ScaledWidget::new(text_display));
+		}//end remembering each text display
+	}//end initialize_tree_lists(self, player)
+
+	/// # update_tree_lists(self, player)
+	/// 
+	/// Shows the given player's board and the trees in their available area.
+	pub fn update_tree_lists(&mut self, player: &Player) {
+		self.player_board_widget.set_board(&player.player_board, player.color);
 
-		let mut num_seeds = 0;
-		let mut num_small_trees = 0;
-		let mut num_med_trees = 0;
-		let mut num_large_trees = 0;
-		
-		for tree in to_buy {
-			match tree.size {
-				crate::game::TreeSize::Seed => num_seeds += 1,
-				crate::game::TreeSize::Small => num_small_trees += 1,
-				crate::game::TreeSize::Medium => num_med_trees += 1,
-				crate::game::TreeSize::Large => num_large_trees += 1
-			}
-		}
+		let mut available_text = "".to_string();
+		for avai in &player.available {
+			available_text += format!("{}\n", avai.size).as_str();
+		}//end looping over available trees
+		self.availible_trees.set_text(&available_text);
+	}//end update_tree_lists(self, player)
 
-		self.buying_trees_choice.add_emit(
-			format!("Seed ({})", num_seeds).as_str(),
-			Shortcut::None,
-			menu::MenuFlag::Normal,
-			self.msg_sender.clone(),
-			"Buy:Seed".to_string()
-		);
+	/// # update_from_state(&mut self, state, viewing_player)
+	/// 
+	/// Updates every part of the display to match the given game state.  
+	/// 
+	/// The points and trees shown are for the viewing player, which is the player whose turn it is when everyone shares one computer,
+	/// or the player sitting at this computer when playing over the network.
+	pub fn update_from_state(&mut self, state:&GameState, viewing_player:usize) {
+		let current_player = state.current();
+		self.current_player_display.set_label(&format!("Current Player: {}", current_player.name));
+		self.current_player_display.set_color(Color::from_rgb(current_player.color.0, current_player.color.1, current_player.color.2));
+		self.current_player_display.redraw();
+
+		let player = state.players.get(viewing_player).unwrap_or(current_player);
+		self.turn_count.set_text(&format!("{}", state.round));
+		self.player_points.set_text(&format!("{}", player.score()));
+		let light: Vec<((u8,u8,u8), usize)> = state.players.iter().map(|player| (player.color, player.light_points)).collect();
+		let lunar: Vec<((u8,u8,u8), usize)> = state.players.iter().map(|player| (player.color, player.lunar_points)).collect();
+		self.light_track.set_max(state.rules.max_light);
+		self.light_track.set_markers(&light, viewing_player);
+		self.light_track.set_pending(None);
+		// the lunar track has no limit, so it grows once someone passes the end of it
+		self.lunar_track.set_max(lunar.iter().map(|(_, points)| *points).max().unwrap_or(0).max(20));
+		self.lunar_track.set_markers(&lunar, viewing_player);
+
+		self.update_tree_lists(player);
+		self.board_widget.set_owners(state.players.iter().map(|player| player.color).collect());
+		self.update_board(&state.board, &state.rules.shade);
+		self.shown_state = Some(state.clone());
+		self.update_hover_info();
+	}//end update_from_state(&mut self, state, viewing_player)
+
+	/// # update_hover_info(&mut self)
+	/// 
+	/// Describes the spot the mouse is over, as it is in the game being shown. The description is left alone when the mouse leaves the board.
+	pub fn update_hover_info(&mut self) {
+		if let (Some((row, col)), Some(state)) = (self.board_widget.hovered(), &self.shown_state) {
+			if let Some(info) = SpotInfo::new(state, row, col) {
+				self.hover_info.set_text(&info.to_string());
+			}//end if there's a spot there
+		}//end if mouse is over the board
+	}//end update_hover_info(&mut self)
+
+	/// # initialize_handoff(&mut self)
+	/// 
+	/// Creates the screen shown in between turns, which covers up the window until the next player is ready.  
+	/// 
+	/// This should be called after everything else has been added to the main window, so that it shows up on top.
+	pub fn initialize_handoff(&mut self) {
+		self.handoff_group = Group::new(0, 0, get_default_win_width(), get_default_win_height(), None);
+
+		// background, which also eats clicks so they don't reach the board underneath
+		let mut background = Frame::default_fill();
+		background.set_frame(FrameType::FlatBox);
+		background.set_color(Color::from_rgb(101, 68, 24));
+		background.handle(|_, event| matches!(event, Event::Push | Event::Released | Event::Drag));
+
+		self.handoff_label = Frame::default()
+			.with_size(get_default_win_width(), 100)
+			.center_of_parent();
+		self.handoff_label.set_label_size(32);
+		self.handoff_label.set_label_color(Color::White);
+		self.handoff_label.set_label_font(Font::HelveticaBold);
+
+		// Enter starts the turn too, since the button gets focus whenever the window is covered
+		let mut continue_button = ReturnButton::default()
+			.with_size(3 * get_default_txt_width() / 2, 2 * get_default_txt_height())
+			.below_of(&self.handoff_label, get_default_txt_padding());
+		continue_button.set_label("Start Turn");
+		continue_button.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+		continue_button.set_color(Color::from_rgb(68,140,184));
+		continue_button.set_label_color(Color::White);
+		continue_button.emit(self.msg_sender.clone(), "Handoff:Continue".to_string());
+
+		self.handoff_group.end();
+		self.handoff_group.hide();
+		self.main_window.add(&self.handoff_group);
+	}//end initialize_handoff(&mut self)
+
+	/// # show_handoff(&mut self, next_player)
+	/// 
+	/// Covers up the window, asking for the next player to take over.
+	pub fn show_handoff(&mut self, next_player:&Player) {
+		self.handoff_label.set_label(&format!("Pass to {}", next_player.name));
+		self.handoff_label.set_label_color(Color::from_rgb(next_player.color.0, next_player.color.1, next_player.color.2));
+		self.handoff_group.show();
+		let _ = self.handoff_group.take_focus();
+		self.main_window.redraw();
+	}//end show_handoff(&mut self, next_player)
+
+	/// # hide_handoff(&mut self)
+	/// 
+	/// Uncovers the window once the next player is ready.
+	pub fn hide_handoff(&mut self) {
+		self.handoff_group.hide();
+		let _ = self.board_widget.take_focus();
+		self.main_window.redraw();
+	}//end hide_handoff(&mut self)
+
+	/// # initialize_replay(&mut self)
+	/// 
+	/// Creates the controls used for stepping through a replay, along the bottom of the window.  
+	/// 
+	/// The controls stay hidden until a replay is opened.
+	pub fn initialize_replay(&mut self) {
+		let bar_height = 3 * get_default_txt_height() + get_default_txt_padding();
+		self.replay_group = Group::new(0, get_default_win_height() - bar_height, get_default_win_width(), bar_height, None);
+
+		let mut background = Frame::default_fill();
+		background.set_frame(FrameType::FlatBox);
+		background.set_color(Color::from_rgb(101, 68, 24));
+
+		let mut back_button = Button::default()
+			.with_size(get_default_txt_height() * 2, get_default_txt_height())
+			.with_pos(get_default_txt_padding(), get_default_win_height() - bar_height + get_default_txt_padding() / 2)
+			.with_label("@<");
+		back_button.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+		back_button.set_color(Color::from_rgb(68,140,184));
+		back_button.set_label_color(Color::White);
+		back_button.emit(self.msg_sender.clone(), "Replay:Back".to_string());
+
+		self.replay_slider = HorNiceSlider::default()
+			.with_size(get_default_win_width() - 6 * get_default_txt_height() - 4 * get_default_txt_padding(), get_default_txt_height())
+			.right_of(&back_button, get_default_txt_padding());
+		self.replay_slider.set_step(1.0, 1);
+		self.replay_slider.set_color(Color::from_rgb(147, 196, 125));
+		self.replay_slider.emit(self.msg_sender.clone(), "Replay:Slider".to_string());
+
+		let mut forward_button = Button::default()
+			.with_size(get_default_txt_height() * 2, get_default_txt_height())
+			.right_of(&self.replay_slider, get_default_txt_padding())
+			.with_label("@>");
+		forward_button.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+		forward_button.set_color(Color::from_rgb(68,140,184));
+		forward_button.set_label_color(Color::White);
+		forward_button.emit(self.msg_sender.clone(), "Replay:Forward".to_string());
+
+		let mut info_display = TextDisplay::default()
+			.with_size(get_default_win_width() - 2 * get_default_txt_padding(), 2 * get_default_txt_height())
+			.below_of(&back_button, 0);
+		info_display.set_buffer(self.replay_info.clone());
+		info_display.set_color(Color::from_rgb(147, 196, 125));
+
+		self.replay_group.end();
+		self.replay_group.hide();
+		self.main_window.add(&self.replay_group);
+	}//end initialize_replay(&mut self)
+
+	/// # show_replay(&mut self, replay, step, state)
+	/// 
+	/// Shows the replay controls, and updates the display to show the game as it was at the given step of the replay.  
+	/// 
+	/// The state should be the one rebuilt for that step.
+	pub fn show_replay(&mut self, replay:&Replay, step:usize, state:&GameState) {
+		self.replay_slider.set_range(0.0, replay.step_count() as f64);
+		self.replay_slider.set_value(step as f64);
 
-		self.buying_trees_choice.add_emit(
-			format!("Small Tree ({})", num_small_trees).as_str(), 
-			Shortcut::None, 
-			menu::MenuFlag::Normal, 
-			self.msg_sender.clone(),
-			"Buy:Small".to_string()
-		);
+		let descriptions: Vec<String> = replay.entries_for_step(step).iter().map(|entry| entry.describe(&state.players)).collect();
+		self.replay_info.set_text(&format!("Replay step {} of {}: {}", step, replay.step_count(), descriptions.join(". ")));
 
-		self.buying_trees_choice.add_emit(
-			format!("Medium Tree ({})", num_med_trees).as_str(),
-			Shortcut::None,
-			menu::MenuFlag::Normal,
-			self.msg_sender.clone(),
-			"Buy:Medium".to_string()
-		);
+		self.update_from_state(state, state.current_player);
+		self.replay_group.show();
+		self.main_window.redraw();
+	}//end show_replay(&mut self, replay, step, state)
 
-		self.buying_trees_choice.add_emit(
-			format!("Large Tree ({})", num_large_trees).as_str(),
-			Shortcut::None,
-			menu::MenuFlag::Normal,
-			self.msg_sender.clone(),
-			"Buy:Large".to_string()
-		);
+	/// # hide_replay(&mut self)
+	/// 
+	/// Hides the replay controls, going back to the game in progress.
+	pub fn hide_replay(&mut self) {
+		self.replay_group.hide();
+		self.main_window.redraw();
+	}//end hide_replay(&mut self)
 
-		let mut available_text = "".to_string();
-		for avai in available {
-			available_text += format!("{}\n", avai.size).as_str();
-		}//end looping over available trees
-		self.availible_trees.set_text(&available_text);
-	}//end update_tree_lists(self, to_buy, available)
+	/// # show_plan(&mut self, plan)
+	/// 
+	/// Shows the game as it would be after the plan, with the spots that would be shaded next round darkened.  
+	/// 
+	/// The light cost of the plan and the light the player would get next round are shown above the board, and the light being spent is highlighted on the light track.
+	pub fn show_plan(&mut self, plan:&Plan) {
+		self.update_from_state(&plan.preview, plan.preview.current_player);
+
+		self.board_widget.set_shaded(Some(plan.next_round_shade()));
+
+		let (before, after) = (plan.start.current().light_points, plan.preview.current().light_points);
+		self.light_track.set_pending(if before != after {Some((before, after))} else {None});
+		self.plan_info.set_label(&format!("Planning {} actions: costs {} light, next round +{} light", plan.actions.len(), plan.light_cost(), plan.projected_income()));
+		self.plan_info.show();
+		self.plan_info.redraw();
+	}//end show_plan(&mut self, plan)
+
+	/// # set_animated(&mut self, animated)
+	/// 
+	/// Turns smooth movement of the sun, moon, shadows and point markers on or off.
+	pub fn set_animated(&mut self, animated:bool) {
+		self.animated = animated;
+		self.sun_marker.set_animated(animated);
+		self.board_widget.set_animated(animated);
+		self.light_track.set_animated(animated);
+		self.lunar_track.set_animated(animated);
+		if let Some(mut item) = self.menu.find_item("View/Animations\t") {
+			if animated {item.set();} else {item.clear();}
+		}//end if the menu has been set up yet
+	}//end set_animated(&mut self, animated)
+
+	/// # set_board_palette(&mut self, palette, symbols)
+	/// 
+	/// Changes the colors the board is drawn in, and whether the board and point tracks show leaf counts, hatched shade and owner symbols.
+	pub fn set_board_palette(&mut self, palette:BoardPalette, symbols:bool) {
+		self.board_palette = palette;
+		self.symbols = symbols;
+		self.board_widget.set_palette(palette, symbols);
+		self.light_track.set_symbols(symbols);
+		self.lunar_track.set_symbols(symbols);
+	}//end set_board_palette(&mut self, palette, symbols)
+
+	/// # hide_plan(&mut self)
+	/// 
+	/// Hides the plan mode indicator. The board should be updated from the real game state afterwards.
+	pub fn hide_plan(&mut self) {
+		self.plan_info.hide();
+		self.main_window.redraw();
+	}//end hide_plan(&mut self)
 }//end impl for GUI
@@ -0,0 +1,508 @@
+use std::{cell::RefCell, rc::Rc};
+use fltk::{app::{self, Sender}, draw::{self, LineStyle}, enums::{Align, Color, Event, Font, Key, Shortcut}, prelude::{WidgetBase, WidgetExt}, widget::Widget, widget_extends};
+use grid::Grid;
+use crate::game::{Board, BoardSpot, Moon, MoonDirection, PieceType, SunDirection, TreeSize};
+use super::gui_utils::{get_animation_frame_time, get_animation_frames};
+use super::palette::{BoardPalette, draw_hatching, draw_leaf_glyphs, draw_owner_symbol, text_color_on};
+
+/// color that spots used this turn are washed out toward
+const EXPENDED_GRAY: Color = Color::from_rgb(110, 110, 110);
+
+/// # BoardView
+///
+/// Everything the board widget draws, shared between the widget and its draw and event callbacks.
+#[derive(Debug, Clone)]
+struct BoardView {
+	/// copy of the spots on the board being shown
+	spots: Grid<BoardSpot>,
+	/// spots in shade from the sun where it is now
+	sun_shaded: Grid<bool>,
+	/// spots which were in shade before the sun last moved, while the old shadows fade into the new ones
+	faded_from: Option<Grid<bool>>,
+	/// how far the old shadows have faded into the new ones, from 0 to 1
+	fade: f64,
+	/// direction the sun was shining in when the board was last set
+	sun: Option<SunDirection>,
+	/// the moon, if the board has been set
+	moon: Option<Moon>,
+	/// place around the edge of the board the moon is being drawn, as in Moon::ring_index(). This slides toward where the moon is after it moves.
+	moon_shown: f64,
+	/// spots to darken instead of the shadows from the sun, such as the ones which would be shaded next round while planning
+	shaded: Option<Grid<bool>>,
+	/// spot to outline as chosen, such as where a seed is being planted from
+	selected: Option<(usize,usize)>,
+	/// spot the mouse is over, if any
+	hovered: Option<(usize,usize)>,
+	/// spot the keyboard cursor is on, once the arrow keys have been used
+	cursor: Option<(usize,usize)>,
+	/// whether the moon or shadows are moving right now
+	animating: bool,
+	/// whether the moon slides and shadows fade after the sun and moon move, instead of jumping
+	animated: bool,
+	/// colors used for how fertile each spot is
+	palette: BoardPalette,
+	/// whether to draw leaf counts, hatching over shade and owner symbols, so the board can be read without telling colors apart
+	symbols: bool,
+	/// color of each player, in turn order, which picks the symbol drawn on their trees
+	owners: Vec<(u8,u8,u8)>,
+}//end struct BoardView
+
+/// # BoardWidget
+///
+/// Draws the board as a grid of square cells, showing the fertility of each spot along with any trees, pieces and animals on it.
+/// Spots used this turn are drawn washed out with a dashed outline.
+/// Spots in shade are darkened, and the moon is drawn at its place around the edge of the board.
+/// When the sun and moon move, the moon slides along the edge and the old shadows fade into the new ones.
+/// With symbols turned on, each spot also shows its leaf count, shade is hatched, and trees are marked with their owner's symbol.
+///
+/// Clicking a cell sends "uwu board:row,col", the same message the board used to send from its buttons.
+/// Spots which were already used this turn can't be used again, so clicking one sends "Board:Expended:row,col" instead.
+/// Moving the mouse onto another cell, or off the board, sends "Board:Hover".
+///
+/// The board can also be played from the keyboard. The arrow keys move a cursor between cells, sending "Board:Hover" like the mouse does,
+/// and Enter or Space uses the cell under the cursor the same way clicking it would.
+/// Enter and Space only work while the board has focus, while the arrow keys also work whenever whatever has focus doesn't use them.
+pub struct BoardWidget {
+	/// the widget the board is drawn in
+	widget: Widget,
+	/// what's being drawn
+	view: Rc<RefCell<BoardView>>,
+}//end struct BoardWidget
+
+#[allow(dead_code)]
+impl BoardWidget {
+	/// # new(sender)
+	///
+	/// Creates an empty board widget, which sends board clicks through the sender.
+	pub fn new(sender:Sender<String>) -> BoardWidget {
+		let view = Rc::new(RefCell::new(BoardView {
+			spots: Grid::new(0, 0),
+			sun_shaded: Grid::new(0, 0),
+			faded_from: None,
+			fade: 1.0,
+			sun: None,
+			moon: None,
+			moon_shown: 0.0,
+			shaded: None,
+			selected: None,
+			hovered: None,
+			cursor: None,
+			animating: false,
+			animated: true,
+			palette: BoardPalette::Classic,
+			symbols: false,
+			owners: Vec::new(),
+		}));//end struct construction
+		let mut widget = Widget::default();
+
+		let draw_view = view.clone();
+		widget.draw(move |widget| draw_board(widget, &draw_view.borrow()));
+
+		let handle_view = view.clone();
+		widget.handle(move |widget, event| {
+			let (rows, cols) = handle_view.borrow().spots.size();
+			let cell = cell_at(widget, rows, cols, fltk::app::event_x(), fltk::app::event_y());
+			let use_cell = |cell: Option<(usize,usize)>| {
+				let expended = cell.map(|(row, col)| handle_view.borrow().spots.get(row, col).unwrap().is_expended()).unwrap_or(false);
+				match cell {
+					Some((row, col)) if expended => sender.send(format!("Board:Expended:{},{}", row, col)),
+					Some((row, col)) => sender.send(format!("uwu board:{},{}", row, col)),
+					None => {},
+				}//end matching what was used
+			};//end use_cell closure
+			match event {
+				Event::Push => {
+					let _ = widget.take_focus();
+					use_cell(cell);
+					return cell.is_some();
+				},
+				Event::Enter | Event::Move | Event::Leave => {
+					let hovered = if event == Event::Leave {None} else {cell};
+					if handle_view.borrow().hovered != hovered {
+						handle_view.borrow_mut().hovered = hovered;
+						widget.redraw();
+						sender.send("Board:Hover".to_string());
+					}//end if mouse moved to another cell
+					return true;
+				},
+				Event::Focus | Event::Unfocus => return true,
+				Event::KeyDown | Event::Shortcut => {
+					if rows == 0 || cols == 0 || app::event_state().intersects(Shortcut::Ctrl | Shortcut::Alt | Shortcut::Meta) {
+						return false;
+					}//end if there's no board or the key is part of a menu shortcut
+					let cursor = handle_view.borrow().cursor;
+					let key = app::event_key();
+					if key == Key::Enter || key == Key::KPEnter || key == Key::from_char(' ') {
+						// only while the board has focus, so these keys can still press whatever button does have it
+						if event == Event::KeyDown {
+							use_cell(cursor);
+						}//end if the board has focus
+						return event == Event::KeyDown && cursor.is_some();
+					}//end if user wants to use the spot under the cursor
+					let (row, col) = cursor.unwrap_or((rows / 2, cols / 2));
+					let moved = match key {
+						_ if cursor.is_none() && [Key::Up, Key::Down, Key::Left, Key::Right].contains(&key) => (row, col),
+						Key::Up => (row.saturating_sub(1), col),
+						Key::Down => ((row + 1).min(rows - 1), col),
+						Key::Left => (row, col.saturating_sub(1)),
+						Key::Right => (row, (col + 1).min(cols - 1)),
+						_ => return false,
+					};//end matching which way the cursor moves
+					let mut view = handle_view.borrow_mut();
+					view.cursor = Some(moved);
+					// the cursor takes over from the mouse until the mouse moves again
+					view.hovered = None;
+					drop(view);
+					widget.redraw();
+					sender.send("Board:Hover".to_string());
+					return true;
+				},
+				_ => return false,
+			}//end matching event
+		});
+
+		BoardWidget {
+			widget,
+			view,
+		}//end struct construction
+	}//end new(sender)
+
+	/// # set_board(&mut self, board, sun_shaded)
+	///
+	/// Shows the spots on the given board, with the spots in sun_shaded darkened as the shadows from the sun.
+	///
+	/// If the sun and moon moved since the board was last set, the moon slides to its new place and the old shadows fade into the new ones.
+	pub fn set_board(&mut self, board:&Board, sun_shaded:Grid<bool>) {
+		let mut view = self.view.borrow_mut();
+		let same_size = view.spots.size() == board.board.size();
+		let sun_moved = same_size && view.sun.is_some() && view.sun != Some(board.sun.direction);
+		let moon_moved = same_size && view.moon.is_some_and(|moon| moon.ring_index() != board.moon.ring_index());
+		if view.animated && sun_moved {
+			view.faded_from = Some(view.sun_shaded.clone());
+			view.fade = 0.0;
+		}//end if the old shadows should fade out
+		else if !view.animating {
+			view.faded_from = None;
+			view.fade = 1.0;
+		}//end else if shadows should just show where they are
+		if !same_size {
+			view.cursor = None;
+		}//end if the cursor might be off the new board
+		if !view.animated || !moon_moved {
+			view.moon_shown = board.moon.ring_index() as f64;
+		}//end if the moon should jump straight to its place
+		view.spots = board.board.clone();
+		view.sun_shaded = sun_shaded;
+		view.sun = Some(board.sun.direction);
+		view.moon = Some(board.moon);
+		view.shaded = None;
+		let start_animating = view.animated && !view.animating && (view.fade < 1.0 || view.moon_shown != board.moon.ring_index() as f64);
+		view.animating |= start_animating;
+		drop(view);
+		if start_animating {
+			self.animate();
+		}//end if something needs to move
+		self.widget.redraw();
+	}//end set_board(&mut self, board, sun_shaded)
+
+	/// # set_animated(&mut self, animated)
+	///
+	/// Sets whether the moon slides and shadows fade after the sun and moon move, or whether they jump straight to their new places.
+	pub fn set_animated(&mut self, animated:bool) {
+		self.view.borrow_mut().animated = animated;
+	}//end set_animated(&mut self, animated)
+
+	/// # set_palette(&mut self, palette, symbols)
+	///
+	/// Sets the colors used for fertility, and whether to draw symbols which don't rely on color.
+	pub fn set_palette(&mut self, palette:BoardPalette, symbols:bool) {
+		let mut view = self.view.borrow_mut();
+		view.palette = palette;
+		view.symbols = symbols;
+		drop(view);
+		self.widget.redraw();
+	}//end set_palette(&mut self, palette, symbols)
+
+	/// # set_owners(&mut self, owners)
+	///
+	/// Sets the color of each player in turn order, so trees can be marked with their owner's symbol.
+	pub fn set_owners(&mut self, owners:Vec<(u8,u8,u8)>) {
+		self.view.borrow_mut().owners = owners;
+	}//end set_owners(&mut self, owners)
+
+	/// # animate(&mut self)
+	///
+	/// Fades the shadows and slides the moon a little further each frame, until everything is where it belongs.
+	fn animate(&mut self) {
+		let view = self.view.clone();
+		let mut widget = self.widget.clone();
+		app::add_timeout3(get_animation_frame_time(), move |handle| {
+			let mut view = view.borrow_mut();
+			view.fade = (view.fade + 1.0 / get_animation_frames()).min(1.0);
+			if view.fade >= 1.0 {
+				view.faded_from = None;
+			}//end if the old shadows are gone
+			if let Some(moon) = view.moon {
+				let (target, count) = (moon.ring_index() as f64, moon.ring_count() as f64);
+				let distance = ring_distance(view.moon_shown, target, count);
+				let step = (distance / get_animation_frames()).max(0.05);
+				view.moon_shown = if distance <= step {target} else {(view.moon_shown + step) % count};
+			}//end if there's a moon to move
+			view.animating = view.fade < 1.0 || view.moon.is_some_and(|moon| view.moon_shown != moon.ring_index() as f64);
+			if view.animating {
+				app::repeat_timeout3(get_animation_frame_time(), handle);
+			}//end if anything still needs to move
+			widget.redraw();
+		});
+	}//end animate(&mut self)
+
+	/// # set_shaded(&mut self, shaded)
+	///
+	/// Darkens the spots marked in the grid, which should be parallel to the board.
+	pub fn set_shaded(&mut self, shaded:Option<Grid<bool>>) {
+		self.view.borrow_mut().shaded = shaded;
+		self.widget.redraw();
+	}//end set_shaded(&mut self, shaded)
+
+	/// # set_selected(&mut self, selected)
+	///
+	/// Outlines a spot as chosen, or clears the outline with None.
+	pub fn set_selected(&mut self, selected:Option<(usize,usize)>) {
+		if self.view.borrow().selected != selected {
+			self.view.borrow_mut().selected = selected;
+			self.widget.redraw();
+		}//end if selection changed
+	}//end set_selected(&mut self, selected)
+
+	/// # hovered(&self)
+	///
+	/// Returns the row and column of the spot the mouse is over, or the spot under the keyboard cursor if the mouse isn't over one.
+	pub fn hovered(&self) -> Option<(usize,usize)> {
+		let view = self.view.borrow();
+		return view.hovered.or(view.cursor);
+	}//end hovered(&self)
+
+	/// # cursor(&self)
+	///
+	/// Returns the row and column of the spot under the keyboard cursor, if the arrow keys have been used.
+	pub fn cursor(&self) -> Option<(usize,usize)> {
+		return self.view.borrow().cursor;
+	}//end cursor(&self)
+
+	/// # set_cursor(&mut self, cursor)
+	///
+	/// Moves the keyboard cursor to a spot, or hides it with None.
+	pub fn set_cursor(&mut self, cursor:Option<(usize,usize)>) {
+		if self.view.borrow().cursor != cursor {
+			self.view.borrow_mut().cursor = cursor;
+			self.widget.redraw();
+		}//end if cursor moved
+	}//end set_cursor(&mut self, cursor)
+
+	/// # rows(&self)
+	pub fn rows(&self) -> usize {
+		return self.view.borrow().spots.rows();
+	}//end rows(&self)
+
+	/// # cols(&self)
+	pub fn cols(&self) -> usize {
+		return self.view.borrow().spots.cols();
+	}//end cols(&self)
+}//end impl for BoardWidget
+
+widget_extends!(BoardWidget, Widget, widget);
+
+/// # cell_size(widget, rows, cols)
+///
+/// Returns the width and height of each cell, which are the same so cells stay square.
+fn cell_size<W: WidgetExt>(widget:&W, rows:usize, cols:usize) -> i32 {
+	return (widget.width() / cols.max(1) as i32).min(widget.height() / rows.max(1) as i32).max(1);
+}//end cell_size(widget, rows, cols)
+
+/// # cell_at(widget, rows, cols, x, y)
+///
+/// Returns the row and column of the cell at a point in window coordinates, if there's one there.
+fn cell_at<W: WidgetExt>(widget:&W, rows:usize, cols:usize, x:i32, y:i32) -> Option<(usize,usize)> {
+	let cell = cell_size(widget, rows, cols);
+	let (dx, dy) = (x - widget.x(), y - widget.y());
+	if dx < 0 || dy < 0 {
+		return None;
+	}//end if point is above or left of the board
+	let (row, col) = ((dy / cell) as usize, (dx / cell) as usize);
+	if row >= rows || col >= cols {
+		return None;
+	}//end if point is below or right of the board
+	return Some((row, col));
+}//end cell_at(widget, rows, cols, x, y)
+
+/// # draw_board(widget, view)
+///
+/// Paints every cell of the board, then the outlines for the hovered, cursor and selected cells on top.
+fn draw_board(widget:&Widget, view:&BoardView) {
+	let (rows, cols) = view.spots.size();
+	let cell = cell_size(widget, rows, cols);
+	let font_size = (cell / 6).clamp(8, 16);
+	for row in 0..rows {
+		for col in 0..cols {
+			let (x, y) = (widget.x() + col as i32 * cell, widget.y() + row as i32 * cell);
+			let spot = view.spots.get(row, col).unwrap();
+			let is_shaded = |grid: &Grid<bool>| if grid.get(row, col).copied().unwrap_or(false) {1.0} else {0.0};
+			// how much to darken the cell, which is partway while the old shadows fade into the new ones
+			let shade = match (&view.shaded, &view.faded_from) {
+				(Some(shaded), _) => is_shaded(shaded),
+				(None, Some(faded_from)) => is_shaded(faded_from) * (1.0 - view.fade) + is_shaded(&view.sun_shaded) * view.fade,
+				(None, None) => is_shaded(&view.sun_shaded),
+			};//end matching which shadows to show
+			let mut background = match spot.piece_type {
+				PieceType::GreatElderTree => Color::from_rgb(92, 64, 51),
+				_ => view.palette.fertility_color(spot.fertility),
+			};//end matching what color the cell should be
+			if shade > 0.0 {
+				background = Color::color_average(background.darker().darker(), background, shade as f32);
+			}//end if cell is shaded
+			// spots used this turn are washed out toward gray, which looks different from shade
+			let dim = |color: Color| if spot.is_expended() {Color::color_average(color, EXPENDED_GRAY, 0.45)} else {color};
+			background = dim(background);
+			draw::draw_rect_fill(x, y, cell, cell, background);
+			draw::draw_rect_with_color(x, y, cell, cell, background.darker());
+			if view.symbols {
+				let marks = Color::color_average(text_color_on(background), background, 0.6);
+				if shade >= 0.5 {
+					draw_hatching(x + 1, y + 1, cell - 2, cell - 2, marks);
+				}//end if cell is mostly in shade
+				if spot.piece_type != PieceType::GreatElderTree {
+					draw_leaf_glyphs(spot.fertility, x + 4, y + 4, cell / 8, marks);
+				}//end if spot has a fertility to show
+			}//end if drawing symbols
+
+			match (spot.tree, spot.piece_type) {
+				(Some(tree), _) => {
+					let tree_color = dim(Color::from_rgb(tree.color.0, tree.color.1, tree.color.2));
+					draw_tree(x, y, cell, tree.size, tree_color);
+					if let Some(owner) = view.owners.iter().position(|color| *color == tree.color).filter(|_| view.symbols) {
+						let (center_x, center_y, radius) = tree_canopy(x, y, cell, tree.size);
+						draw_owner_symbol(owner, center_x, center_y, radius / 2, text_color_on(tree_color));
+					}//end if the tree should show who owns it
+					draw::set_font(Font::HelveticaBold, font_size);
+					draw::set_draw_color(text_color_on(background));
+					draw::draw_text2(&format!("{}", tree.size), x, y + cell - font_size - 4, cell, font_size + 2, Align::Center);
+				},
+				(None, PieceType::GreatElderTree) => {
+					draw::set_font(Font::HelveticaBold, font_size);
+					draw::set_draw_color(Color::from_rgb(241, 196, 15));
+					draw::draw_text2("Great\nElder\nTree", x, y, cell, cell, Align::Center);
+				},
+				(None, PieceType::Moonstone) => {
+					let radius = cell / 3;
+					draw::set_draw_color(Color::from_rgb(52, 73, 94));
+					draw::draw_pie(x + cell / 2 - radius, y + cell / 2 - radius, 2 * radius, 2 * radius, 0.0, 360.0);
+					draw::set_font(Font::HelveticaBold, font_size);
+					draw::set_draw_color(Color::from_rgb(236, 240, 241));
+					draw::draw_text2("Moon\nstone", x, y, cell, cell, Align::Center);
+				},
+				_ => {},
+			}//end matching what's on this spot
+
+			if spot.is_expended() {
+				draw::set_draw_color(Color::from_rgb(60, 60, 60));
+				draw::set_line_style(LineStyle::Dash, 2);
+				draw::draw_rect(x + 3, y + 3, cell - 6, cell - 6);
+				draw::set_line_style(LineStyle::Solid, 0);
+			}//end if spot was used this turn
+
+			if let Some(animal) = spot.animal {
+				draw::set_font(Font::Helvetica, font_size);
+				draw::set_draw_color(text_color_on(background));
+				draw::draw_text2(&format!("{}", animal), x, y + 2, cell, font_size + 2, Align::Center);
+			}//end if there's an animal here
+		}//end looping over cols
+	}//end looping over rows
+
+	if let Some(moon) = view.moon {
+		draw_moon(widget, cell, rows, &moon, view.moon_shown);
+	}//end if there's a moon to draw
+
+	// outlines go last, so neighboring cells don't draw over them
+	for (outlined, color, width) in [(view.hovered, Color::White, 2), (view.cursor, Color::from_rgb(52, 152, 219), 3), (view.selected, Color::from_rgb(241, 196, 15), 4)] {
+		if let Some((row, col)) = outlined {
+			draw::set_draw_color(color);
+			draw::set_line_style(LineStyle::Solid, width);
+			draw::draw_rect(widget.x() + col as i32 * cell + width / 2, widget.y() + row as i32 * cell + width / 2, cell - width, cell - width);
+			draw::set_line_style(LineStyle::Solid, 0);
+		}//end if there's a cell to outline
+	}//end outlining hovered, cursor and selected cells
+}//end draw_board(widget, view)
+
+/// # ring_distance(from, to, count)
+///
+/// Returns how far it is from one place around the edge of the board to another, going counter-clockwise.
+/// The moon only ever moves counter-clockwise, so it's never animated going the other way, even when that would be shorter.
+fn ring_distance(from:f64, to:f64, count:f64) -> f64 {
+	return (to - from).rem_euclid(count);
+}//end ring_distance(from, to, count)
+
+/// # moon_point(widget, cell, side, index)
+///
+/// Returns the center of the moon marker when the moon is at the given place around the edge of the board, as in Moon::ring_index().
+/// The marker sits between the two spots the moon's light starts from, just inside the edge of the board.
+fn moon_point(widget:&Widget, cell:i32, side:usize, index:usize) -> (f64, f64) {
+	let moon = Moon::at_ring_index(side, index, false);
+	let center = |row: usize, col: usize| (widget.x() as f64 + (col as f64 + 0.5) * cell as f64, widget.y() as f64 + (row as f64 + 0.5) * cell as f64);
+	let ((x1, y1), (x2, y2)) = (center(moon.row1, moon.col1), center(moon.row2, moon.col2));
+	let outward = cell as f64 * 0.25;
+	let (dx, dy) = match moon.direction {
+		MoonDirection::South => (0.0, -outward),
+		MoonDirection::East => (-outward, 0.0),
+		MoonDirection::North => (0.0, outward),
+		MoonDirection::West => (outward, 0.0),
+	};//end matching which edge the moon is on
+	return ((x1 + x2) / 2.0 + dx, (y1 + y2) / 2.0 + dy);
+}//end moon_point(widget, cell, side, index)
+
+/// # draw_moon(widget, cell, side, moon, shown)
+///
+/// Draws the moon marker at a place around the edge of the board, which can be partway between two places while it's sliding.
+/// A full moon is drawn as a full circle, and a half moon as half of one.
+fn draw_moon(widget:&Widget, cell:i32, side:usize, moon:&Moon, shown:f64) {
+	let count = moon.ring_count();
+	if side < 2 {
+		return;
+	}//end if the board is too small to have a moon
+	let from = shown.floor() as usize % count;
+	let along = shown - shown.floor();
+	let ((x1, y1), (x2, y2)) = (moon_point(widget, cell, side, from), moon_point(widget, cell, side, from + 1));
+	let (center_x, center_y) = ((x1 + (x2 - x1) * along).round() as i32, (y1 + (y2 - y1) * along).round() as i32);
+	let radius = (cell / 5).max(3);
+	let (x, y) = (center_x - radius, center_y - radius);
+	draw::set_draw_color(Color::from_rgb(236, 240, 241));
+	if moon.full_moon {
+		draw::draw_pie(x, y, 2 * radius, 2 * radius, 0.0, 360.0);
+	}//end if it's a full moon
+	else {
+		draw::draw_pie(x, y, 2 * radius, 2 * radius, 90.0, 270.0);
+	}//end else it's a half moon
+	draw::set_draw_color(Color::from_rgb(52, 73, 94));
+	draw::draw_arc(x, y, 2 * radius, 2 * radius, 0.0, 360.0);
+}//end draw_moon(widget, cell, side, moon, shown)
+
+/// # draw_tree(x, y, cell, size, color)
+///
+/// Draws a tree in a cell, with a canopy in its owner's color which gets bigger as the tree grows. Seeds are drawn without a trunk.
+pub(super) fn draw_tree(x:i32, y:i32, cell:i32, size:TreeSize, color:Color) {
+	let (center_x, center_y, radius) = tree_canopy(x, y, cell, size);
+	if size != TreeSize::Seed {
+		let trunk_w = (cell / 10).max(2);
+		draw::draw_rect_fill(center_x - trunk_w / 2, center_y, trunk_w, cell / 3, Color::from_rgb(92, 64, 51));
+	}//end if tree has a trunk
+	draw::set_draw_color(color);
+	draw::draw_pie(center_x - radius, center_y - radius, 2 * radius, 2 * radius, 0.0, 360.0);
+	draw::set_draw_color(Color::Black);
+	draw::draw_arc(center_x - radius, center_y - radius, 2 * radius, 2 * radius, 0.0, 360.0);
+}//end draw_tree(x, y, cell, size, color)
+
+/// # tree_canopy(x, y, cell, size)
+///
+/// Returns the center and radius of the canopy of a tree drawn in a cell.
+fn tree_canopy(x:i32, y:i32, cell:i32, size:TreeSize) -> (i32, i32, i32) {
+	return (x + cell / 2, y + cell / 2 - cell / 10, cell * [10, 17, 24, 31][size.size()] / 100);
+}//end tree_canopy(x, y, cell, size)
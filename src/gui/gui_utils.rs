@@ -1,4 +1,4 @@
-use fltk::{prelude::{WidgetExt, GroupExt, WidgetBase}, button::Button, group::{Flex, self}, widget_extends, enums::Align, widget::Widget};
+use fltk::{app, prelude::{WidgetExt, GroupExt, WidgetBase, DisplayExt, MenuExt}, button::Button, group::{Flex, self}, widget_extends, enums::Align, widget::Widget, text::TextDisplay, menu::Choice};
 use grid::Grid;
 
 
@@ -15,6 +15,131 @@ pub fn get_default_txt_width() -> i32 {(get_default_win_width() / 6) - (get_defa
 pub fn get_default_txt_height() -> i32 {25}
 pub fn get_default_txt_padding() -> i32 {10}
 pub fn get_default_grid_padding() -> i32 {100}
+pub fn get_min_win_width() -> i32 {700}
+pub fn get_min_win_height() -> i32 {490}
+pub fn get_animation_frame_time() -> f64 {1.0 / 60.0}
+pub fn get_animation_frames() -> f64 {20.0}
+
+/// # scale_size(size, scale)
+/// 
+/// Scales a size or position which was chosen for the default window size.
+pub fn scale_size(size:i32, scale:f64) -> i32 {
+	return (size as f64 * scale).round() as i32;
+}//end scale_size(size, scale)
+
+/// # get_fitting_win_size()
+/// 
+/// Returns the size the main window should start at, which is the default size unless that won't fit on the screen.  
+/// 
+/// The screen's work area is measured in FLTK units, which already account for the screen scale on HiDPI screens, so this only shrinks the window on screens which are actually small.
+pub fn get_fitting_win_size() -> (i32, i32) {
+	let (_, _, screen_w, screen_h) = app::screen_work_area(0);
+	if screen_w <= 0 || screen_h <= 0 {
+		return (get_default_win_width(), get_default_win_height());
+	}//end if we couldn't find out how big the screen is
+	let scale = (0.9 * screen_w as f64 / get_default_win_width() as f64).min(0.9 * screen_h as f64 / get_default_win_height() as f64).min(1.0);
+	return (scale_size(get_default_win_width(), scale).max(get_min_win_width()), scale_size(get_default_win_height(), scale).max(get_min_win_height()));
+}//end get_fitting_win_size()
+
+/// # BoardLayout
+/// 
+/// Where the board and the sun and moon markers around it go for one size of window.  
+/// 
+/// Everything else in the window is scaled evenly by the same scale, starting from where it goes at the default window size.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BoardLayout {
+	/// how much bigger the window is than the default size, going by whichever side is relatively shorter
+	pub scale: f64,
+	/// x, y, width and height of the board, which always has square cells
+	pub board: (i32,i32,i32,i32),
+	/// width and height of each sun marker
+	pub sun_size: i32,
+	/// top left corner of each sun marker, going clockwise from the southwest corner of the board
+	pub suns: [(i32,i32); 8],
+	/// x, y, width and height of the moon marker
+	pub moon: (i32,i32,i32,i32),
+}//end struct BoardLayout
+
+impl BoardLayout {
+	/// # new(win_w, win_h, rows, cols)
+	/// 
+	/// Works out where the board goes in a window of the given size.  
+	/// 
+	/// The board gets as much of the space to the right of the side panel as it can, while keeping every cell square, and is centered in that space.
+	pub fn new(win_w:i32, win_h:i32, rows:usize, cols:usize) -> BoardLayout {
+		let scale = (win_w as f64 / get_default_win_width() as f64).min(win_h as f64 / get_default_win_height() as f64);
+		// space left for the board, after the side panel on the left, the info bar on top, and room for the moon and suns
+		let (space_x, space_y) = (scale_size(get_default_win_width() - get_default_grid_width() - get_default_grid_padding(), scale), scale_size(175, scale));
+		let space_w = win_w - scale_size(get_default_grid_padding(), scale) - space_x;
+		let space_h = win_h - scale_size(80, scale) - space_y;
+		let cell = (space_w / cols.max(1) as i32).min(space_h / rows.max(1) as i32).max(1);
+		let (board_w, board_h) = (cell * cols as i32, cell * rows as i32);
+		let (board_x, board_y) = (space_x + (space_w - board_w) / 2, space_y + (space_h - board_h) / 2);
+
+		let sun_size = scale_size(100, scale);
+		let (left, top) = (board_x - sun_size / 2, board_y - sun_size / 2);
+		let (middle_x, middle_y, right, bottom) = (left + board_w / 2, top + board_h / 2, left + board_w, top + board_h);
+		return BoardLayout {
+			scale,
+			board: (board_x, board_y, board_w, board_h),
+			sun_size,
+			suns: [(left, bottom), (left, middle_y), (left, top), (middle_x, top), (right, top), (right, middle_y), (right, bottom), (middle_x, bottom)],
+			moon: (win_w - sun_size, scale_size(125, scale), sun_size, sun_size),
+		}//end struct construction
+	}//end new(win_w, win_h, rows, cols)
+}//end impl for BoardLayout
+
+/// # ScaledWidget
+/// 
+/// A widget placed for the default window size, which keeps the same place relative to the rest of the window as it's resized.  
+/// 
+/// The widget is scaled evenly from where it was first placed, so it never gets stretched.
+pub struct ScaledWidget {
+	/// the widget being scaled
+	widget: Widget,
+	/// x, y, width and height at the default window size
+	rect: (i32,i32,i32,i32),
+	/// size of the label at the default window size
+	label_size: i32,
+	/// size of the text inside text displays and menus at the default window size, if the widget has any
+	text_size: Option<i32>,
+}//end struct ScaledWidget
+
+impl ScaledWidget {
+	/// # new(widget)
+	/// 
+	/// Remembers where a widget is now, which should be where it goes at the default window size.
+	pub fn new<W: WidgetExt>(widget:&W) -> ScaledWidget {
+		let text_size = match (TextDisplay::from_dyn_widget(widget), Choice::from_dyn_widget(widget)) {
+			(Some(display), _) => Some(display.text_size()),
+			(None, Some(choice)) => Some(choice.text_size()),
+			(None, None) => None,
+		};//end matching whether widget has text inside it
+		ScaledWidget {
+			widget: widget.as_base_widget(),
+			rect: (widget.x(), widget.y(), widget.width(), widget.height()),
+			label_size: widget.label_size(),
+			text_size,
+		}//end struct construction
+	}//end new(widget)
+
+	/// # apply(&mut self, scale)
+	/// 
+	/// Moves and resizes the widget for a window the given amount bigger than the default size.
+	pub fn apply(&mut self, scale:f64) {
+		let (x, y, w, h) = self.rect;
+		self.widget.resize(scale_size(x, scale), scale_size(y, scale), scale_size(w, scale), scale_size(h, scale));
+		self.widget.set_label_size(scale_size(self.label_size, scale));
+		if let Some(text_size) = self.text_size {
+			if let Some(mut display) = TextDisplay::from_dyn_widget(&self.widget) {
+				display.set_text_size(scale_size(text_size, scale));
+			}//end if widget is a text display
+			else if let Some(mut choice) = Choice::from_dyn_widget(&self.widget) {
+				choice.set_text_size(scale_size(text_size, scale));
+			}//end else if widget is a menu
+		}//end if widget has text inside it
+	}//end apply(&mut self, scale)
+}//end impl for ScaledWidget
 
 /// # FlexGrid
 /// 
@@ -0,0 +1,61 @@
+use fltk::{app, button::Button, enums::{Align, Color, Font}, frame::Frame, prelude::{GroupExt, WidgetBase, WidgetExt, WindowExt}, window::Window};
+use fltk_theme::widget_themes;
+
+/// every key the game listens for, along with what it does, in the order they're listed in the help dialog
+const KEYBOARD_SHORTCUTS: [(&str, &str); 13] = [
+	("Arrow keys", "Move the cursor around the board"),
+	("Enter or Space", "Use the spot under the cursor, the same as clicking it"),
+	("Esc", "Forget the tree a seed was coming from, or hide the cursor"),
+	("G", "Grow the trees on spots you use"),
+	("S", "Plant seeds: use the parent tree, then where the seed goes"),
+	("C", "Collect the trees on spots you use"),
+	("1, 2, 3, 4", "Buy a seed, small tree, medium tree or large tree"),
+	("P", "Switch between planning and placing"),
+	("E", "End your turn"),
+	("Ctrl+Z", "Undo"),
+	("Ctrl+Y", "Redo"),
+	("Ctrl+,", "Preferences"),
+	("F1", "Show this list"),
+];
+
+/// # run_keyboard_help()
+///
+/// Shows a dialog listing every key the game listens for. This waits until the dialog is closed.
+pub fn run_keyboard_help() {
+	let (sender, receiver) = app::channel::<bool>();
+	let row_h = 24;
+	let rows = KEYBOARD_SHORTCUTS.len() as i32;
+	let mut window = Window::default().with_size(520, 120 + rows * row_h).with_label("Keyboard Shortcuts");
+	window.set_color(Color::from_rgb(101, 68, 24));
+	window.make_modal(true);
+
+	let mut title = Frame::new(20, 10, 480, 40, "Keyboard Shortcuts");
+	title.set_label_size(24);
+	title.set_label_color(Color::White);
+	title.set_label_font(Font::HelveticaBold);
+
+	for (row, (keys, action)) in KEYBOARD_SHORTCUTS.iter().enumerate() {
+		let y = 60 + row as i32 * row_h;
+		let mut key_label = Frame::new(20, y, 130, row_h, None).with_label(keys).with_align(Align::Right | Align::Inside);
+		key_label.set_label_color(Color::from_rgb(241, 196, 15));
+		key_label.set_label_font(Font::HelveticaBold);
+		let mut action_label = Frame::new(165, y, 335, row_h, None).with_label(action).with_align(Align::Left | Align::Inside);
+		action_label.set_label_color(Color::White);
+	}//end adding a row for each shortcut
+
+	let mut close = Button::new(400, 70 + rows * row_h, 100, 30, "Close");
+	close.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+	close.set_color(Color::from_rgb(68,140,184));
+	close.set_label_color(Color::White);
+	close.emit(sender, true);
+	window.set_callback(move |_| sender.send(true));
+
+	window.end();
+	window.show();
+	while window.shown() && app::wait() {
+		if receiver.recv().is_some() {
+			window.hide();
+		}//end if dialog should close
+	}//end waiting for the dialog to close
+	Window::delete(window);
+}//end run_keyboard_help()
@@ -0,0 +1,119 @@
+use std::fmt::Display;
+use fltk::{draw::{self, Coord}, enums::Color};
+use crate::game::Fertility;
+
+/// # BoardPalette
+///
+/// The colors used for how fertile each spot on the board is.
+///
+/// The classic palette is four shades of green, which some players can't tell apart.
+/// The others go from light to dark through colors which are still different for the common kinds of color blindness, and stay in order when printed in grayscale.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Default)]
+pub enum BoardPalette {
+	/// four shades of green, getting darker as spots get more fertile
+	#[default]
+	Classic,
+	/// yellow through green and blue to purple
+	Viridis,
+	/// yellow through gray to navy, made for red-green color blindness
+	Cividis,
+}//end enum BoardPalette
+
+#[allow(dead_code)]
+impl BoardPalette {
+	/// # all()
+	///
+	/// Returns every palette, in the order they're offered.
+	pub fn all() -> [BoardPalette; 3] {
+		return [BoardPalette::Classic, BoardPalette::Viridis, BoardPalette::Cividis];
+	}//end all()
+
+	/// # from_name(name)
+	///
+	/// Returns the palette with the given name, ignoring case, as written by Display.
+	pub fn from_name(name: &str) -> Option<BoardPalette> {
+		return BoardPalette::all().into_iter().find(|palette| palette.to_string().eq_ignore_ascii_case(name.trim()));
+	}//end from_name(name)
+
+	/// # fertility_color(&self, fertility)
+	///
+	/// Returns the color a board spot should be, based on how fertile it is. More fertile spots are darker in every palette.
+	pub fn fertility_color(&self, fertility: Fertility) -> Color {
+		let (r, g, b) = match (self, fertility) {
+			(BoardPalette::Classic, Fertility::OneLeaf) => (147, 196, 125),
+			(BoardPalette::Classic, Fertility::TwoLeaf) => (106, 168, 79),
+			(BoardPalette::Classic, Fertility::ThreeLeaf) => (56, 118, 29),
+			(BoardPalette::Classic, Fertility::FourLeaf) => (39, 78, 19),
+			(BoardPalette::Viridis, Fertility::OneLeaf) => (253, 231, 37),
+			(BoardPalette::Viridis, Fertility::TwoLeaf) => (53, 183, 121),
+			(BoardPalette::Viridis, Fertility::ThreeLeaf) => (49, 104, 142),
+			(BoardPalette::Viridis, Fertility::FourLeaf) => (68, 1, 84),
+			(BoardPalette::Cividis, Fertility::OneLeaf) => (255, 234, 70),
+			(BoardPalette::Cividis, Fertility::TwoLeaf) => (166, 157, 117),
+			(BoardPalette::Cividis, Fertility::ThreeLeaf) => (87, 92, 109),
+			(BoardPalette::Cividis, Fertility::FourLeaf) => (0, 32, 77),
+		};//end matching palette and fertility
+		return Color::from_rgb(r, g, b);
+	}//end fertility_color(&self, fertility)
+}//end impl for BoardPalette
+
+impl Display for BoardPalette {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BoardPalette::Classic => write!(f, "Classic"),
+			BoardPalette::Viridis => write!(f, "Viridis"),
+			BoardPalette::Cividis => write!(f, "Cividis"),
+		}//end matching self
+	}//end fmt(&self, f)
+}//end impl Display for BoardPalette
+
+/// # text_color_on(background)
+///
+/// Returns black or white, whichever is easier to read on the background.
+pub(super) fn text_color_on(background:Color) -> Color {
+	let (r, g, b) = background.to_rgb();
+	let brightness = 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+	return if brightness > 128_000 {Color::Black} else {Color::White};
+}//end text_color_on(background)
+
+/// # draw_owner_symbol(index, center_x, center_y, radius, color)
+///
+/// Draws a small shape for the player at the given index, so trees and markers can be told apart without relying on color.
+/// In turn order, players get a dot, a square, a triangle and a diamond.
+pub(super) fn draw_owner_symbol(index:usize, center_x:i32, center_y:i32, radius:i32, color:Color) {
+	let (x, y, r) = (center_x, center_y, radius.max(2));
+	draw::set_draw_color(color);
+	match index % 4 {
+		0 => draw::draw_pie(x - r, y - r, 2 * r, 2 * r, 0.0, 360.0),
+		1 => draw::draw_rect_fill(x - r * 4 / 5, y - r * 4 / 5, r * 8 / 5, r * 8 / 5, color),
+		2 => draw::draw_polygon(x, y - r, x + r, y + r * 4 / 5, x - r, y + r * 4 / 5),
+		_ => draw::draw_polygon3(Coord(x, y - r), Coord(x + r, y), Coord(x, y + r), Coord(x - r, y)),
+	}//end matching which symbol this player gets
+}//end draw_owner_symbol(index, center_x, center_y, radius, color)
+
+/// # draw_leaf_glyphs(fertility, x, y, size, color)
+///
+/// Draws one small leaf for each leaf of fertility, in a column going down from x, y, so fertility can be read without relying on color.
+pub(super) fn draw_leaf_glyphs(fertility:Fertility, x:i32, y:i32, size:i32, color:Color) {
+	let size = size.max(3);
+	draw::set_draw_color(color);
+	for leaf in 0..fertility.as_int() {
+		// each leaf is a flat oval, so a column of them stays narrow
+		draw::draw_pie(x, y + leaf * (size / 2 + 2), size, size / 2, 0.0, 360.0);
+	}//end drawing each leaf
+}//end draw_leaf_glyphs(fertility, x, y, size, color)
+
+/// # draw_hatching(x, y, w, h, color)
+///
+/// Draws diagonal lines across a rectangle, which marks spots in shade in a way that doesn't depend on seeing how dark they are.
+pub(super) fn draw_hatching(x:i32, y:i32, w:i32, h:i32, color:Color) {
+	let spacing = (w.min(h) / 6).max(4);
+	draw::push_clip(x, y, w, h);
+	draw::set_draw_color(color);
+	let mut offset = -h;
+	while offset < w {
+		draw::draw_line(x + offset, y + h, x + offset + h, y);
+		offset += spacing;
+	}//end drawing each line
+	draw::pop_clip();
+}//end draw_hatching(x, y, w, h, color)
@@ -0,0 +1,166 @@
+use std::{cell::RefCell, rc::Rc};
+use fltk::{app::{self, Sender}, draw::{self, LineStyle}, enums::{Align, Color, Event, Font, FrameType}, prelude::{WidgetBase, WidgetExt}, widget::Widget, widget_extends};
+use crate::game::{PlayerBoard, TreeSize};
+use super::board_widget::draw_tree;
+
+/// tree sizes in the order their columns are drawn
+const COLUMN_SIZES: [TreeSize; 4] = [TreeSize::Seed, TreeSize::Small, TreeSize::Medium, TreeSize::Large];
+
+/// # StoreView
+///
+/// Everything the player board widget draws, shared between the widget and its draw and event callbacks.
+#[derive(Debug, PartialEq, Clone)]
+struct StoreView {
+	/// the player board being shown
+	board: PlayerBoard,
+	/// color of the player the board belongs to
+	color: Color,
+	/// column the mouse is over, if any
+	hovered: Option<TreeSize>,
+}//end struct StoreView
+
+/// # PlayerBoardWidget
+///
+/// Draws a player board the way it's laid out in the physical game, with a column of priced slots for each tree size.
+///
+/// Filled slots show a tree, and the cheapest filled slot in each column is outlined, since that's the one the next tree is bought from.
+/// Clicking a column sends "Buy:" followed by the tree size, such as "Buy:Medium".
+pub struct PlayerBoardWidget {
+	/// the widget the player board is drawn in
+	widget: Widget,
+	/// what's being drawn
+	view: Rc<RefCell<StoreView>>,
+}//end struct PlayerBoardWidget
+
+#[allow(dead_code)]
+impl PlayerBoardWidget {
+	/// # new(sender)
+	///
+	/// Creates a player board widget, which sends purchases through the sender.
+	pub fn new(sender:Sender<String>) -> PlayerBoardWidget {
+		let view = Rc::new(RefCell::new(StoreView {
+			board: PlayerBoard::default(),
+			color: Color::White,
+			hovered: None,
+		}));//end struct construction
+		let mut widget = Widget::default();
+		widget.set_frame(FrameType::RoundedBox);
+		widget.set_color(Color::from_rgb(56, 118, 29));
+
+		let draw_view = view.clone();
+		widget.draw(move |widget| draw_store(widget, &draw_view.borrow()));
+
+		let handle_view = view.clone();
+		widget.handle(move |widget, event| {
+			let column = column_at(widget, app::event_x(), app::event_y());
+			match event {
+				Event::Push => {
+					if let Some(size) = column {
+						sender.send(format!("Buy:{}", size));
+					}//end if a column was clicked
+					return column.is_some();
+				},
+				Event::Enter | Event::Move | Event::Leave => {
+					let hovered = if event == Event::Leave {None} else {column};
+					if handle_view.borrow().hovered != hovered {
+						handle_view.borrow_mut().hovered = hovered;
+						widget.redraw();
+					}//end if mouse moved to another column
+					return true;
+				},
+				_ => return false,
+			}//end matching event
+		});
+
+		PlayerBoardWidget {
+			widget,
+			view,
+		}//end struct construction
+	}//end new(sender)
+
+	/// # set_board(&mut self, board, color)
+	///
+	/// Shows a player's board, with trees drawn in their color.
+	pub fn set_board(&mut self, board:&PlayerBoard, color:(u8,u8,u8)) {
+		let mut view = self.view.borrow_mut();
+		view.board = board.clone();
+		view.color = Color::from_rgb(color.0, color.1, color.2);
+		drop(view);
+		self.widget.redraw();
+	}//end set_board(&mut self, board, color)
+}//end impl for PlayerBoardWidget
+
+widget_extends!(PlayerBoardWidget, Widget, widget);
+
+/// # header_height(widget)
+///
+/// Returns the height of the row of column names at the top, which is the same as the caption at the bottom.
+fn header_height<W: WidgetExt>(widget:&W) -> i32 {
+	return (widget.height() / 7).max(10);
+}//end header_height(widget)
+
+/// # column_at(widget, x, y)
+///
+/// Returns the tree size of the column at a point in window coordinates, if there's one there.
+fn column_at<W: WidgetExt>(widget:&W, x:i32, y:i32) -> Option<TreeSize> {
+	let column_w = (widget.width() / COLUMN_SIZES.len() as i32).max(1);
+	if x < widget.x() || y < widget.y() || y >= widget.y() + widget.height() {
+		return None;
+	}//end if point is outside the widget
+	return COLUMN_SIZES.get(((x - widget.x()) / column_w) as usize).copied();
+}//end column_at(widget, x, y)
+
+/// # draw_store(widget, view)
+///
+/// Paints the column names, every slot with its price, and a caption saying what clicking the hovered column would buy.
+fn draw_store(widget:&Widget, view:&StoreView) {
+	draw::draw_box(widget.frame(), widget.x(), widget.y(), widget.width(), widget.height(), widget.color());
+	let header = header_height(widget);
+	let column_w = widget.width() / COLUMN_SIZES.len() as i32;
+	let rows = COLUMN_SIZES.iter().map(|size| view.board.column(*size).len()).max().unwrap_or(1).max(1) as i32;
+	let slot = ((widget.height() - 2 * header) / rows).min(column_w);
+	let font_size = (header - 2).clamp(8, 14);
+
+	for (index, size) in COLUMN_SIZES.iter().enumerate() {
+		let x = widget.x() + index as i32 * column_w;
+		draw::set_font(Font::HelveticaBold, font_size);
+		draw::set_draw_color(Color::White);
+		draw::draw_text2(&format!("{}", size), x, widget.y(), column_w, header, Align::Center);
+
+		// slots fill up from the bottom, cheapest first, like the physical board
+		let next_price = view.board.next_price(*size);
+		let mut outlined = false;
+		let bottom = widget.y() + widget.height() - header;
+		for (slot_index, store_slot) in view.board.column(*size).iter().enumerate() {
+			let (slot_x, slot_y) = (x + (column_w - slot) / 2, bottom - (slot_index as i32 + 1) * slot);
+			let fill = if store_slot.filled {Color::from_rgb(147, 196, 125)} else {Color::from_rgb(39, 78, 19)};
+			draw::draw_rect_fill(slot_x + 1, slot_y + 1, slot - 2, slot - 2, fill);
+			if store_slot.filled {
+				draw_tree(slot_x, slot_y, slot, *size, view.color);
+			}//end if there's a tree in this slot
+			draw::set_font(Font::HelveticaBold, (font_size - 2).max(8));
+			draw::set_draw_color(if store_slot.filled {Color::Black} else {Color::from_rgb(147, 196, 125)});
+			draw::draw_text2(&format!("{}", store_slot.price), slot_x + 3, slot_y + 2, slot / 2, font_size, Align::Left);
+			if store_slot.filled && Some(store_slot.price) == next_price && !outlined {
+				// the next tree bought comes from here
+				outlined = true;
+				let width = if view.hovered == Some(*size) {3} else {1};
+				draw::set_draw_color(Color::from_rgb(241, 196, 15));
+				draw::set_line_style(LineStyle::Solid, width);
+				draw::draw_rect(slot_x + 1, slot_y + 1, slot - 2, slot - 2);
+				draw::set_line_style(LineStyle::Solid, 0);
+			}//end if this is the cheapest filled slot
+		}//end drawing each slot
+	}//end drawing each column
+
+	let caption = match view.hovered {
+		Some(size) => match view.board.next_price(size) {
+			Some(price) => format!("Buy a {} for {} light", size, price),
+			None => format!("No {} trees left to buy", size),
+		},
+		None => "Click a column to buy a tree".to_string(),
+	};//end matching which column the mouse is over
+	draw::set_font(Font::Helvetica, font_size);
+	draw::set_draw_color(Color::White);
+	draw::draw_text2(&caption, widget.x(), widget.y() + widget.height() - header, widget.width(), header, Align::Center);
+}//end draw_store(widget, view)
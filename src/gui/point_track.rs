@@ -0,0 +1,215 @@
+use std::{cell::RefCell, rc::Rc};
+use fltk::{app, draw, enums::{Align, Color, Font, FrameType}, prelude::{WidgetBase, WidgetExt}, widget::Widget, widget_extends};
+use super::gui_utils::{get_animation_frame_time, get_animation_frames};
+use super::palette::{draw_owner_symbol, text_color_on};
+
+/// # TrackMarker
+///
+/// One player's marker on a track.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct TrackMarker {
+	/// color of the player the marker belongs to
+	color: Color,
+	/// where the marker belongs on the track
+	value: usize,
+	/// where the marker is being drawn, which slides toward value after it changes
+	shown: f64,
+}//end struct TrackMarker
+
+/// # TrackView
+///
+/// Everything a track draws, shared between the widget and its draw callback and animation.
+#[derive(Debug, PartialEq, Clone)]
+struct TrackView {
+	/// name drawn at the start of the track
+	title: String,
+	/// highest number on the track
+	max: usize,
+	/// each player's marker, in player order
+	markers: Vec<TrackMarker>,
+	/// index of the player at this computer, whose marker is outlined
+	viewing: usize,
+	/// points about to be spent, from the number before to the number after, shown as a highlighted stretch of the track
+	pending: Option<(usize,usize)>,
+	/// whether markers are sliding along the track right now
+	animating: bool,
+	/// whether markers slide to their new spot at all, instead of jumping there
+	animated: bool,
+	/// whether each marker shows its player's symbol, so markers can be told apart without relying on color
+	symbols: bool,
+}//end struct TrackView
+
+/// # PointTrack
+///
+/// Draws a numbered track like the ones on the physical board, with a marker for each player showing how many points they have.
+///
+/// The track can't be typed into, and markers slide to their new spot when points are gained or spent.
+pub struct PointTrack {
+	/// the widget the track is drawn in
+	widget: Widget,
+	/// what's being drawn
+	view: Rc<RefCell<TrackView>>,
+}//end struct PointTrack
+
+#[allow(dead_code)]
+impl PointTrack {
+	/// # new(title, max)
+	///
+	/// Creates a track numbered from 0 to max, with no markers on it yet.
+	pub fn new(title:&str, max:usize) -> PointTrack {
+		let view = Rc::new(RefCell::new(TrackView {
+			title: title.to_string(),
+			max,
+			markers: Vec::new(),
+			viewing: 0,
+			pending: None,
+			animating: false,
+			animated: true,
+			symbols: false,
+		}));//end struct construction
+		let mut widget = Widget::default();
+		widget.set_frame(FrameType::RoundedBox);
+		widget.set_color(Color::from_rgb(56, 118, 29));
+		let draw_view = view.clone();
+		widget.draw(move |widget| draw_track(widget, &draw_view.borrow()));
+		PointTrack {
+			widget,
+			view,
+		}//end struct construction
+	}//end new(title, max)
+
+	/// # set_max(&mut self, max)
+	///
+	/// Changes the highest number on the track.
+	pub fn set_max(&mut self, max:usize) {
+		if self.view.borrow().max != max {
+			self.view.borrow_mut().max = max;
+			self.widget.redraw();
+		}//end if track length changed
+	}//end set_max(&mut self, max)
+
+	/// # set_markers(&mut self, markers, viewing)
+	///
+	/// Puts a marker on the track for each player, given as their color and points. Viewing is the index of the player at this computer.
+	///
+	/// Markers which moved slide over to their new spot, unless the number of players changed.
+	pub fn set_markers(&mut self, markers:&[((u8,u8,u8), usize)], viewing:usize) {
+		let mut view = self.view.borrow_mut();
+		view.viewing = viewing;
+		if view.markers.len() != markers.len() {
+			view.markers = markers.iter().map(|(color, value)| TrackMarker {color: Color::from_rgb(color.0, color.1, color.2), value: *value, shown: *value as f64}).collect();
+		}//end if these are new players
+		else {
+			for (marker, (color, value)) in view.markers.iter_mut().zip(markers) {
+				marker.color = Color::from_rgb(color.0, color.1, color.2);
+				marker.value = *value;
+			}//end updating each marker
+		}//end else players are the same
+		if !view.animated {
+			for marker in view.markers.iter_mut() {
+				marker.shown = marker.value as f64;
+			}//end jumping each marker to its spot
+		}//end if markers shouldn't slide
+		let start_animating = !view.animating && view.markers.iter().any(|marker| marker.shown != marker.value as f64);
+		view.animating |= start_animating;
+		drop(view);
+		if start_animating {
+			self.animate();
+		}//end if a marker needs to move
+		self.widget.redraw();
+	}//end set_markers(&mut self, markers, viewing)
+
+	/// # set_pending(&mut self, pending)
+	///
+	/// Highlights the points the player at this computer is about to spend, going from the number before to the number after. None clears the highlight.
+	pub fn set_pending(&mut self, pending:Option<(usize,usize)>) {
+		if self.view.borrow().pending != pending {
+			self.view.borrow_mut().pending = pending;
+			self.widget.redraw();
+		}//end if highlight changed
+	}//end set_pending(&mut self, pending)
+
+	/// # set_animated(&mut self, animated)
+	///
+	/// Sets whether markers slide to their new spot, or jump straight there.
+	pub fn set_animated(&mut self, animated:bool) {
+		self.view.borrow_mut().animated = animated;
+	}//end set_animated(&mut self, animated)
+
+	/// # set_symbols(&mut self, symbols)
+	///
+	/// Sets whether each marker shows its player's symbol, the same one drawn on their trees.
+	pub fn set_symbols(&mut self, symbols:bool) {
+		self.view.borrow_mut().symbols = symbols;
+		self.widget.redraw();
+	}//end set_symbols(&mut self, symbols)
+
+	/// # animate(&mut self)
+	///
+	/// Slides each marker a little closer to where it belongs every frame, until they've all arrived.
+	fn animate(&mut self) {
+		let view = self.view.clone();
+		let mut widget = self.widget.clone();
+		app::add_timeout3(get_animation_frame_time(), move |handle| {
+			let mut view = view.borrow_mut();
+			for marker in view.markers.iter_mut() {
+				let distance = marker.value as f64 - marker.shown;
+				let step = (distance.abs() / get_animation_frames()).max(0.05);
+				marker.shown = if distance.abs() <= step {marker.value as f64} else {marker.shown + step * distance.signum()};
+			}//end moving each marker
+			view.animating = view.markers.iter().any(|marker| marker.shown != marker.value as f64);
+			if view.animating {
+				app::repeat_timeout3(get_animation_frame_time(), handle);
+			}//end if markers still need to move
+			widget.redraw();
+		});
+	}//end animate(&mut self)
+}//end impl for PointTrack
+
+widget_extends!(PointTrack, Widget, widget);
+
+/// # draw_track(widget, view)
+///
+/// Paints the title, a numbered box for each spot on the track, the highlight for pending points, and then the markers on top.
+fn draw_track(widget:&Widget, view:&TrackView) {
+	draw::draw_box(widget.frame(), widget.x(), widget.y(), widget.width(), widget.height(), widget.color());
+	let font_size = (widget.height() / 2).clamp(8, 14);
+	draw::set_font(Font::HelveticaBold, font_size);
+	let title_w = draw::width(&view.title) as i32 + 12;
+	draw::set_draw_color(Color::White);
+	draw::draw_text2(&view.title, widget.x(), widget.y(), title_w, widget.height(), Align::Center);
+
+	// spots go after the title, one for each number from 0 to max
+	let spots = view.max as i32 + 1;
+	let spot_w = ((widget.width() - title_w - 4) / spots).max(1);
+	let spot_x = |value: f64| widget.x() + title_w + (value * spot_w as f64).round() as i32;
+	let (top, height) = (widget.y() + 2, widget.height() - 4);
+	if let Some((before, after)) = view.pending {
+		let (low, high) = (before.min(after), before.max(after));
+		draw::draw_rect_fill(spot_x(low as f64), top, spot_x(high as f64 + 1.0) - spot_x(low as f64), height, Color::from_rgb(241, 196, 15));
+	}//end if points are about to be spent
+	draw::set_font(Font::Helvetica, font_size - 2);
+	for value in 0..spots {
+		let x = spot_x(value as f64);
+		draw::draw_rect_with_color(x, top, spot_w, height, Color::from_rgb(39, 78, 19));
+		if spot_w >= 2 * font_size {
+			draw::set_draw_color(Color::from_rgb(147, 196, 125));
+			draw::draw_text2(&format!("{}", value), x, top, spot_w, height, Align::Center);
+		}//end if there's room for the number
+	}//end drawing each spot
+
+	// markers which share a spot are spread out a little so each can be seen
+	let radius = (spot_w.min(height) / 2 - 1).max(2);
+	for (index, marker) in view.markers.iter().enumerate() {
+		let offset = (index as i32 - view.markers.len() as i32 / 2) * radius / 2;
+		let center_x = spot_x(marker.shown.min(view.max as f64)) + spot_w / 2;
+		let center_y = top + height / 2 + offset;
+		draw::set_draw_color(marker.color);
+		draw::draw_pie(center_x - radius, center_y - radius, 2 * radius, 2 * radius, 0.0, 360.0);
+		draw::set_draw_color(if index == view.viewing {Color::White} else {Color::Black});
+		draw::draw_arc(center_x - radius, center_y - radius, 2 * radius, 2 * radius, 0.0, 360.0);
+		if view.symbols {
+			draw_owner_symbol(index, center_x, center_y, radius / 2, text_color_on(marker.color));
+		}//end if marker should show its player's symbol
+	}//end drawing each marker
+}//end draw_track(widget, view)
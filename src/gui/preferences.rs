@@ -0,0 +1,312 @@
+use std::{env, fs, path::PathBuf};
+use fltk::{app, button::{Button, CheckButton}, enums::{Align, Color, Font}, frame::Frame, menu::Choice, prelude::{GroupExt, MenuExt, WidgetBase, WidgetExt, WindowExt}, window::Window};
+use fltk_theme::{ColorTheme, SchemeType, WidgetScheme, color_themes, widget_themes};
+use crate::game::{Difficulty, rules::{TomlValue, parse_toml, toml_text}};
+use super::palette::BoardPalette;
+
+/// name of the folder inside the config directory that the preferences file goes in
+const CONFIG_FOLDER: &str = "photosynthesis-fltk-rs";
+/// name of the preferences file
+const CONFIG_FILE: &str = "preferences.toml";
+/// widget schemes which can be chosen, by the name they're saved with
+const SCHEME_NAMES: [&str; 6] = ["Aqua", "Clean", "Crystal", "Fluent", "Gleam", "SvgBased"];
+/// color palettes which can be chosen, by the name they're saved with. Default leaves FLTK's own colors alone.
+const COLOR_THEME_NAMES: [&str; 6] = ["Default", "Black", "Dark", "Gray", "Shake", "Tan"];
+/// every key this version reads from the preferences file, by section and name
+const PREFERENCE_KEYS: [&str; 8] = ["appearance.scheme", "appearance.color_theme", "appearance.animated", "appearance.board_palette", "appearance.symbols", "window.geometry", "defaults.player_count", "defaults.difficulty"];
+
+/// # PrefsMsg
+///
+/// Messages sent by the widgets in the preferences dialog.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum PrefsMsg {
+	Save,
+	Cancel,
+}//end enum PrefsMsg
+
+/// # Preferences
+///
+/// Settings which are kept between games, in a file in the user's config directory.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Preferences {
+	/// name of the fltk_theme widget scheme, from SCHEME_NAMES
+	pub scheme: String,
+	/// name of the fltk_theme color palette, from COLOR_THEME_NAMES
+	pub color_theme: String,
+	/// x, y, width and height of the main window when it was last closed, if it's been closed before
+	pub window: Option<(i32,i32,i32,i32)>,
+	/// whether the sun, moon, shadows and point markers move smoothly
+	pub animated: bool,
+	/// colors used for how fertile each spot on the board is
+	pub board_palette: BoardPalette,
+	/// whether the board shows leaf counts, hatched shade and owner symbols, so it can be read without telling colors apart
+	pub symbols: bool,
+	/// number of players the setup wizard starts with
+	pub player_count: usize,
+	/// how well computer players play, unless changed in the setup wizard
+	pub difficulty: Difficulty,
+}//end struct Preferences
+
+impl Default for Preferences {
+	fn default() -> Self {
+		Preferences {
+			scheme: "Aqua".to_string(),
+			color_theme: "Default".to_string(),
+			window: None,
+			animated: true,
+			board_palette: BoardPalette::Classic,
+			symbols: false,
+			player_count: 2,
+			difficulty: Difficulty::Normal,
+		}//end struct construction
+	}//end default()
+}//end impl Default for Preferences
+
+#[allow(dead_code)]
+impl Preferences {
+	/// # config_path()
+	///
+	/// Returns where the preferences file is kept, following the XDG base directory spec.
+	/// That's $XDG_CONFIG_HOME, or ~/.config if it isn't set, or %APPDATA% on Windows.
+	///
+	/// Returns None if there's no home directory to put it in.
+	pub fn config_path() -> Option<PathBuf> {
+		let config_dir = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+			Some(dir) => PathBuf::from(dir),
+			None => match (env::var_os("APPDATA"), env::var_os("HOME")) {
+				(Some(dir), _) if cfg!(windows) => PathBuf::from(dir),
+				(_, Some(home)) => PathBuf::from(home).join(".config"),
+				_ => return None,
+			},
+		};//end matching where config files go
+		return Some(config_dir.join(CONFIG_FOLDER).join(CONFIG_FILE));
+	}//end config_path()
+
+	/// # load()
+	///
+	/// Reads the preferences file. If there isn't one yet, or it can't be read, the defaults are used instead.
+	pub fn load() -> Preferences {
+		let path = match Preferences::config_path() {
+			Some(path) => path,
+			None => return Preferences::default(),
+		};//end matching whether there's anywhere to look
+		let text = match fs::read_to_string(&path) {
+			Ok(text) => text,
+			Err(_) => return Preferences::default(),
+		};//end matching whether the file is there
+		return match Preferences::from_toml(&text) {
+			Ok(prefs) => prefs,
+			Err(msg) => {
+				println!("Couldn't read preferences from {}, so the defaults are being used. Details:\n{}", path.display(), msg);
+				Preferences::default()
+			},
+		};//end matching whether the file made sense
+	}//end load()
+
+	/// # save(&self)
+	///
+	/// Writes these preferences to the preferences file, creating its folder if needed.
+	pub fn save(&self) -> Result<(), String> {
+		let path = Preferences::config_path().ok_or("Couldn't find a config directory to save preferences in.".to_string())?;
+		if let Some(folder) = path.parent() {
+			fs::create_dir_all(folder).map_err(|e| format!("Couldn't create {}. Details:\n{}", folder.display(), e))?;
+		}//end if the file is in a folder
+		return fs::write(&path, self.to_toml()).map_err(|e| format!("Couldn't save preferences to {}. Details:\n{}", path.display(), e));
+	}//end save(&self)
+
+	/// # from_toml(text)
+	///
+	/// Reads preferences from the text of a preferences file, using the defaults for anything it doesn't list.
+	///
+	/// Keys this version doesn't know about are skipped, so a file written by a newer version can still be read.
+	pub fn from_toml(text: &str) -> Result<Preferences, String> {
+		let mut prefs = Preferences::default();
+		for (key, value) in parse_toml(text)? {
+			let name = |names: &[&str]| match &value {
+				TomlValue::Text(text) => names.iter().find(|name| name.eq_ignore_ascii_case(text)).map(|name| name.to_string())
+					.ok_or(format!("{} should be one of: {}", key, names.join(", "))),
+				_ => Err(format!("{} should be some text in quotes.", key)),
+			};//end name closure
+			match (key.as_str(), &value) {
+				("appearance.scheme", _) => prefs.scheme = name(&SCHEME_NAMES)?,
+				("appearance.color_theme", _) => prefs.color_theme = name(&COLOR_THEME_NAMES)?,
+				("appearance.animated", TomlValue::Boolean(animated)) => prefs.animated = *animated,
+				("appearance.board_palette", TomlValue::Text(text)) => prefs.board_palette = BoardPalette::from_name(text)
+					.ok_or(format!("{} should be one of: {}", key, BoardPalette::all().map(|palette| palette.to_string()).join(", ")))?,
+				("appearance.symbols", TomlValue::Boolean(symbols)) => prefs.symbols = *symbols,
+				("window.geometry", TomlValue::Array(items)) => {
+					let numbers: Vec<i32> = items.iter().filter_map(|item| match item {TomlValue::Integer(n) => i32::try_from(*n).ok(), _ => None}).collect();
+					match numbers.as_slice() {
+						[x, y, w, h] => prefs.window = Some((*x, *y, *w, *h)),
+						_ => return Err(format!("{} should be a list of 4 whole numbers: x, y, width and height.", key)),
+					}//end matching whether there are four numbers
+				},
+				("defaults.player_count", TomlValue::Integer(count)) => prefs.player_count = (*count).clamp(2, 4) as usize,
+				("defaults.difficulty", TomlValue::Text(text)) => prefs.difficulty = Difficulty::from_name(text).ok_or(format!("{} should be Easy, Normal or Hard.", key))?,
+				(known, _) if PREFERENCE_KEYS.contains(&known) => return Err(format!("{} = {} isn't the right kind of value for that preference.", key, toml_text(&value))),
+				_ => {},
+			}//end matching key
+		}//end reading each entry
+		return Ok(prefs);
+	}//end from_toml(text)
+
+	/// # to_toml(&self)
+	///
+	/// Writes these preferences as the text of a preferences file.
+	pub fn to_toml(&self) -> String {
+		let mut text = String::new();
+		text += "[appearance]\n";
+		text += &format!("scheme = \"{}\"\n", self.scheme);
+		text += &format!("color_theme = \"{}\"\n", self.color_theme);
+		text += &format!("animated = {}\n", self.animated);
+		text += &format!("board_palette = \"{}\"\n", self.board_palette);
+		text += &format!("symbols = {}\n", self.symbols);
+		if let Some((x, y, w, h)) = self.window {
+			text += &format!("\n[window]\ngeometry = [{}, {}, {}, {}]\n", x, y, w, h);
+		}//end if there's a window size to remember
+		text += "\n[defaults]\n";
+		text += &format!("player_count = {}\n", self.player_count);
+		text += &format!("difficulty = \"{}\"\n", self.difficulty);
+		return text;
+	}//end to_toml(&self)
+
+	/// # apply_appearance(&self)
+	///
+	/// Switches every widget over to the chosen scheme and color palette.
+	pub fn apply_appearance(&self) {
+		let scheme = match self.scheme.as_str() {
+			"Clean" => SchemeType::Clean,
+			"Crystal" => SchemeType::Crystal,
+			"Fluent" => SchemeType::Fluent,
+			"Gleam" => SchemeType::Gleam,
+			"SvgBased" => SchemeType::SvgBased,
+			_ => SchemeType::Aqua,
+		};//end matching scheme name
+		WidgetScheme::new(scheme).apply();
+		let palette = match self.color_theme.as_str() {
+			"Black" => Some(color_themes::BLACK_THEME),
+			"Dark" => Some(color_themes::DARK_THEME),
+			"Gray" => Some(color_themes::GRAY_THEME),
+			"Shake" => Some(color_themes::SHAKE_THEME),
+			"Tan" => Some(color_themes::TAN_THEME),
+			_ => None,
+		};//end matching palette name
+		match palette {
+			Some(palette) => ColorTheme::new(palette).apply(),
+			None => app::redraw(),
+		}//end matching whether there's a palette to apply
+	}//end apply_appearance(&self)
+}//end impl for Preferences
+
+/// # run_preferences_dialog(prefs)
+///
+/// Shows a dialog for changing preferences, starting from the ones given. This waits until the dialog is closed.
+///
+/// Switching back to the Default palette only takes full effect after restarting, since FLTK's own colors aren't kept once they've been replaced.
+///
+/// ## return
+/// Returns the changed preferences, or None if the dialog was cancelled.
+pub fn run_preferences_dialog(prefs: &Preferences) -> Option<Preferences> {
+	let (sender, receiver) = app::channel::<PrefsMsg>();
+	let mut window = Window::default().with_size(380, 370).with_label("Preferences");
+	window.set_color(Color::from_rgb(101, 68, 24));
+	window.make_modal(true);
+
+	let mut title = Frame::new(20, 10, 340, 40, "Preferences");
+	title.set_label_size(24);
+	title.set_label_color(Color::White);
+	title.set_label_font(Font::HelveticaBold);
+
+	let choice = |y: i32, label: &str, options: &[String], selected: usize| {
+		let mut choice = Choice::new(170, y, 150, 25, None);
+		let mut label = Frame::new(20, y, 140, 25, None).with_label(label).with_align(Align::Right | Align::Inside);
+		label.set_label_color(Color::White);
+		for option in options {
+			choice.add_choice(option);
+		}//end adding each option
+		choice.set_value(selected as i32);
+		choice
+	};//end choice closure
+	let to_strings = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+	let scheme_choice = choice(60, "Widget style", &to_strings(&SCHEME_NAMES), SCHEME_NAMES.iter().position(|name| *name == prefs.scheme).unwrap_or(0));
+	let theme_choice = choice(95, "Color palette", &to_strings(&COLOR_THEME_NAMES), COLOR_THEME_NAMES.iter().position(|name| *name == prefs.color_theme).unwrap_or(0));
+	let count_choice = choice(130, "Players", &to_strings(&["2", "3", "4"]), prefs.player_count.clamp(2, 4) - 2);
+	let difficulties: Vec<String> = Difficulty::all().iter().map(|difficulty| difficulty.to_string()).collect();
+	let difficulty_choice = choice(165, "Computer skill", &difficulties, Difficulty::all().iter().position(|difficulty| *difficulty == prefs.difficulty).unwrap_or(1));
+	let palettes: Vec<String> = BoardPalette::all().iter().map(|palette| palette.to_string()).collect();
+	let palette_choice = choice(200, "Board colors", &palettes, BoardPalette::all().iter().position(|palette| *palette == prefs.board_palette).unwrap_or(0));
+	let mut animated_check = CheckButton::new(170, 235, 150, 25, "Animations");
+	animated_check.set_label_color(Color::White);
+	animated_check.set_checked(prefs.animated);
+	let mut symbols_check = CheckButton::new(170, 265, 190, 25, "Board symbols");
+	symbols_check.set_label_color(Color::White);
+	symbols_check.set_checked(prefs.symbols);
+	symbols_check.set_tooltip("Show leaf counts on each spot, hatch spots in shade, and mark trees with their owner's shape.");
+
+	let mut cancel = Button::new(140, 320, 100, 30, "Cancel");
+	cancel.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+	cancel.set_color(Color::from_rgb(184,68,68));
+	cancel.set_label_color(Color::White);
+	cancel.emit(sender, PrefsMsg::Cancel);
+	let mut save = Button::new(260, 320, 100, 30, "Save");
+	save.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+	save.set_color(Color::from_rgb(68,140,184));
+	save.set_label_color(Color::White);
+	save.emit(sender, PrefsMsg::Save);
+	window.set_callback(move |_| sender.send(PrefsMsg::Cancel));
+
+	window.end();
+	window.show();
+	let mut changed = None;
+	while window.shown() && app::wait() {
+		match receiver.recv() {
+			Some(PrefsMsg::Save) => {
+				changed = Some(Preferences {
+					scheme: SCHEME_NAMES[scheme_choice.value().max(0) as usize].to_string(),
+					color_theme: COLOR_THEME_NAMES[theme_choice.value().max(0) as usize].to_string(),
+					window: prefs.window,
+					animated: animated_check.is_checked(),
+					board_palette: BoardPalette::all()[palette_choice.value().max(0) as usize],
+					symbols: symbols_check.is_checked(),
+					player_count: count_choice.value().max(0) as usize + 2,
+					difficulty: Difficulty::all()[difficulty_choice.value().max(0) as usize],
+				});//end struct construction
+				window.hide();
+			},
+			Some(PrefsMsg::Cancel) => window.hide(),
+			None => {},
+		}//end matching message
+	}//end waiting for the dialog to close
+	Window::delete(window);
+	return changed;
+}//end run_preferences_dialog(prefs)
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn preferences_survive_a_trip_through_a_file() {
+		let prefs = Preferences {
+			scheme: "Gleam".to_string(),
+			color_theme: "Dark".to_string(),
+			window: Some((-1200, -8, 900, 700)),
+			animated: false,
+			board_palette: BoardPalette::Cividis,
+			symbols: true,
+			player_count: 3,
+			difficulty: Difficulty::Hard,
+		};//end struct construction
+		assert_eq!(Preferences::from_toml(&prefs.to_toml()).unwrap(), prefs, "{}", prefs.to_toml());
+		assert_eq!(Preferences::from_toml(&Preferences::default().to_toml()).unwrap(), Preferences::default());
+	}//end preferences_survive_a_trip_through_a_file()
+
+	#[test]
+	fn unknown_preferences_are_skipped() {
+		let text = "[appearance]\nscheme = \"Fluent\"\nsparkles = true\n\n[sound]\nvolume = 11\n";
+		let prefs = Preferences::from_toml(text).unwrap();
+		assert_eq!(prefs.scheme, "Fluent");
+		assert_eq!(prefs.animated, Preferences::default().animated);
+		assert!(Preferences::from_toml("[appearance]\nanimated = 3").is_err());
+	}//end unknown_preferences_are_skipped()
+}//end mod tests
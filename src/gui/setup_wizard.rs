@@ -0,0 +1,238 @@
+use fltk::{app, button::{Button, CheckButton}, dialog::{self, NativeFileChooser, NativeFileChooserType}, enums::{Align, Color, Font, Shortcut}, menu::{Choice, MenuFlag}, frame::Frame, input::Input, prelude::{GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt}, window::Window};
+use fltk_theme::widget_themes;
+use crate::game::{Difficulty, RuleSet, default_player_color};
+
+/// most players a game can have
+const MAX_SEATS: usize = 4;
+/// board sizes offered, as the number of spots along each side
+const BOARD_SIZES: [usize; 3] = [5, 7, 9];
+
+/// # WizardMsg
+///
+/// Messages sent by the widgets in the setup wizard.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum WizardMsg {
+	PlayerCount,
+	Rules,
+	Start,
+	Cancel,
+}//end enum WizardMsg
+
+/// # SeatSetup
+///
+/// Who is sitting in one seat of a new game.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SeatSetup {
+	/// name shown for this player
+	pub name: String,
+	/// color of this player's trees
+	pub color: (u8,u8,u8),
+	/// whether the computer plays this seat
+	pub computer: bool,
+}//end struct SeatSetup
+
+/// # GameSetup
+///
+/// Everything chosen in the setup wizard, which is enough to start a new game.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GameSetup {
+	/// each player, in turn order
+	pub seats: Vec<SeatSetup>,
+	/// the rules to play with, including the size of the board
+	pub rules: RuleSet,
+	/// how well the computer plays its seats
+	pub difficulty: Difficulty,
+}//end struct GameSetup
+
+/// # player_colors()
+///
+/// Returns the colors players can choose from, along with a name for each. The first four are the default colors, in seat order.
+/// The last few are from the Okabe-Ito palette, which players with color blindness can still tell apart.
+fn player_colors() -> Vec<(&'static str, (u8,u8,u8))> {
+	return vec![
+		("Orange", default_player_color(0)),
+		("Yellow", default_player_color(1)),
+		("Blue", default_player_color(2)),
+		("Purple", default_player_color(3)),
+		("Red", (231,76,60)),
+		("White", (236,240,241)),
+		("Pink", (255,121,198)),
+		("Vermilion", (213,94,0)),
+		("Sky Blue", (86,180,233)),
+		("Bluish Green", (0,158,115)),
+		("Reddish Purple", (204,121,167)),
+	];
+}//end player_colors()
+
+/// # run_setup_wizard(rules, player_count, difficulty)
+///
+/// Shows a dialog for choosing the players, their colors, whether the computer plays each seat, how well it plays, the rules and the size of the board.
+/// This waits until the dialog is closed.
+///
+/// ## parameters
+/// rules : rules to start with, such as ones given on the command line
+/// player_count : number of players to start with
+/// difficulty : how well the computer plays, to start with
+///
+/// ## return
+/// Returns None if the dialog was closed without starting a game.
+pub fn run_setup_wizard(rules: RuleSet, player_count: usize, difficulty: Difficulty) -> Option<GameSetup> {
+	let (sender, receiver) = app::channel::<WizardMsg>();
+	let colors = player_colors();
+	// rules offered in the rules menu, with whatever we were given first
+	let mut rule_options: Vec<RuleSet> = vec![rules.clone()];
+	for name in RuleSet::preset_names() {
+		let preset = RuleSet::preset(name).unwrap();
+		if preset != rules {
+			rule_options.push(preset);
+		}//end if this preset isn't already the first option
+	}//end adding each preset
+
+	let mut window = Window::default().with_size(520, 390).with_label("New Game");
+	window.set_color(Color::from_rgb(101, 68, 24));
+	window.make_modal(true);
+
+	let mut title = Frame::new(20, 10, 480, 40, "Set up a new game");
+	title.set_label_size(24);
+	title.set_label_color(Color::White);
+	title.set_label_font(Font::HelveticaBold);
+
+	let mut count_choice = Choice::new(140, 60, 80, 25, "Players");
+	count_choice.add_choice("2|3|4");
+	let player_count = player_count.clamp(2, MAX_SEATS);
+	count_choice.set_value(player_count as i32 - 2);
+	count_choice.emit(sender, WizardMsg::PlayerCount);
+
+	// one row for each seat
+	let mut names = Vec::new();
+	let mut color_choices = Vec::new();
+	let mut computer_checks = Vec::new();
+	for seat in 0..MAX_SEATS {
+		let y = 100 + seat as i32 * 35;
+		let mut name = Input::new(140, y, 170, 25, None);
+		name.set_value(&format!("Player {}", seat + 1));
+		let mut label = Frame::new(20, y, 110, 25, None).with_label(&format!("Seat {}", seat + 1)).with_align(Align::Right | Align::Inside);
+		label.set_label_color(Color::White);
+		let mut color_choice = Choice::new(320, y, 90, 25, None);
+		for (color_name, _) in &colors {
+			color_choice.add_choice(color_name);
+		}//end adding each color
+		color_choice.set_value(seat as i32);
+		let mut computer = CheckButton::new(420, y, 90, 25, "Computer");
+		computer.set_label_color(Color::White);
+		names.push(name);
+		color_choices.push(color_choice);
+		computer_checks.push(computer);
+	}//end adding a row for each seat
+
+	let mut rules_choice = Choice::new(140, 250, 170, 25, "Rules");
+	for option in &rule_options {
+		rules_choice.add_choice(&option.name.replace('/', "\\/"));
+	}//end adding each set of rules
+	rules_choice.add_choice("Open rules file...");
+	rules_choice.set_value(0);
+	rules_choice.emit(sender, WizardMsg::Rules);
+
+	let mut size_choice = Choice::new(140, 285, 90, 25, "Board size");
+	let mut sizes = BOARD_SIZES.to_vec();
+	if !sizes.contains(&rules.board_size) {
+		sizes.push(rules.board_size);
+	}//end if the rules ask for a size we don't usually offer
+	for size in &sizes {
+		size_choice.add_choice(&format!("{}x{}", size, size));
+	}//end adding each size
+	size_choice.set_value(sizes.iter().position(|size| *size == rules.board_size).unwrap_or(1) as i32);
+	let mut difficulty_choice = Choice::new(410, 285, 90, 25, "Computer skill");
+	for option in Difficulty::all() {
+		difficulty_choice.add_choice(&option.to_string());
+	}//end adding each difficulty
+	difficulty_choice.set_value(Difficulty::all().iter().position(|option| *option == difficulty).unwrap_or(1) as i32);
+	for choice in [&mut count_choice, &mut rules_choice, &mut size_choice, &mut difficulty_choice] {
+		choice.set_label_color(Color::White);
+	}//end making labels readable
+
+	let mut cancel = Button::new(280, 340, 100, 30, "Quit");
+	cancel.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+	cancel.set_color(Color::from_rgb(184,68,68));
+	cancel.set_label_color(Color::White);
+	cancel.emit(sender, WizardMsg::Cancel);
+	let mut start = Button::new(400, 340, 100, 30, "Start");
+	start.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+	start.set_color(Color::from_rgb(68,140,184));
+	start.set_label_color(Color::White);
+	start.emit(sender, WizardMsg::Start);
+	window.set_callback(move |_| sender.send(WizardMsg::Cancel));
+
+	window.end();
+	window.show();
+	let show_seats = |count: usize, names: &mut Vec<Input>, color_choices: &mut Vec<Choice>, computer_checks: &mut Vec<CheckButton>| {
+		for seat in 0..MAX_SEATS {
+			if seat < count {
+				names[seat].activate();
+				color_choices[seat].activate();
+				computer_checks[seat].activate();
+			}//end if this seat is being played
+			else {
+				names[seat].deactivate();
+				color_choices[seat].deactivate();
+				computer_checks[seat].deactivate();
+			}//end else this seat is empty
+		}//end looping over each seat
+	};//end show_seats closure
+	show_seats(player_count, &mut names, &mut color_choices, &mut computer_checks);
+
+	let mut setup = None;
+	while window.shown() && app::wait() {
+		match receiver.recv() {
+			Some(WizardMsg::PlayerCount) => show_seats(count_choice.value() as usize + 2, &mut names, &mut color_choices, &mut computer_checks),
+			Some(WizardMsg::Rules) if rules_choice.value() as usize == rule_options.len() => {
+				// the last option opens a rules file, which is then added to the menu
+				let mut chooser = NativeFileChooser::new(NativeFileChooserType::BrowseFile);
+				chooser.set_filter("*.toml");
+				chooser.show();
+				let path = chooser.filename();
+				let loaded = if path.as_os_str().is_empty() {None} else {Some(RuleSet::load(&path.to_string_lossy()))};
+				match loaded {
+					Some(Ok(loaded)) => {
+						rules_choice.insert(rule_options.len() as i32, &loaded.name.replace('/', "\\/"), Shortcut::None, MenuFlag::Normal, |_| {});
+						rule_options.push(loaded);
+						rules_choice.set_value(rule_options.len() as i32 - 1);
+					},
+					Some(Err(msg)) => {
+						dialog::alert_default(&msg);
+						rules_choice.set_value(0);
+					},
+					None => {
+						rules_choice.set_value(0);
+					},
+				}//end matching whether we loaded a file
+			},
+			Some(WizardMsg::Rules) => {},
+			Some(WizardMsg::Start) => {
+				let count = count_choice.value() as usize + 2;
+				let seats: Vec<SeatSetup> = (0..count).map(|seat| SeatSetup {
+					name: names[seat].value().trim().to_string(),
+					color: colors[color_choices[seat].value().max(0) as usize].1,
+					computer: computer_checks[seat].is_checked(),
+				}).collect();
+				if seats.iter().any(|seat| seat.name.is_empty()) {
+					dialog::alert_default("Every player needs a name.");
+					continue;
+				}//end if a name is missing
+				if (1..count).any(|seat| seats[..seat].iter().any(|other| other.color == seats[seat].color)) {
+					dialog::alert_default("Each player needs their own color, since trees are matched to players by color.");
+					continue;
+				}//end if two players share a color
+				let mut rules = rule_options[rules_choice.value().max(0) as usize].clone();
+				rules.board_size = sizes[size_choice.value().max(0) as usize];
+				let difficulty = Difficulty::all()[difficulty_choice.value().max(0) as usize];
+				setup = Some(GameSetup {seats, rules, difficulty});
+				window.hide();
+			},
+			Some(WizardMsg::Cancel) => window.hide(),
+			None => {},
+		}//end matching message
+	}//end waiting for the dialog to close
+	Window::delete(window);
+	return setup;
+}//end run_setup_wizard(rules, player_count, difficulty)
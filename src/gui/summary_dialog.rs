@@ -0,0 +1,235 @@
+use fltk::{app, button::Button, dialog::{self, NativeFileChooser, NativeFileChooserType}, draw, enums::{Align, Color, Font, FrameType}, frame::Frame, prelude::{GroupExt, WidgetBase, WidgetExt, WindowExt}, widget::Widget, window::Window};
+use fltk_theme::widget_themes;
+use crate::game::{Fertility, GameSummary, Replay};
+use super::palette::{BoardPalette, draw_owner_symbol, text_color_on};
+
+/// headings of the columns in the results table, after the player's name
+const COLUMN_NAMES: [&str; 8] = ["One-Leaf", "Two-Leaf", "Three-Leaf", "Four-Leaf", "Lunar", "Light", "Trees", "Total"];
+
+/// # SummaryMsg
+///
+/// Messages sent by the buttons in the results dialog.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum SummaryMsg {
+	SaveReplay,
+	NewGame,
+	Close,
+}//end enum SummaryMsg
+
+/// # SummaryChoice
+///
+/// What the players chose to do once they were done looking at the results.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SummaryChoice {
+	/// close the results and keep looking at the final board
+	Close,
+	/// start another game with the same players and rules
+	NewGame,
+}//end enum SummaryChoice
+
+/// # run_summary_dialog(summary, replay, allow_new_game, palette, symbols)
+///
+/// Shows the results of a game, with a table breaking down each player's score and a chart of the light each player gained every round.
+/// This waits until the dialog is closed.
+///
+/// ## parameters
+/// summary : results of the game
+/// replay : replay of the game, which can be saved from the dialog
+/// allow_new_game : whether to offer starting a new game with the same settings, which is only possible when playing on one computer
+/// palette : colors the board is drawn in, which the token columns are tinted to match
+/// symbols : whether to mark each player with their symbol from the board, as well as their color
+pub fn run_summary_dialog(summary: &GameSummary, replay: &Replay, allow_new_game: bool, palette: BoardPalette, symbols: bool) -> SummaryChoice {
+	let (sender, receiver) = app::channel::<SummaryMsg>();
+	let rows = summary.players.len() as i32;
+
+	let mut window = Window::default().with_size(720, 250 + rows * 28 + 200).with_label("Results");
+	window.set_color(Color::from_rgb(101, 68, 24));
+	window.make_modal(true);
+
+	let mut title = Frame::new(20, 10, 680, 40, None).with_label(&winner_text(summary));
+	title.set_label_size(24);
+	title.set_label_color(Color::White);
+	title.set_label_font(Font::HelveticaBold);
+
+	let mut table = Widget::new(20, 60, 680, 30 + rows * 28, None);
+	let table_summary = summary.clone();
+	table.draw(move |widget| draw_table(widget, &table_summary, palette, symbols));
+
+	let mut notes = Frame::new(20, 95 + rows * 28, 680, 40, None).with_label(&notes_text(summary)).with_align(Align::Left | Align::Inside | Align::Wrap);
+	notes.set_label_color(Color::White);
+	notes.set_label_size(12);
+
+	let mut chart = Widget::new(20, 140 + rows * 28, 680, 240, None);
+	chart.set_frame(FrameType::RoundedBox);
+	chart.set_color(Color::from_rgb(56, 118, 29));
+	let chart_summary = summary.clone();
+	chart.draw(move |widget| draw_light_chart(widget, &chart_summary, symbols));
+
+	let button_y = 400 + rows * 28;
+	let mut save = Button::new(20, button_y, 140, 30, "Save Replay...");
+	let mut new_game = Button::new(420, button_y, 160, 30, "New Game (Same Settings)");
+	let mut close = Button::new(600, button_y, 100, 30, "Close");
+	for (button, color, msg) in [(&mut save, Color::from_rgb(68,140,184), SummaryMsg::SaveReplay), (&mut new_game, Color::from_rgb(68,140,184), SummaryMsg::NewGame), (&mut close, Color::from_rgb(184,68,68), SummaryMsg::Close)] {
+		button.set_frame(widget_themes::OS_RADIO_ROUND_DOWN_BOX);
+		button.set_color(color);
+		button.set_label_color(Color::White);
+		button.emit(sender, msg);
+	}//end styling each button
+	if !allow_new_game {
+		new_game.deactivate();
+	}//end if a new game can't be started from here
+	window.set_callback(move |_| sender.send(SummaryMsg::Close));
+
+	window.end();
+	window.show();
+
+	let mut choice = SummaryChoice::Close;
+	while window.shown() && app::wait() {
+		match receiver.recv() {
+			Some(SummaryMsg::SaveReplay) => {
+				let mut chooser = NativeFileChooser::new(NativeFileChooserType::BrowseSaveFile);
+				chooser.set_filter("*.replay");
+				chooser.show();
+				let path = chooser.filename();
+				if !path.as_os_str().is_empty() {
+					match replay.save(&path.to_string_lossy()) {
+						Ok(()) => dialog::message_default("Replay saved."),
+						Err(msg) => dialog::alert_default(&msg),
+					}//end matching whether we could save
+				}//end if user picked a file
+			},
+			Some(SummaryMsg::NewGame) => {
+				choice = SummaryChoice::NewGame;
+				window.hide();
+			},
+			Some(SummaryMsg::Close) => window.hide(),
+			None => {},
+		}//end matching message
+	}//end waiting for the dialog to close
+	Window::delete(window);
+	return choice;
+}//end run_summary_dialog(summary, replay, allow_new_game, palette, symbols)
+
+/// # winner_text(summary)
+///
+/// Returns the headline announcing who won, or who shares the win.
+fn winner_text(summary: &GameSummary) -> String {
+	let names: Vec<&str> = summary.winners().iter().map(|index| summary.players[*index].name.as_str()).collect();
+	return match names.as_slice() {
+		[] => "The game is over!".to_string(),
+		[name] => format!("{} wins!", name),
+		[first @ .., last] => format!("{} and {} share the win!", first.join(", "), last),
+	};//end matching how many players won
+}//end winner_text(summary)
+
+/// # notes_text(summary)
+///
+/// Returns a short explanation of how leftover light and ties were scored.
+fn notes_text(summary: &GameSummary) -> String {
+	let light = match summary.players.iter().find(|player| player.light_left > 0 && player.light_score > 0) {
+		Some(player) => format!("Leftover light is worth points under these rules, such as {} light for {} points.", player.light_left, player.light_score),
+		None => "Leftover light isn't worth any points under these rules.".to_string(),
+	};//end matching whether light scored anything
+	let tied = summary.players.iter().enumerate().any(|(index, player)| summary.players[..index].iter().any(|other| other.total == player.total));
+	let ties = if tied {"Players with the same total are ranked by how many seeds and trees they have on the board."} else {"Nobody tied, so the tiebreak wasn't needed."};
+	return format!("{}\n{}", light, ties);
+}//end notes_text(summary)
+
+/// # draw_table(widget, summary, palette, symbols)
+///
+/// Paints the results table, with a row for each player in turn order and a column for each part of their score.
+fn draw_table(widget: &Widget, summary: &GameSummary, palette: BoardPalette, symbols: bool) {
+	let name_w = 150;
+	let column_w = (widget.width() - name_w) / COLUMN_NAMES.len() as i32;
+	let row_h = 28;
+	draw::draw_rect_fill(widget.x(), widget.y(), widget.width(), widget.height(), Color::from_rgb(56, 118, 29));
+
+	// headings, with the token columns tinted like the spots they come from
+	let fertilities = [Fertility::OneLeaf, Fertility::TwoLeaf, Fertility::ThreeLeaf, Fertility::FourLeaf];
+	draw::set_font(Font::HelveticaBold, 12);
+	for (index, heading) in COLUMN_NAMES.iter().enumerate() {
+		let x = widget.x() + name_w + index as i32 * column_w;
+		if let Some(fertility) = fertilities.get(index) {
+			draw::draw_rect_fill(x, widget.y(), column_w, row_h, palette.fertility_color(*fertility));
+			draw::set_draw_color(text_color_on(palette.fertility_color(*fertility)));
+		}//end if this is a token column
+		else {
+			draw::set_draw_color(Color::White);
+		}//end else heading is on the table background
+		draw::draw_text2(heading, x, widget.y(), column_w, row_h, Align::Center);
+	}//end drawing each heading
+	draw::draw_text2("Player", widget.x() + 8, widget.y(), name_w, row_h, Align::Left);
+
+	for (row, player) in summary.players.iter().enumerate() {
+		let y = widget.y() + (row as i32 + 1) * row_h;
+		draw::draw_rect_fill(widget.x(), y, widget.width(), row_h, if row % 2 == 0 {Color::from_rgb(39, 78, 19)} else {Color::from_rgb(49, 98, 24)});
+		let color = Color::from_rgb(player.color.0, player.color.1, player.color.2);
+		draw::set_draw_color(color);
+		draw::draw_pie(widget.x() + 8, y + 7, 14, 14, 0.0, 360.0);
+		if symbols {
+			draw_owner_symbol(row, widget.x() + 15, y + 14, 4, text_color_on(color));
+		}//end if player should be marked with their symbol
+		draw::set_font(if player.place == 1 {Font::HelveticaBold} else {Font::Helvetica}, 12);
+		draw::set_draw_color(Color::White);
+		draw::draw_text2(&format!("{}. {}", player.place, player.name), widget.x() + 28, y, name_w - 28, row_h, Align::Left | Align::Clip);
+
+		let tokens = player.tokens.iter().map(|values| match values.len() {
+			0 => "-".to_string(),
+			_ => values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join("+"),
+		});
+		let light = format!("{} ({}pt)", player.light_left, player.light_score);
+		let cells: Vec<String> = tokens.chain([player.lunar_points.to_string(), light, player.trees_on_board.to_string(), player.total.to_string()]).collect();
+		for (index, cell) in cells.iter().enumerate() {
+			draw::draw_text2(cell, widget.x() + name_w + index as i32 * column_w, y, column_w, row_h, Align::Center | Align::Clip);
+		}//end drawing each cell
+	}//end drawing a row for each player
+}//end draw_table(widget, summary, palette, symbols)
+
+/// # draw_light_chart(widget, summary, symbols)
+///
+/// Paints a bar chart of the light points each player gained each round, with one bar in each player's color for every round.
+/// With symbols turned on, each bar is topped with its player's symbol when there's room.
+fn draw_light_chart(widget: &Widget, summary: &GameSummary, symbols: bool) {
+	draw::draw_box(widget.frame(), widget.x(), widget.y(), widget.width(), widget.height(), widget.color());
+	draw::set_font(Font::HelveticaBold, 12);
+	draw::set_draw_color(Color::White);
+	draw::draw_text2("Light gained each round", widget.x(), widget.y() + 4, widget.width(), 16, Align::Center);
+	if summary.light_income.is_empty() {
+		draw::draw_text2("No rounds were played.", widget.x(), widget.y(), widget.width(), widget.height(), Align::Center);
+		return;
+	}//end if there's nothing to chart
+
+	let (left, top) = (widget.x() + 30, widget.y() + 26);
+	let (width, height) = (widget.width() - 40, widget.height() - 50);
+	let most = summary.most_light_in_a_round().max(1);
+	let bottom = top + height;
+
+	// a few lines across the chart so bars can be read against the scale
+	draw::set_font(Font::Helvetica, 10);
+	let step = (most / 4).max(1);
+	for value in (0..=most).step_by(step) {
+		let y = bottom - (value * height as usize / most) as i32;
+		draw::set_draw_color(Color::from_rgb(39, 78, 19));
+		draw::draw_line(left, y, left + width, y);
+		draw::set_draw_color(Color::White);
+		draw::draw_text2(&value.to_string(), widget.x() + 2, y - 6, 24, 12, Align::Right);
+	}//end drawing each line of the scale
+
+	let group_w = width / summary.light_income.len() as i32;
+	let bar_w = ((group_w - 4) / summary.players.len().max(1) as i32).max(1);
+	for (group, (round, gained)) in summary.light_income.iter().enumerate() {
+		let group_x = left + group as i32 * group_w;
+		for (player, light) in gained.iter().enumerate() {
+			let bar_h = (*light * height as usize / most) as i32;
+			let color = summary.players.get(player).map(|player| Color::from_rgb(player.color.0, player.color.1, player.color.2)).unwrap_or(Color::White);
+			draw::draw_rect_fill(group_x + 2 + player as i32 * bar_w, bottom - bar_h, bar_w, bar_h, color);
+			if symbols && bar_w >= 8 {
+				draw_owner_symbol(player, group_x + 2 + player as i32 * bar_w + bar_w / 2, bottom - bar_h - bar_w / 2 - 1, (bar_w / 2 - 1).min(5), color);
+			}//end if there's room for the player's symbol
+		}//end drawing a bar for each player
+		if group_w >= 16 {
+			draw::set_draw_color(Color::White);
+			draw::draw_text2(&round.to_string(), group_x, bottom + 2, group_w, 12, Align::Center);
+		}//end if there's room to number the round
+	}//end drawing each round
+}//end draw_light_chart(widget, summary, symbols)
@@ -0,0 +1,174 @@
+use std::{cell::RefCell, rc::Rc};
+use fltk::{app, frame::Frame, image::PngImage, prelude::{ImageExt, WidgetExt}, widget_extends};
+use crate::game::SunDirection;
+use super::gui_utils::{get_animation_frame_time, get_animation_frames};
+
+/// # SunView
+///
+/// Where the sun marker is and where it's going, shared between the marker and its animation.
+#[derive(Debug, PartialEq, Clone)]
+struct SunView {
+	/// top left corner of each place the sun can be, going clockwise from the southwest corner of the board
+	positions: [(i32,i32); 8],
+	/// width and height of the marker
+	size: i32,
+	/// place the sun belongs in, as an index into positions
+	target: usize,
+	/// place the marker is being drawn, which slides toward target after it changes. This can be between two places.
+	shown: f64,
+	/// whether the marker has been put anywhere yet, since it jumps to its first place without sliding
+	placed: bool,
+	/// whether the marker is sliding right now
+	animating: bool,
+	/// whether the marker slides to its new place at all, instead of jumping there
+	animated: bool,
+}//end struct SunView
+
+/// # SunMarker
+///
+/// The bright sun shown in whichever of the eight places around the board the sun is shining from.
+///
+/// When the sun moves, the marker slides clockwise around the board to its new place.
+pub struct SunMarker {
+	/// the frame the sun image is shown in
+	frame: Frame,
+	/// where it's being shown
+	view: Rc<RefCell<SunView>>,
+}//end struct SunMarker
+
+#[allow(dead_code)]
+impl SunMarker {
+	/// # new()
+	///
+	/// Creates a sun marker without an image. It's hidden until set_layout() gives it one.
+	pub fn new() -> SunMarker {
+		let view = Rc::new(RefCell::new(SunView {
+			positions: [(0,0); 8],
+			size: 100,
+			target: 0,
+			shown: 0.0,
+			placed: false,
+			animating: false,
+			animated: true,
+		}));//end struct construction
+		let mut frame = Frame::default().with_size(100, 100);
+		frame.hide();
+		SunMarker {
+			frame,
+			view,
+		}//end struct construction
+	}//end new()
+
+	/// # set_layout(&mut self, positions, size, image)
+	///
+	/// Moves the places the sun can be, such as after the window is resized, and shows the sun image at the given size.
+	pub fn set_layout(&mut self, positions:[(i32,i32); 8], size:i32, image:&PngImage) {
+		let mut image = image.clone();
+		image.scale(size, size, true, true);
+		self.frame.set_image(Some(image));
+		self.frame.show();
+		let mut view = self.view.borrow_mut();
+		view.positions = positions;
+		view.size = size;
+		let (x, y) = shown_position(&view);
+		drop(view);
+		self.frame.resize(x, y, size, size);
+	}//end set_layout(&mut self, positions, size, image)
+
+	/// # set_direction(&mut self, direction)
+	///
+	/// Moves the sun to where it shines in the given direction from, sliding it there if it was somewhere else.
+	pub fn set_direction(&mut self, direction:SunDirection) {
+		let target = sun_position_index(direction);
+		let mut view = self.view.borrow_mut();
+		if view.placed && view.target == target {
+			return;
+		}//end if the sun hasn't moved
+		view.target = target;
+		if !view.animated || !view.placed {
+			view.placed = true;
+			view.shown = target as f64;
+			let (x, y) = shown_position(&view);
+			let size = view.size;
+			drop(view);
+			self.frame.resize(x, y, size, size);
+			redraw_around(&self.frame);
+			return;
+		}//end if the sun should jump straight there
+		let start_animating = !view.animating;
+		view.animating = true;
+		drop(view);
+		if start_animating {
+			self.animate();
+		}//end if the sun wasn't already moving
+	}//end set_direction(&mut self, direction)
+
+	/// # set_animated(&mut self, animated)
+	///
+	/// Sets whether the sun slides to its new place, or jumps straight there.
+	pub fn set_animated(&mut self, animated:bool) {
+		self.view.borrow_mut().animated = animated;
+	}//end set_animated(&mut self, animated)
+
+	/// # animate(&mut self)
+	///
+	/// Slides the sun a little further each frame, the short way around the board, until it gets where it belongs.
+	fn animate(&mut self) {
+		let view = self.view.clone();
+		let mut frame = self.frame.clone();
+		app::add_timeout3(get_animation_frame_time(), move |handle| {
+			let mut view = view.borrow_mut();
+			// distance to go, wrapped so that it's the short way around
+			let distance = (view.target as f64 - view.shown + 12.0) % 8.0 - 4.0;
+			let step = 1.0 / get_animation_frames();
+			view.shown = if distance.abs() <= step {view.target as f64} else {(view.shown + step * distance.signum() + 8.0) % 8.0};
+			view.animating = view.shown != view.target as f64;
+			if view.animating {
+				app::repeat_timeout3(get_animation_frame_time(), handle);
+			}//end if the sun still needs to move
+			let (x, y) = shown_position(&view);
+			frame.resize(x, y, view.size, view.size);
+			redraw_around(&frame);
+		});
+	}//end animate(&mut self)
+}//end impl for SunMarker
+
+widget_extends!(SunMarker, Frame, frame);
+
+/// # sun_position_index(direction)
+///
+/// Returns which of the places around the board the sun sits in when it shines in the given direction, going clockwise from the southwest corner.
+/// The sun sits on the opposite side of the board from where its light points.
+fn sun_position_index(direction:SunDirection) -> usize {
+	match direction {
+		SunDirection::Northeast => 0,
+		SunDirection::East => 1,
+		SunDirection::Southeast => 2,
+		SunDirection::South => 3,
+		SunDirection::Southwest => 4,
+		SunDirection::West => 5,
+		SunDirection::Northwest => 6,
+		SunDirection::North => 7,
+	}//end matching direction
+}//end sun_position_index(direction)
+
+/// # shown_position(view)
+///
+/// Returns the top left corner the marker is drawn at, which is partway between two places while it's sliding.
+fn shown_position(view:&SunView) -> (i32, i32) {
+	let from = view.shown.floor() as usize % 8;
+	let to = (from + 1) % 8;
+	let along = view.shown - view.shown.floor();
+	let between = |a: i32, b: i32| a + ((b - a) as f64 * along).round() as i32;
+	return (between(view.positions[from].0, view.positions[to].0), between(view.positions[from].1, view.positions[to].1));
+}//end shown_position(view)
+
+/// # redraw_around(frame)
+///
+/// Redraws whatever the frame is in, so the place it just left doesn't keep a copy of it.
+fn redraw_around(frame:&Frame) {
+	match frame.parent() {
+		Some(mut parent) => parent.redraw(),
+		None => frame.clone().redraw(),
+	}//end matching whether the frame is in anything
+}//end redraw_around(frame)
@@ -1,74 +1,87 @@
 mod gui;
-use crate::gui::GUI;
+use crate::gui::{GUI, GameSetup, Preferences, SummaryChoice, run_keyboard_help, run_preferences_dialog, run_setup_wizard, run_summary_dialog};
 mod game;
-use game::{Board, Tree, TreeSize};
+mod net;
+use fltk::{app, dialog::{self, NativeFileChooser, NativeFileChooserType}, prelude::{MenuExt, ValuatorExt, WidgetExt}};
+use game::{Board, Difficulty, GameAction, GameState, GameSummary, MAX_ACTIONS_PER_TURN, Plan, Player, Replay, RuleSet, TreeSize, choose_action_at};
+use net::{ClientEvent, HostEvent, NetClient, NetHost, HOST_SEAT};
+
+/// # BoardClickAction
+///
+/// What should happen when the current player clicks on a spot on the board.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum BoardClickAction {
+	Grow,
+	/// planting a seed takes two clicks, so we hold onto the first spot clicked, if there was one
+	Seed(Option<(usize,usize)>),
+	Collect,
+	/// while setting up, clicking the board puts a moonstone there
+	Moonstone,
+}//end enum BoardClickAction
+
+/// # NetMode
+///
+/// Whether this game is being played on one computer, or hosted or joined over the network.
+enum NetMode {
+	Local,
+	Host(NetHost),
+	Client(NetClient),
+}//end enum NetMode
 
 /// # main
 /// method where program starts
 fn main() {
-	// set up program model
-	let mut game_board = Board::default();
-	// set up game_board for beginning of the game
-	game_board.initialize_board();
-
 	// set up gui
 	let mut gui = GUI::default();
-	gui.initialize();
+	let mut prefs = Preferences::load();
+	// the setup wizard should already look the way the player likes
+	prefs.apply_appearance();
+
+	// set up program model
+	let setup = match run_setup_wizard(rules_from_args(), prefs.player_count, prefs.difficulty) {
+		Some(setup) => setup,
+		None => return,
+	};//end matching whether the player started a game
+	// whether the computer plays each seat, which only matters when playing on one computer
+	let ai_seats: Vec<bool> = setup.seats.iter().map(|seat| seat.computer).collect();
+	let mut game_state = new_game(&setup);
+	let mut click_action = BoardClickAction::Grow;
+	let mut net_mode = NetMode::Local;
+	// replay being watched, along with the step being shown
+	let mut replay: Option<(Replay, usize)> = None;
+	// turn being planned, if the player is in plan mode
+	let mut plan: Option<Plan> = None;
+	// whether the results of the game that just ended have been shown yet
+	let mut results_shown = false;
+
+	gui.initialize(&prefs);
     // gui.button_grid_test();
 	gui.initialize_menu();
-	gui.initialize_board(&game_board);
-	let mut initial_trees_to_buy = Vec::new();
-	let mut initial_trees_available = Vec::new();
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Seed});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Seed});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Seed});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Seed});
-	
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Medium});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Medium});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Medium});
-	
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Large});
-	initial_trees_to_buy.push(Tree {color: (0,0,0), size: game::TreeSize::Large});
-
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Seed});
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Seed});
-
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Small});
-
-	initial_trees_available.push(Tree {color: (0,0,0), size: game::TreeSize::Medium});
-	
-	gui.initialize_tree_lists(initial_trees_to_buy, initial_trees_available);
+	gui.initialize_board(&game_state.board, &game_state.rules.shade);
+	gui.initialize_tree_lists(game_state.current());
+	gui.initialize_handoff();
+	gui.initialize_replay();
+	gui.update_from_state(&game_state, game_state.current_player);
 
 	// display gui and start program
-	gui.show();
+	gui.show(prefs.window);
+	pass_turn(&mut gui, &game_state, &ai_seats);
 	while gui.app.wait() {
 		if let Some(val) = gui.msg_receiver.recv() {
 			// we'll need to figure out what the message was
-			if val.starts_with("test") {
+			if gui.handoff_group.visible() && (is_game_action(&val) || val.starts_with("Action") || val == "Board:Cancel") {
+				// the keyboard still reaches the game under the cover, but only the next player should be able to play
+			}//end if someone tried to play before the next player took over
+			else if replay.is_some() && is_game_action(&val) {
+				dialog::alert_default("Close the replay to keep playing.");
+			}//end if user tried to play while watching a replay
+			else if val.starts_with("test") {
 				/*
 				If the prefix is test, then the msg should be formatted like "test:row,col"
 				As such, we want to break off the "test:" part, and then get the row and col number to print it.
 				*/
-				let colon_split: Vec<&str> = val.split(':').collect();
-				/*
-				At this point, colon split, should contain two elements: "test" and "row,col".
-				We don't care about the "test" because we already used it. Now we just want to look at "row,col",
-				in which row and col are numbers representing the row and column number.
-				*/
-				let colon_split_second = colon_split.get(1).unwrap().to_owned().to_owned();
-				let comma_split: Vec<&str> = colon_split_second.split(',').collect();
-				let comma_split_first: usize = comma_split.get(0).unwrap().parse().unwrap();
-				let comma_split_second: usize = comma_split.get(1).unwrap().parse().unwrap();
-				println!("Received test msg from the test button grid. Row:{}, Col:{}", comma_split_first, comma_split_second);
+				let (row, col) = parse_row_col(&val);
+				println!("Received test msg from the test button grid. Row:{}, Col:{}", row, col);
 			}//end if first four chars are "test"
 			else if val.starts_with("Buy") {
 				let tree_size_to_buy: TreeSize= match val.split(':').collect::<Vec<&str>>().get(1).unwrap() {
@@ -78,7 +91,500 @@ fn main() {
 					&"Large" => TreeSize::Large,
 					_ => panic!("Tree size not recognized"),
 				};
+				take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::Buy(tree_size_to_buy));
 			}//end if user wants to buy a tree from to-buy list
+			else if val.starts_with("Action") {
+				click_action = match val.split(':').collect::<Vec<&str>>().get(1).unwrap() {
+					&"Grow" => BoardClickAction::Grow,
+					&"Seed" => BoardClickAction::Seed(None),
+					&"Collect" => BoardClickAction::Collect,
+					_ => panic!("Board click action not recognized"),
+				};
+				// the keyboard can change this too, so the menu needs to follow along
+				gui.action_choice.set_value(match click_action {
+					BoardClickAction::Seed(_) => 1,
+					BoardClickAction::Collect => 2,
+					_ => 0,
+				});
+			}//end if user wants to change what clicking the board does
+			else if val.starts_with("uwu board") {
+				// msg should be formatted like "uwu board:row,col"
+				let (row, col) = parse_row_col(&val);
+				match click_action {
+					// until everyone has placed their starting trees, clicking the board places one, unless moonstones are being placed
+					_ if game_state.starting_trees_left > 0 && click_action != BoardClickAction::Moonstone => {
+						let placing = game_state.current_player;
+						take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::PlaceStartingTree(row, col));
+						if let (NetMode::Local, true) = (&net_mode, game_state.current_player != placing) {
+							pass_turn(&mut gui, &game_state, &ai_seats);
+						}//end if we pass to the next player at this computer
+					},
+					BoardClickAction::Grow => take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::Grow(row, col)),
+					BoardClickAction::Collect => take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::Collect(row, col)),
+					BoardClickAction::Moonstone => take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::PlaceMoonstone(row, col)),
+					BoardClickAction::Seed(None) => click_action = BoardClickAction::Seed(Some((row, col))),
+					BoardClickAction::Seed(Some(from)) => {
+						click_action = BoardClickAction::Seed(None);
+						take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::PlantSeed {from, to: (row, col)});
+					},
+				}//end matching what clicking should do
+			}//end if user clicked on the board
+			else if val == "EndTurn" && plan.is_some() {
+				dialog::alert_default("Commit or discard your plan before ending your turn.");
+			}//end if user tried to end their turn in the middle of planning
+			else if val == "EndTurn" {
+				take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::EndTurn);
+				if let BoardClickAction::Seed(_) = click_action {
+					click_action = BoardClickAction::Seed(None);
+				}//end if we need to forget the seed source
+				if let (NetMode::Local, false) = (&net_mode, game_state.game_over) {
+					pass_turn(&mut gui, &game_state, &ai_seats);
+				}//end if we pass to the next player at this computer
+			}//end if user wants to end their turn
+			else if val == "MenuChoice::Undo" || val == "MenuChoice::Redo" {
+				let action = if val == "MenuChoice::Undo" {GameAction::Undo} else {GameAction::Redo};
+				take_action(&mut gui, &mut game_state, &net_mode, &mut plan, action);
+				if let BoardClickAction::Seed(_) = click_action {
+					click_action = BoardClickAction::Seed(None);
+				}//end if we need to forget the seed source
+			}//end if user wants to undo or redo an action this turn
+			else if val == "MenuChoice::GreatElderTree" {
+				// the great elder tree goes in the middle of the board
+				let (rows, cols) = game_state.board.board.size();
+				take_action(&mut gui, &mut game_state, &net_mode, &mut plan, GameAction::PlaceGreatElderTree(rows / 2, cols / 2));
+			}//end if user wants to play with the great elder tree
+			else if val == "MenuChoice::Moonstones" {
+				if game_state.is_setting_up() {
+					click_action = BoardClickAction::Moonstone;
+					dialog::message_default("Click on the board to place moonstones, then choose another action to start playing.");
+				}//end if moonstones can still be placed
+				else {
+					dialog::alert_default("Moonstones can only be placed while setting up the game.");
+				}//end else game has already started
+			}//end if user wants to place moonstones
+			else if val == "TogglePlan" {
+				match plan.take() {
+					None => {
+						let new_plan = Plan::new(&game_state);
+						gui.show_plan(&new_plan);
+						plan = Some(new_plan);
+					},
+					Some(old_plan) => {
+						match dialog::choice2_default("What should happen to this plan?", "Commit", "Discard", "Keep Planning") {
+							Some(0) => {
+								match commit_plan(&mut game_state, &net_mode, &old_plan) {
+									Ok(()) => {
+										gui.hide_plan();
+										gui.update_from_state(&game_state, viewing_player(&game_state, &net_mode));
+									},
+									Err(msg) => {
+										dialog::alert_default(&msg);
+										plan = Some(old_plan);
+									},
+								}//end matching whether plan could be carried out
+							},
+							Some(1) => {
+								gui.hide_plan();
+								gui.update_from_state(&game_state, viewing_player(&game_state, &net_mode));
+							},
+							_ => plan = Some(old_plan),
+						}//end matching what to do with plan
+					},
+				}//end matching whether we're already planning
+				if let BoardClickAction::Seed(_) = click_action {
+					click_action = BoardClickAction::Seed(None);
+				}//end if we need to forget the seed source
+			}//end if user wants to switch between planning and placing
+			else if val == "Board:Cancel" {
+				match click_action {
+					BoardClickAction::Seed(Some(_)) => click_action = BoardClickAction::Seed(None),
+					_ => gui.board_widget.set_cursor(None),
+				}//end matching what there is to cancel
+			}//end if user wants to back out of what they were doing
+			else if val == "MenuChoice::KeyboardHelp" {
+				run_keyboard_help();
+			}//end if user wants to see the keyboard shortcuts
+			else if val.starts_with("Board:Expended") {
+				dialog::alert_default("That spot has already been used this turn.");
+			}//end if user clicked a spot that can't be used again until next turn
+			else if val == "Board:Hover" {
+				gui.update_hover_info();
+			}//end if mouse moved over the board
+			else if val == "Window:Resize" {
+				gui.apply_layout();
+				match (&replay, &plan) {
+					(Some((watching, step)), _) => {show_replay_step(&mut gui, watching, *step);},
+					(None, Some(plan)) => gui.show_plan(plan),
+					(None, None) => gui.update_from_state(&game_state, viewing_player(&game_state, &net_mode)),
+				}//end matching what's being shown
+			}//end if the window changed size
+			else if val == "Handoff:Continue" {
+				gui.hide_handoff();
+			}//end if next player is ready
+			else if val == "MenuChoice::Host" {
+				if let Some(port) = dialog::input_default("Port to host the game on:", "7878") {
+					match NetHost::start(&format!("0.0.0.0:{}", port.trim()), game_state.players.len(), app::awake) {
+						Ok(host) => {
+							dialog::message_default(&format!("Hosting on port {}. Other players can join using this computer's address.", host.local_addr().port()));
+							net_mode = NetMode::Host(host);
+							gui.hide_handoff();
+							gui.update_from_state(&game_state, HOST_SEAT);
+						},
+						Err(msg) => dialog::alert_default(&msg),
+					}//end matching whether we could start hosting
+				}//end if user gave us a port
+			}//end if user wants to host a network game
+			else if val == "MenuChoice::Join" {
+				if let Some(address) = dialog::input_default("Address of the host:", "127.0.0.1:7878") {
+					let name = dialog::input_default("Your name:", "Remote Player").unwrap_or_default();
+					match NetClient::connect(address.trim(), name.trim(), app::awake) {
+						Ok(client) => {
+							net_mode = NetMode::Client(client);
+							gui.hide_handoff();
+						},
+						Err(msg) => dialog::alert_default(&msg),
+					}//end matching whether we could connect
+				}//end if user gave us an address
+			}//end if user wants to join a network game
+			else if val == "MenuChoice::ToggleAnimations" {
+				let animated = !gui.animated;
+				gui.set_animated(animated);
+				prefs.animated = animated;
+				save_preferences(&prefs);
+			}//end if user turned animations on or off
+			else if val == "MenuChoice::Preferences" {
+				if let Some(changed) = run_preferences_dialog(&prefs) {
+					prefs = changed;
+					prefs.apply_appearance();
+					gui.set_animated(prefs.animated);
+					gui.set_board_palette(prefs.board_palette, prefs.symbols);
+					save_preferences(&prefs);
+				}//end if user saved their changes
+			}//end if user wants to change their preferences
+			else if val == "MenuChoice::SaveReplay" {
+				let mut chooser = NativeFileChooser::new(NativeFileChooserType::BrowseSaveFile);
+				chooser.set_filter("*.replay");
+				chooser.show();
+				let path = chooser.filename();
+				if !path.as_os_str().is_empty() {
+					let to_save = match &replay {
+						Some((watching, _)) => watching.clone(),
+						None => Replay::from_state(&game_state),
+					};//end matching which replay to save
+					if let Err(msg) = to_save.save(&path.to_string_lossy()) {
+						dialog::alert_default(&msg);
+					}//end if we couldn't save
+				}//end if user picked a file
+			}//end if user wants to save a replay
+			else if val == "MenuChoice::OpenReplay" {
+				let mut chooser = NativeFileChooser::new(NativeFileChooserType::BrowseFile);
+				chooser.set_filter("*.replay");
+				chooser.show();
+				let path = chooser.filename();
+				if !path.as_os_str().is_empty() {
+					match Replay::load(&path.to_string_lossy()) {
+						Ok(opened) => {
+							if show_replay_step(&mut gui, &opened, 0) {
+								replay = Some((opened, 0));
+							}//end if replay could be shown
+						},
+						Err(msg) => dialog::alert_default(&msg),
+					}//end matching whether we could load the replay
+				}//end if user picked a file
+			}//end if user wants to watch a replay from a file
+			else if val == "MenuChoice::WatchReplay" {
+				let watching = Replay::from_state(&game_state);
+				let last_step = watching.step_count();
+				if show_replay_step(&mut gui, &watching, last_step) {
+					replay = Some((watching, last_step));
+				}//end if replay could be shown
+			}//end if user wants to watch the game so far
+			else if val.starts_with("Replay:") {
+				if let Some((watching, step)) = &mut replay {
+					let new_step = match val.as_str() {
+						"Replay:Back" => step.saturating_sub(1),
+						"Replay:Forward" => (*step + 1).min(watching.step_count()),
+						"Replay:Slider" => gui.replay_slider.value().round() as usize,
+						_ => *step,
+					};//end matching which way to move through the replay
+					if val == "Replay:Close" {
+						replay = None;
+						gui.hide_replay();
+						match &plan {
+							Some(plan) => gui.show_plan(plan),
+							None => gui.update_from_state(&game_state, viewing_player(&game_state, &net_mode)),
+						}//end matching whether we were planning
+						if let NetMode::Local = net_mode {
+							pass_turn(&mut gui, &game_state, &ai_seats);
+						}//end if the player at this computer might have changed
+					}//end if user is done watching
+					else if show_replay_step(&mut gui, watching, new_step) {
+						*step = new_step;
+					}//end else if user moved through the replay
+				}//end if there's a replay being watched
+			}//end if user is controlling a replay
+
+			// outline where a seed is being planted from, if the player has picked one
+			gui.board_widget.set_selected(match click_action {
+				BoardClickAction::Seed(Some(from)) => Some(from),
+				_ => None,
+			});
 		}//end if we got a message
+
+		// let the computer play its seats, but not while someone's looking at a replay or plan
+		if let (NetMode::Local, None, None) = (&net_mode, &replay, &plan) {
+			if play_computer_turns(&mut gui, &mut game_state, &ai_seats, setup.difficulty) && !game_state.game_over {
+				pass_turn(&mut gui, &game_state, &ai_seats);
+			}//end if the computer played and a person plays next
+		}//end if the computer might be playing next
+
+		// handle anything that came in over the network, without changing the display while a replay or plan is shown
+		let viewing_other = replay.is_some() || plan.is_some();
+		match &net_mode {
+			NetMode::Local => {},
+			NetMode::Host(host) => {
+				for event in host.process(&mut game_state) {
+					match event {
+						HostEvent::Joined {seat, name} => dialog::message_default(&format!("{} joined as player {}.", name, seat + 1)),
+						HostEvent::Rejoined {seat} => dialog::message_default(&format!("{} reconnected.", seat_name(&game_state, seat))),
+						HostEvent::Left {seat} => dialog::alert_default(&format!("{} lost their connection. The game will wait for them to reconnect.", seat_name(&game_state, seat))),
+						HostEvent::Action {..} => {},
+					}//end matching event
+					if !viewing_other {gui.update_from_state(&game_state, HOST_SEAT);}
+				}//end looping over events from clients
+			},
+			NetMode::Client(client) => {
+				for event in client.process(&mut game_state) {
+					match event {
+						ClientEvent::Error(msg) => dialog::alert_default(&msg),
+						ClientEvent::Reconnecting => dialog::alert_default("Lost connection to the host. Trying to reconnect..."),
+						ClientEvent::Reconnected => dialog::message_default("Reconnected to the host."),
+						ClientEvent::Disconnected => dialog::alert_default("Lost connection to the host."),
+						ClientEvent::Welcome {..} | ClientEvent::State(_) => {},
+					}//end matching event
+					if !viewing_other {gui.update_from_state(&game_state, client.seat().unwrap_or(game_state.current_player));}
+				}//end looping over events from host
+			},
+		}//end matching network mode
+
+		// once the last turn is over, show everyone how the game turned out
+		if game_state.game_over && !results_shown && replay.is_none() {
+			results_shown = true;
+			let local = matches!(net_mode, NetMode::Local);
+			if run_summary_dialog(&GameSummary::new(&game_state), &Replay::from_state(&game_state), local, gui.board_palette, gui.symbols) == SummaryChoice::NewGame {
+				game_state = new_game(&setup);
+				results_shown = false;
+				click_action = BoardClickAction::Grow;
+				plan = None;
+				gui.update_from_state(&game_state, game_state.current_player);
+				pass_turn(&mut gui, &game_state, &ai_seats);
+			}//end if players want to play again
+		}//end if the game just ended
 	}//end application loop
+
+	// remember where the window was for next time. The window is already gone, so there's nowhere to say if this fails.
+	prefs.window = Some(gui.geometry());
+	let _ = prefs.save();
 }//end main method
+
+/// # seat_name(state, seat)
+///
+/// Returns the name of the player in the given seat, for messages about the network.
+fn seat_name(state: &GameState, seat: usize) -> String {
+	return state.players.get(seat).map_or(format!("Player {}", seat + 1), |player| player.name.clone());
+}//end seat_name(state, seat)
+
+/// # save_preferences(prefs)
+///
+/// Saves preferences to the config file after the user changes them, telling them if they can't be saved. The game still works fine without them.
+fn save_preferences(prefs: &Preferences) {
+	if let Err(msg) = prefs.save() {
+		dialog::alert_default(&format!("Your preferences will only last until the game is closed. {}", msg));
+	}//end if we couldn't save
+}//end save_preferences(prefs)
+
+/// # new_game(setup)
+///
+/// Starts a game with the players and rules chosen in the setup wizard.
+fn new_game(setup: &GameSetup) -> GameState {
+	let players = setup.seats.iter().map(|seat| Player::new(&seat.name, seat.color)).collect();
+	return GameState::with_rules(players, setup.rules.clone());
+}//end new_game(setup)
+
+/// # take_action(gui, game_state, net_mode, plan, action)
+///
+/// Applies an action for the player at this computer, either updating the gui with the result or telling the player why they can't do that.
+///
+/// When playing over the network, clients send the action to the host instead, and the result comes back later as a change in state.
+/// While planning, the action is added to the plan instead of changing the game.
+fn take_action(gui: &mut GUI, game_state: &mut GameState, net_mode: &NetMode, plan: &mut Option<Plan>, action: GameAction) {
+	if let Some(plan) = plan {
+		match plan.add(action) {
+			Ok(()) => gui.show_plan(plan),
+			Err(msg) => dialog::alert_default(&msg),
+		}//end matching whether action could be planned
+		return;
+	}//end if we're planning
+
+	let result = match net_mode {
+		NetMode::Local => game_state.apply(action),
+		NetMode::Host(host) => host.apply_local(game_state, action),
+		NetMode::Client(client) => {
+			if client.seat() == Some(game_state.current_player) {
+				client.send_action(action)
+			}//end if it's our turn
+			else {
+				Err(format!("Waiting for {} to finish their turn.", game_state.current().name))
+			}//end else it's someone else's turn
+		},
+	};//end matching network mode
+	match result {
+		Ok(()) => gui.update_from_state(game_state, viewing_player(game_state, net_mode)),
+		Err(msg) => dialog::alert_default(&msg),
+	}//end matching whether action was allowed
+}//end take_action(gui, game_state, net_mode, plan, action)
+
+/// # is_game_action(val)
+///
+/// Returns whether a GUI message would change the game, rather than only what's being shown.
+fn is_game_action(val: &str) -> bool {
+	return val.starts_with("Buy") || val.starts_with("uwu board") || val.starts_with("Board:Expended") || val == "EndTurn" || val == "MenuChoice::Undo" || val == "MenuChoice::Redo" || val == "TogglePlan" || val == "MenuChoice::GreatElderTree" || val == "MenuChoice::Moonstones";
+}//end is_game_action(val)
+
+/// # pass_turn(gui, game_state, ai_seats)
+///
+/// Covers the board until the next person at this computer is ready. This is skipped when the computer plays next, or when only one person is playing, since there's nobody to hide anything from.
+fn pass_turn(gui: &mut GUI, game_state: &GameState, ai_seats: &[bool]) {
+	let people = ai_seats.iter().filter(|computer| !**computer).count();
+	if people > 1 && !ai_seats.get(game_state.current_player).copied().unwrap_or(false) {
+		gui.show_handoff(game_state.current());
+	}//end if the next player needs to take over
+}//end pass_turn(gui, game_state, ai_seats)
+
+/// # play_computer_turns(gui, game_state, ai_seats, difficulty)
+///
+/// Lets the computer take actions until a person needs to play or the game is over, then updates the gui.
+/// The computer plays as well as the given difficulty.
+///
+/// ## return
+/// Returns whether the computer took any actions.
+fn play_computer_turns(gui: &mut GUI, game_state: &mut GameState, ai_seats: &[bool], difficulty: Difficulty) -> bool {
+	let mut played = false;
+	let mut actions_this_turn = 0;
+	while !game_state.game_over && ai_seats.get(game_state.current_player).copied().unwrap_or(false) {
+		let action = if actions_this_turn >= MAX_ACTIONS_PER_TURN {GameAction::EndTurn} else {choose_action_at(game_state, difficulty)};
+		if let Err(msg) = game_state.apply(action) {
+			// choose_action_at() only picks actions the rules allow, so this is a bug there. Release builds just end the computer's turn.
+			debug_assert!(false, "The computer couldn't {}: {}", action, msg);
+			if action == GameAction::EndTurn {
+				break;
+			}//end if the computer is stuck
+			actions_this_turn = MAX_ACTIONS_PER_TURN;
+			continue;
+		}//end if the computer picked something it can't do
+		actions_this_turn = if matches!(action, GameAction::EndTurn | GameAction::PlaceStartingTree(..)) {0} else {actions_this_turn + 1};
+		played = true;
+	}//end looping while it's the computer's turn
+	if played {
+		gui.update_from_state(game_state, game_state.current_player);
+	}//end if there's something new to show
+	return played;
+}//end play_computer_turns(gui, game_state, ai_seats, difficulty)
+
+/// # commit_plan(game_state, net_mode, plan)
+///
+/// Carries out every action in a plan. The whole plan is checked before anything is changed, so either all of it happens or none of it does.
+fn commit_plan(game_state: &mut GameState, net_mode: &NetMode, plan: &Plan) -> Result<(), String> {
+	match net_mode {
+		NetMode::Local => plan.commit(game_state)?,
+		NetMode::Host(host) => {
+			plan.check(game_state)?;
+			for action in &plan.actions {
+				host.apply_local(game_state, *action)?;
+			}//end carrying out each action
+		},
+		NetMode::Client(client) => {
+			if client.seat() != Some(game_state.current_player) {
+				return Err(format!("Waiting for {} to finish their turn.", game_state.current().name));
+			}//end if it isn't our turn
+			plan.check(game_state)?;
+			for action in &plan.actions {
+				client.send_action(*action)?;
+			}//end sending each action
+		},
+	}//end matching network mode
+	return Ok(());
+}//end commit_plan(game_state, net_mode, plan)
+
+/// # viewing_player(game_state, net_mode)
+///
+/// Returns the index of the player whose points and trees should be shown at this computer.
+fn viewing_player(game_state: &GameState, net_mode: &NetMode) -> usize {
+	match net_mode {
+		NetMode::Local => game_state.current_player,
+		NetMode::Host(_) => HOST_SEAT,
+		NetMode::Client(client) => client.seat().unwrap_or(game_state.current_player),
+	}//end matching network mode
+}//end viewing_player(game_state, net_mode)
+
+/// # rules_from_args()
+///
+/// Picks the rules to play with from the command line, so that house rules can be tried without recompiling.
+///
+/// Passing `--rules expert` uses a preset, and `--rules house.toml` reads a rules file. Without the option, or if the rules can't be read, the official rules are used.
+fn rules_from_args() -> RuleSet {
+	let args: Vec<String> = std::env::args().collect();
+	let chosen = match args.iter().position(|arg| arg == "--rules") {
+		Some(index) => args.get(index + 1),
+		None => return RuleSet::default(),
+	};//end matching whether rules were asked for
+	let result = match chosen {
+		Some(name) if RuleSet::preset_names().contains(&name.as_str()) => RuleSet::preset(name),
+		Some(path) => RuleSet::load(path),
+		None => Err(format!("--rules needs a preset ({}) or the path of a rules file.", RuleSet::preset_names().join(", "))),
+	};//end matching what was chosen
+	match result {
+		Ok(rules) => return rules,
+		Err(msg) => {
+			dialog::alert_default(&format!("{}\nPlaying with the official rules instead.", msg));
+			return RuleSet::default();
+		},
+	}//end matching whether rules could be read
+}//end rules_from_args()
+
+/// # show_replay_step(gui, replay, step)
+///
+/// Rebuilds the game as it was at the given step of a replay, and shows it.
+///
+/// ## return
+/// Returns false if the replay couldn't be rebuilt, in which case the player is told why and the display is left as it was.
+fn show_replay_step(gui: &mut GUI, replay: &Replay, step: usize) -> bool {
+	match replay.state_at(step) {
+		Ok(state) => {
+			gui.hide_handoff();
+			gui.show_replay(replay, step, &state);
+			return true;
+		},
+		Err(msg) => {
+			dialog::alert_default(&msg);
+			return false;
+		},
+	}//end matching whether we could rebuild the state
+}//end show_replay_step(gui, replay, step)
+
+/// # parse_row_col(msg)
+///
+/// Gets the row and column out of a message formatted like "prefix:row,col".
+fn parse_row_col(msg: &str) -> (usize, usize) {
+	/*
+	At this point, colon split, should contain two elements: the prefix and "row,col".
+	We don't care about the prefix because we already used it. Now we just want to look at "row,col",
+	in which row and col are numbers representing the row and column number.
+	*/
+	let colon_split: Vec<&str> = msg.split(':').collect();
+	let colon_split_second = colon_split.get(1).unwrap().to_owned().to_owned();
+	let comma_split: Vec<&str> = colon_split_second.split(',').collect();
+	let comma_split_first: usize = comma_split.get(0).unwrap().parse().unwrap();
+	let comma_split_second: usize = comma_split.get(1).unwrap().parse().unwrap();
+	return (comma_split_first, comma_split_second);
+}//end parse_row_col(msg)
@@ -0,0 +1,713 @@
+use std::{collections::hash_map::RandomState, hash::{BuildHasher, Hasher}, io::{BufRead, BufReader, Write}, net::{Shutdown, SocketAddr, TcpListener, TcpStream}, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, mpsc::{self, Receiver, Sender}, Arc, Mutex}, thread, time::Duration};
+
+use crate::game::{GameAction, GameState, encoding::{apply_state_lines, encode_state, state_diff}};
+
+/*
+Games over the network have one host, which holds the real game state, and any number of clients.
+
+Everything is sent as lines of text. Clients send "join <name>" or "rejoin <seat> <token>" when they connect,
+and then "action <action>" whenever their player tries to do something.
+The host checks each action against the game state, and if it's allowed, it sends every client a "diff" block with the lines of the state that changed.
+Blocks of state lines start with "state" or "diff" and finish with "end".
+*/
+
+/// The host always plays as the first player.
+pub const HOST_SEAT: usize = 0;
+
+/// How many times a client will try to connect again after losing its connection.
+const RECONNECT_ATTEMPTS: usize = 40;
+
+/// How long a client waits between attempts to connect again.
+const RECONNECT_DELAY: Duration = Duration::from_millis(250);
+
+/// How long the host waits for a message to go out to a client before giving up on that client, so one stalled client can't freeze the host.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// # HostEvent
+///
+/// Something that happened on the host because of a client.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum HostEvent {
+	/// A new client took the given seat.
+	Joined {seat: usize, name: String},
+	/// A client came back to a seat it had before losing its connection.
+	Rejoined {seat: usize},
+	/// The client in the given seat tried to do something.
+	Action {seat: usize, action: GameAction},
+	/// The client in the given seat lost its connection. The seat is kept for it in case it comes back.
+	Left {seat: usize},
+}//end enum HostEvent
+
+/// # ClientEvent
+///
+/// Something that happened on a client because of the host.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ClientEvent {
+	/// The host gave us a seat.
+	Welcome {seat: usize},
+	/// Lines of game state to apply on top of our copy of the game.
+	State(Vec<String>),
+	/// The host rejected something we tried to do.
+	Error(String),
+	/// We lost our connection to the host, and are trying to get it back.
+	Reconnecting,
+	/// We got our connection back.
+	Reconnected,
+	/// We lost our connection and couldn't get it back.
+	Disconnected,
+}//end enum ClientEvent
+
+/// # Seat
+///
+/// Information the host keeps for each seat that's been taken by a client.
+struct Seat {
+	/// secret the client needs to take this seat back after losing its connection
+	token: u64,
+	/// connection to the client, if it's currently connected
+	stream: Option<TcpStream>,
+	/// number identifying which connection the stream came from, so an old connection closing doesn't clear a newer one
+	connection: u64,
+}//end struct Seat
+
+/// # NetHost
+///
+/// The host side of a network game. The host listens for clients and owns the real game state.
+pub struct NetHost {
+	/// address we're listening on
+	address: SocketAddr,
+	/// every seat in the game, where the index is the index of the player in that seat
+	seats: Arc<Mutex<Vec<Option<Seat>>>>,
+	/// events coming in from client threads
+	events: Receiver<HostEvent>,
+	/// set when the host is dropped, so the thread accepting connections knows to stop
+	closed: Arc<AtomicBool>,
+}//end struct NetHost
+
+#[allow(dead_code)]
+impl NetHost {
+	/// # start(address, seat_count, waker)
+	///
+	/// Starts listening for clients on the given address. The host takes the first seat, and clients fill the rest.
+	///
+	/// ## parameters
+	/// address : address to listen on, such as "0.0.0.0:7878". Using port 0 will pick any free port.
+	/// seat_count : the number of players in the game
+	/// waker : called from background threads whenever an event comes in, so that the gui can wake up and handle it
+	pub fn start(address: &str, seat_count: usize, waker: fn()) -> Result<NetHost, String> {
+		let listener = TcpListener::bind(address).map_err(|e| format!("Couldn't listen on {}. Details:\n{}", address, e))?;
+		let local_address = listener.local_addr().map_err(|e| e.to_string())?;
+		let mut seats = Vec::new();
+		seats.resize_with(seat_count, || None);
+		let seats = Arc::new(Mutex::new(seats));
+		let closed = Arc::new(AtomicBool::new(false));
+		let (sender, events) = mpsc::channel();
+
+		// accept connections in the background
+		let thread_seats = seats.clone();
+		let thread_closed = closed.clone();
+		thread::spawn(move || {
+			let next_connection = Arc::new(AtomicU64::new(0));
+			for stream in listener.incoming() {
+				if thread_closed.load(Ordering::SeqCst) {
+					break;
+				}//end if the host has shut down
+				if let Ok(stream) = stream {
+					// the timeout carries over to every clone of the stream, including the one writes go through
+					let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+					let seats = thread_seats.clone();
+					let sender = sender.clone();
+					let connection = next_connection.fetch_add(1, Ordering::SeqCst);
+					thread::spawn(move || host_handle_client(stream, connection, seats, sender, waker));
+				}//end if connection worked
+			}//end looping over incoming connections
+		});
+
+		return Ok(NetHost {
+			address: local_address,
+			seats,
+			events,
+			closed,
+		});//end struct construction
+	}//end start(address, seat_count, waker)
+
+	/// # local_addr(&self)
+	///
+	/// Returns the address the host is listening on.
+	pub fn local_addr(&self) -> SocketAddr {
+		return self.address;
+	}//end local_addr(&self)
+
+	/// # is_connected(&self, seat)
+	///
+	/// Returns whether there's currently a client connected in the given seat.
+	pub fn is_connected(&self, seat: usize) -> bool {
+		return matches!(self.seats.lock().unwrap().get(seat), Some(Some(Seat {stream: Some(_), ..})));
+	}//end is_connected(&self, seat)
+
+	/// # process(&self, state)
+	///
+	/// Handles every event that has come in from clients since the last call, updating the game state and letting clients know what changed.
+	///
+	/// Actions are only carried out if they come from the player whose turn it is, and only if the rules allow them.
+	///
+	/// ## return
+	/// Returns the events that were handled, so the gui can update itself.
+	pub fn process(&self, state: &mut GameState) -> Vec<HostEvent> {
+		let events: Vec<HostEvent> = self.events.try_iter().collect();
+		for event in &events {
+			self.handle_event(state, event);
+		}//end handling each event
+		return events;
+	}//end process(&self, state)
+
+	/// # wait_and_process(&self, state, timeout)
+	///
+	/// Waits up to timeout for an event from a client, and then handles it along with any others that came in.
+	pub fn wait_and_process(&self, state: &mut GameState, timeout: Duration) -> Vec<HostEvent> {
+		let mut events = Vec::new();
+		if let Ok(event) = self.events.recv_timeout(timeout) {
+			self.handle_event(state, &event);
+			events.push(event);
+		}//end if we got an event
+		events.append(&mut self.process(state));
+		return events;
+	}//end wait_and_process(&self, state, timeout)
+
+	/// # handle_event(&self, state, event)
+	///
+	/// Helper method for process(), which handles a single event.
+	fn handle_event(&self, state: &mut GameState, event: &HostEvent) {
+		match event {
+			HostEvent::Joined {seat, name} => {
+				let old_state = state.clone();
+				if let Some(player) = state.players.get_mut(*seat) {
+					player.name = name.clone();
+				}//end if seat has a player
+				self.send_snapshot(*seat, state);
+				self.broadcast_diff(&old_state, state);
+			},
+			HostEvent::Rejoined {seat} => self.send_snapshot(*seat, state),
+			HostEvent::Action {seat, action} => {
+				if *seat != state.current_player {
+					self.send_error(*seat, "It isn't your turn.");
+				}//end if client is out of turn
+				else {
+					let old_state = state.clone();
+					match state.apply(*action) {
+						Ok(()) => self.broadcast_diff(&old_state, state),
+						Err(msg) => self.send_error(*seat, &msg),
+					}//end matching whether action was allowed
+				}//end else client is allowed to act
+			},
+			HostEvent::Left {..} => {},
+		}//end matching event
+	}//end handle_event(&self, state, event)
+
+	/// # apply_local(&self, state, action)
+	///
+	/// Carries out an action for the player on the host, letting every client know what changed.
+	pub fn apply_local(&self, state: &mut GameState, action: GameAction) -> Result<(), String> {
+		if state.current_player != HOST_SEAT {
+			return Err(format!("Waiting for {} to finish their turn.", state.current().name));
+		}//end if it isn't the host's turn
+		let old_state = state.clone();
+		state.apply(action)?;
+		self.broadcast_diff(&old_state, state);
+		return Ok(());
+	}//end apply_local(&self, state, action)
+
+	/// # send_snapshot(&self, seat, state)
+	///
+	/// Sends the whole game state to the client in the given seat.
+	pub fn send_snapshot(&self, seat: usize, state: &GameState) {
+		self.send_to(seat, &block_message("state", &encode_state(state)));
+	}//end send_snapshot(&self, seat, state)
+
+	/// # broadcast_diff(&self, old, new)
+	///
+	/// Sends every connected client the parts of the game state which changed between old and new.
+	pub fn broadcast_diff(&self, old: &GameState, new: &GameState) {
+		let lines = state_diff(old, new);
+		if lines.is_empty() {
+			return;
+		}//end if nothing changed
+		let message = block_message("diff", &lines);
+		let seat_count = self.seats.lock().unwrap().len();
+		for seat in 0..seat_count {
+			self.send_to(seat, &message);
+		}//end sending to each seat
+	}//end broadcast_diff(&self, old, new)
+
+	/// # send_error(&self, seat, msg)
+	///
+	/// Lets the client in the given seat know that something they tried didn't work.
+	pub fn send_error(&self, seat: usize, msg: &str) {
+		self.send_to(seat, &format!("error {}\n", msg.replace('\n', " ")));
+	}//end send_error(&self, seat, msg)
+
+	/// # drop_client(&self, seat)
+	///
+	/// Cuts off the connection to the client in the given seat. The seat is kept for them if they reconnect.
+	pub fn drop_client(&self, seat: usize) {
+		if let Some(Some(Seat {stream: Some(stream), ..})) = self.seats.lock().unwrap().get(seat) {
+			let _ = stream.shutdown(Shutdown::Both);
+		}//end if there's a client to drop
+	}//end drop_client(&self, seat)
+
+	/// # send_to(&self, seat, message)
+	///
+	/// Helper method to write a message to the client in a seat, if there is one connected.
+	///
+	/// If the message can't be sent in time, the client is dropped. Its seat is kept for it in case it comes back.
+	fn send_to(&self, seat: usize, message: &str) {
+		if let Some(Some(taken)) = self.seats.lock().unwrap().get_mut(seat) {
+			let failed = taken.stream.as_mut().is_some_and(|stream| stream.write_all(message.as_bytes()).is_err());
+			if failed {
+				// the thread reading from this client will see the connection close and report that it left
+				if let Some(stream) = taken.stream.take() {
+					let _ = stream.shutdown(Shutdown::Both);
+				}//end if there's a stream to close
+			}//end if the client couldn't keep up
+		}//end if there's a seat to send to
+	}//end send_to(&self, seat, message)
+}//end impl for NetHost
+
+impl Drop for NetHost {
+	fn drop(&mut self) {
+		self.closed.store(true, Ordering::SeqCst);
+		for seat in self.seats.lock().unwrap().iter().flatten() {
+			if let Some(stream) = &seat.stream {
+				let _ = stream.shutdown(Shutdown::Both);
+			}//end if seat is connected
+		}//end disconnecting each client
+		// connect to ourselves so the thread accepting connections wakes up and sees that we're closed
+		let _ = TcpStream::connect(self.address);
+	}//end drop(&mut self)
+}//end impl Drop for NetHost
+
+/// # host_handle_client(stream, connection, seats, sender, waker)
+///
+/// Runs in its own thread for each client connected to the host, reading messages from the client and turning them into events.
+fn host_handle_client(stream: TcpStream, connection: u64, seats: Arc<Mutex<Vec<Option<Seat>>>>, sender: Sender<HostEvent>, waker: fn()) {
+	let mut writer = match stream.try_clone() {
+		Ok(writer) => writer,
+		Err(_) => return,
+	};//end matching whether we could clone stream
+	let mut lines = BufReader::new(stream).lines();
+
+	// the first message from a client decides which seat it gets
+	let first_line = match lines.next() {
+		Some(Ok(line)) => line,
+		_ => return,
+	};//end matching first line
+	let words: Vec<&str> = first_line.split_whitespace().collect();
+	let seat = {
+		let mut seats = seats.lock().unwrap();
+		let result = match words.as_slice() {
+			["join", name @ ..] => {
+				match seats.iter().enumerate().position(|(seat, taken)| seat != HOST_SEAT && taken.is_none()) {
+					Some(seat) => {
+						let token = RandomState::new().build_hasher().finish();
+						seats[seat] = Some(Seat {token, stream: writer.try_clone().ok(), connection});
+						Ok((seat, token, HostEvent::Joined {seat, name: name.join(" ")}))
+					},
+					None => Err("The game is full.".to_string()),
+				}//end matching whether there's a free seat
+			},
+			["rejoin", seat, token] => {
+				let seat: usize = seat.parse().unwrap_or(usize::MAX);
+				let token: u64 = token.parse().unwrap_or(0);
+				match seats.get_mut(seat) {
+					Some(Some(taken)) if taken.token == token => {
+						taken.stream = writer.try_clone().ok();
+						taken.connection = connection;
+						Ok((seat, token, HostEvent::Rejoined {seat}))
+					},
+					_ => Err("Couldn't rejoin that seat.".to_string()),
+				}//end matching whether seat belongs to this client
+			},
+			_ => Err("Expected join or rejoin.".to_string()),
+		};//end matching first message
+		match result {
+			Ok((seat, token, event)) => {
+				let _ = writer.write_all(format!("welcome {} {}\n", seat, token).as_bytes());
+				let _ = sender.send(event);
+				waker();
+				seat
+			},
+			Err(msg) => {
+				let _ = writer.write_all(format!("error {}\n", msg).as_bytes());
+				return;
+			},
+		}//end matching whether client got a seat
+	};
+
+	// after that, everything from the client should be an action
+	for line in lines {
+		let line = match line {
+			Ok(line) => line,
+			Err(_) => break,
+		};//end matching whether we could read line
+		match line.strip_prefix("action ").map(|action| action.parse::<GameAction>()) {
+			Some(Ok(action)) => {
+				let _ = sender.send(HostEvent::Action {seat, action});
+				waker();
+			},
+			Some(Err(msg)) => {
+				let _ = writer.write_all(format!("error {}\n", msg).as_bytes());
+			},
+			None => {
+				let _ = writer.write_all(format!("error Couldn't understand \"{}\"\n", line).as_bytes());
+			},
+		}//end matching message
+	}//end reading each line from the client
+
+	// connection is gone, so clear out the stream, unless the client has already come back on a different connection
+	let mut seats = seats.lock().unwrap();
+	if let Some(Some(taken)) = seats.get_mut(seat) {
+		if taken.connection == connection {
+			taken.stream = None;
+			let _ = sender.send(HostEvent::Left {seat});
+			waker();
+		}//end if this is still the client's current connection
+	}//end if seat is still around
+}//end host_handle_client(stream, connection, seats, sender, waker)
+
+/// # block_message(kind, lines)
+///
+/// Wraps some state lines up into a block that a client will read as a single message.
+fn block_message(kind: &str, lines: &[String]) -> String {
+	let mut message = format!("{}\n", kind);
+	for line in lines {
+		message += line;
+		message += "\n";
+	}//end adding each line
+	message += "end\n";
+	return message;
+}//end block_message(kind, lines)
+
+/// # NetClient
+///
+/// The client side of a network game. A client keeps a copy of the game state, which is kept up to date by the host.
+pub struct NetClient {
+	/// connection to the host, which is replaced whenever we reconnect
+	stream: Arc<Mutex<Option<TcpStream>>>,
+	/// the seat the host gave us, once we have one
+	seat: Arc<Mutex<Option<usize>>>,
+	/// events coming in from the thread reading from the host
+	events: Receiver<ClientEvent>,
+	/// set when the client is dropped, so the reading thread doesn't try to reconnect
+	closed: Arc<AtomicBool>,
+}//end struct NetClient
+
+#[allow(dead_code)]
+impl NetClient {
+	/// # connect(address, name, waker)
+	///
+	/// Connects to a host and asks for a seat in the game.
+	///
+	/// ## parameters
+	/// address : address of the host, such as "192.168.1.20:7878"
+	/// name : name to use for our player
+	/// waker : called from background threads whenever an event comes in, so that the gui can wake up and handle it
+	pub fn connect(address: &str, name: &str, waker: fn()) -> Result<NetClient, String> {
+		let mut stream = TcpStream::connect(address).map_err(|e| format!("Couldn't connect to {}. Details:\n{}", address, e))?;
+		stream.write_all(format!("join {}\n", name).as_bytes()).map_err(|e| e.to_string())?;
+		let reader = stream.try_clone().map_err(|e| e.to_string())?;
+
+		let stream = Arc::new(Mutex::new(Some(stream)));
+		let seat = Arc::new(Mutex::new(None));
+		let closed = Arc::new(AtomicBool::new(false));
+		let (sender, events) = mpsc::channel();
+
+		let thread_stream = stream.clone();
+		let thread_seat = seat.clone();
+		let thread_closed = closed.clone();
+		let address = address.to_string();
+		thread::spawn(move || client_read_host(address, reader, thread_stream, thread_seat, thread_closed, sender, waker));
+
+		return Ok(NetClient {
+			stream,
+			seat,
+			events,
+			closed,
+		});//end struct construction
+	}//end connect(address, name, waker)
+
+	/// # seat(&self)
+	///
+	/// Returns the seat the host gave us, if we've been given one yet.
+	pub fn seat(&self) -> Option<usize> {
+		return *self.seat.lock().unwrap();
+	}//end seat(&self)
+
+	/// # send_action(&self, action)
+	///
+	/// Asks the host to carry out an action. If the host allows it, the result will come back as a change in state.
+	pub fn send_action(&self, action: GameAction) -> Result<(), String> {
+		match self.stream.lock().unwrap().as_mut() {
+			Some(stream) => stream.write_all(format!("action {}\n", action).as_bytes()).map_err(|e| e.to_string()),
+			None => Err("Not connected to the host.".to_string()),
+		}//end matching whether we're connected
+	}//end send_action(&self, action)
+
+	/// # process(&self, state)
+	///
+	/// Applies any state changes which have come in from the host to our copy of the game state.
+	///
+	/// ## return
+	/// Returns the events that were handled, so the gui can update itself or show errors.
+	pub fn process(&self, state: &mut GameState) -> Vec<ClientEvent> {
+		let mut events = Vec::new();
+		for event in self.events.try_iter() {
+			let error = self.handle_event(state, &event);
+			events.push(event);
+			events.extend(error);
+		}//end handling each event
+		return events;
+	}//end process(&self, state)
+
+	/// # wait_and_process(&self, state, timeout)
+	///
+	/// Waits up to timeout for an event from the host, and then handles it along with any others that came in.
+	pub fn wait_and_process(&self, state: &mut GameState, timeout: Duration) -> Vec<ClientEvent> {
+		let mut events = Vec::new();
+		if let Ok(event) = self.events.recv_timeout(timeout) {
+			let error = self.handle_event(state, &event);
+			events.push(event);
+			events.extend(error);
+		}//end if we got an event
+		events.append(&mut self.process(state));
+		return events;
+	}//end wait_and_process(&self, state, timeout)
+
+	/// # handle_event(&self, state, event)
+	///
+	/// Helper method for process(), which handles a single event.
+	///
+	/// Returns an error event if the event couldn't be handled, so it can be shown along with the rest.
+	fn handle_event(&self, state: &mut GameState, event: &ClientEvent) -> Option<ClientEvent> {
+		if let ClientEvent::State(lines) = event {
+			if let Err(msg) = apply_state_lines(state, lines) {
+				return Some(ClientEvent::Error(format!("Couldn't apply state from host. Details:\n{}", msg)));
+			}//end if state couldn't be applied
+		}//end if we got new state
+		return None;
+	}//end handle_event(&self, state, event)
+}//end impl for NetClient
+
+impl Drop for NetClient {
+	fn drop(&mut self) {
+		self.closed.store(true, Ordering::SeqCst);
+		if let Some(stream) = self.stream.lock().unwrap().as_ref() {
+			let _ = stream.shutdown(Shutdown::Both);
+		}//end if we're connected
+	}//end drop(&mut self)
+}//end impl Drop for NetClient
+
+/// # client_read_host(address, reader, stream, seat, closed, sender, waker)
+///
+/// Runs in its own thread on a client, reading messages from the host and turning them into events.
+///
+/// If the connection drops, this will keep trying to reconnect to the host and take back the same seat.
+fn client_read_host(address: String, reader: TcpStream, stream: Arc<Mutex<Option<TcpStream>>>, seat: Arc<Mutex<Option<usize>>>, closed: Arc<AtomicBool>, sender: Sender<ClientEvent>, waker: fn()) {
+	let send = |event: ClientEvent| {
+		let _ = sender.send(event);
+		waker();
+	};
+	let mut token: Option<u64> = None;
+	let mut reader = reader;
+	loop {
+		let mut lines = BufReader::new(reader).lines();
+		let mut block: Option<Vec<String>> = None;
+		while let Some(Ok(line)) = lines.next() {
+			if let Some(block_lines) = block.as_mut() {
+				if line == "end" {
+					send(ClientEvent::State(block.take().unwrap()));
+				}//end if block is finished
+				else {
+					block_lines.push(line);
+				}//end else block continues
+				continue;
+			}//end if we're in the middle of a block
+			let words: Vec<&str> = line.split_whitespace().collect();
+			match words.as_slice() {
+				["welcome", new_seat, new_token] => {
+					if let (Ok(new_seat), Ok(new_token)) = (new_seat.parse::<usize>(), new_token.parse::<u64>()) {
+						*seat.lock().unwrap() = Some(new_seat);
+						token = Some(new_token);
+						send(ClientEvent::Welcome {seat: new_seat});
+					}//end if welcome was readable
+				},
+				["state"] | ["diff"] => block = Some(Vec::new()),
+				["error", ..] => send(ClientEvent::Error(line["error ".len().min(line.len())..].to_string())),
+				_ => send(ClientEvent::Error(format!("Couldn't understand message from host: {}", line))),
+			}//end matching message from host
+		}//end reading each line
+
+		// we lost the connection, so try to get it back
+		*stream.lock().unwrap() = None;
+		if closed.load(Ordering::SeqCst) {
+			return;
+		}//end if we're shutting down on purpose
+		if token.is_none() {
+			// the host never gave us a seat, so there's nothing to come back to
+			send(ClientEvent::Disconnected);
+			return;
+		}//end if we never had a seat
+		send(ClientEvent::Reconnecting);
+		let mut reconnected = None;
+		for _ in 0..RECONNECT_ATTEMPTS {
+			thread::sleep(RECONNECT_DELAY);
+			if closed.load(Ordering::SeqCst) {
+				return;
+			}//end if we're shutting down on purpose
+			let hello = format!("rejoin {} {}\n", seat.lock().unwrap().unwrap_or_default(), token.unwrap_or_default());
+			if let Ok(mut new_stream) = TcpStream::connect(&address) {
+				if new_stream.write_all(hello.as_bytes()).is_ok() {
+					if let Ok(new_reader) = new_stream.try_clone() {
+						*stream.lock().unwrap() = Some(new_stream);
+						reconnected = Some(new_reader);
+						break;
+					}//end if we could clone the stream
+				}//end if we could say hello
+			}//end if we could connect
+		}//end trying to reconnect
+		match reconnected {
+			Some(new_reader) => {
+				reader = new_reader;
+				send(ClientEvent::Reconnected);
+			},
+			None => {
+				send(ClientEvent::Disconnected);
+				return;
+			},
+		}//end matching whether we reconnected
+	}//end looping over each connection
+}//end client_read_host(address, reader, stream, seat, closed, sender, waker)
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use super::{ClientEvent, HostEvent, NetClient, NetHost};
+	use crate::game::{GameAction, GameState, TreeSize, encoding::encode_state, tests::{players, started_game}};
+
+	fn new_game() -> GameState {
+		let mut state = started_game(players(2));
+		for player in state.players.iter_mut() {
+			player.light_points = 10;
+		}//end giving each player some light
+		state.rehash();
+		return state;
+	}//end new_game()
+
+	/// Keeps processing events on both sides until the client's copy of the game matches the host's.
+	/// The undo history of the current turn stays on the host, so only what's encoded is compared.
+	fn sync(host: &NetHost, host_state: &mut GameState, client: &NetClient, client_state: &mut GameState) -> Vec<ClientEvent> {
+		let start = Instant::now();
+		let mut client_events = Vec::new();
+		while start.elapsed() < Duration::from_secs(10) {
+			host.wait_and_process(host_state, Duration::from_millis(20));
+			client_events.append(&mut client.wait_and_process(client_state, Duration::from_millis(20)));
+			if encode_state(client_state) == encode_state(host_state) {
+				break;
+			}//end if client has caught up
+		}//end looping until client catches up or we time out
+		return client_events;
+	}//end sync(...)
+
+	#[test]
+	fn client_joins_and_gets_whole_state() {
+		let mut host_state = new_game();
+		let host = NetHost::start("127.0.0.1:0", 2, || {}).unwrap();
+		let client = NetClient::connect(&host.local_addr().to_string(), "Remote Friend", || {}).unwrap();
+		let mut client_state = GameState::new(Vec::new());
+
+		let events = sync(&host, &mut host_state, &client, &mut client_state);
+		assert!(events.contains(&ClientEvent::Welcome {seat: 1}));
+		assert_eq!(client.seat(), Some(1));
+		assert_eq!(host_state.players[1].name, "Remote Friend");
+		assert_eq!(encode_state(&client_state), encode_state(&host_state));
+	}//end client_joins_and_gets_whole_state()
+
+	#[test]
+	fn only_current_player_can_act() {
+		let mut host_state = new_game();
+		let host = NetHost::start("127.0.0.1:0", 2, || {}).unwrap();
+		let client = NetClient::connect(&host.local_addr().to_string(), "Remote Friend", || {}).unwrap();
+		let mut client_state = GameState::new(Vec::new());
+		sync(&host, &mut host_state, &client, &mut client_state);
+
+		// it's the host's turn, so the client gets told off
+		client.send_action(GameAction::Buy(TreeSize::Seed)).unwrap();
+		host.wait_and_process(&mut host_state, Duration::from_secs(5));
+		let events = client.wait_and_process(&mut client_state, Duration::from_secs(5));
+		assert!(events.iter().any(|event| matches!(event, ClientEvent::Error(_))));
+		assert_eq!(host_state.players[1].light_points, 10);
+
+		// host takes its turn, then the client's action goes through
+		host.apply_local(&mut host_state, GameAction::EndTurn).unwrap();
+		assert!(host.apply_local(&mut host_state, GameAction::EndTurn).is_err());
+		sync(&host, &mut host_state, &client, &mut client_state);
+		client.send_action(GameAction::Buy(TreeSize::Seed)).unwrap();
+		host.wait_and_process(&mut host_state, Duration::from_secs(5));
+		sync(&host, &mut host_state, &client, &mut client_state);
+		assert_eq!(host_state.players[1].light_points, 9);
+		assert_eq!(encode_state(&client_state), encode_state(&host_state));
+	}//end only_current_player_can_act()
+
+	#[test]
+	fn client_reconnects_after_drop() {
+		let mut host_state = new_game();
+		let host = NetHost::start("127.0.0.1:0", 2, || {}).unwrap();
+		let client = NetClient::connect(&host.local_addr().to_string(), "Remote Friend", || {}).unwrap();
+		let mut client_state = GameState::new(Vec::new());
+		sync(&host, &mut host_state, &client, &mut client_state);
+
+		// cut the client off, and change the game while it's gone
+		host.drop_client(1);
+		host.apply_local(&mut host_state, GameAction::Buy(TreeSize::Small)).unwrap();
+		host.apply_local(&mut host_state, GameAction::EndTurn).unwrap();
+
+		// client should come back in the same seat, and catch up on what it missed
+		let mut host_events = Vec::new();
+		let mut client_events = Vec::new();
+		let start = Instant::now();
+		while start.elapsed() < Duration::from_secs(10) && !(host.is_connected(1) && client_state == host_state) {
+			host_events.append(&mut host.wait_and_process(&mut host_state, Duration::from_millis(20)));
+			client_events.append(&mut client.wait_and_process(&mut client_state, Duration::from_millis(20)));
+		}//end looping until client is back
+		assert!(host_events.contains(&HostEvent::Rejoined {seat: 1}));
+		assert!(client_events.contains(&ClientEvent::Reconnecting));
+		assert!(client_events.contains(&ClientEvent::Reconnected));
+		assert_eq!(client.seat(), Some(1));
+		assert_eq!(encode_state(&client_state), encode_state(&host_state));
+
+		// and it can still play
+		client.send_action(GameAction::Buy(TreeSize::Seed)).unwrap();
+		host.wait_and_process(&mut host_state, Duration::from_secs(5));
+		sync(&host, &mut host_state, &client, &mut client_state);
+		assert_eq!(host_state.players[1].light_points, 9);
+		assert_eq!(encode_state(&client_state), encode_state(&host_state));
+	}//end client_reconnects_after_drop()
+
+	#[test]
+	fn extra_clients_are_turned_away() {
+		let host = NetHost::start("127.0.0.1:0", 2, || {}).unwrap();
+		let mut host_state = new_game();
+		let first = NetClient::connect(&host.local_addr().to_string(), "First", || {}).unwrap();
+		let second = NetClient::connect(&host.local_addr().to_string(), "Second", || {}).unwrap();
+		let mut state = GameState::new(Vec::new());
+		let start = Instant::now();
+		let mut second_events = Vec::new();
+		while start.elapsed() < Duration::from_secs(5) && !second_events.iter().any(|event| matches!(event, ClientEvent::Error(_))) {
+			host.wait_and_process(&mut host_state, Duration::from_millis(20));
+			first.process(&mut state);
+			second_events.append(&mut second.wait_and_process(&mut state, Duration::from_millis(20)));
+		}//end looping until second client is turned away
+		assert!(second_events.contains(&ClientEvent::Error("The game is full.".to_string())));
+		assert_eq!(first.seat(), Some(1));
+		assert_eq!(second.seat(), None);
+	}//end extra_clients_are_turned_away()
+}//end mod tests